@@ -7,20 +7,37 @@
 //! macro can emit the companion patch struct plus the `Patchable` and `Patch`
 //! trait implementations.
 
+mod args_impl;
+mod compose_impl;
+mod event_impl;
 mod from_impl;
+mod from_patch_impl;
+mod json_merge_patch_impl;
+mod json_patch_impl;
+mod masked_impl;
+mod merge3_impl;
+mod partial_impl;
 mod patch_impl;
 mod patch_struct;
+mod patch_with_context_impl;
 mod patchable_impl;
+mod path_impl;
+mod remote_impl;
+mod report_impl;
+mod snapshot_impl;
 mod utils;
 
 use std::collections::HashMap;
 
-use proc_macro2::TokenStream as TokenStream2;
-use quote::{ToTokens, quote};
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{ToTokens, format_ident, quote};
+use syn::ext::IdentExt;
+use syn::meta::ParseNestedMeta;
+use syn::spanned::Spanned;
 use syn::visit::Visit;
 use syn::{
-    Attribute, Data, DataStruct, DeriveInput, Field, Fields, GenericParam, Generics, Ident, Index,
-    Meta, PathArguments, Type,
+    Attribute, Data, DataStruct, DeriveInput, Expr, Field, Fields, GenericParam, Generics, Ident,
+    Index, LitStr, Meta, Path, PathArguments, Token, Type, Visibility, parse_quote,
 };
 
 pub const IS_SERDE_ENABLED: bool = cfg!(feature = "serde");
@@ -33,16 +50,98 @@ enum TypeUsage {
     Patchable,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The built-in merge strategies, by attribute name and type name in `patchable::merge`.
+const MERGE_STRATEGIES: [(&str, &str); 7] = [
+    ("add", "Add"),
+    ("max", "Max"),
+    ("min", "Min"),
+    ("or", "Or"),
+    ("and", "And"),
+    ("extend", "Extend"),
+    ("append", "Append"),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 enum FieldBehavior {
     Keep,
     Patch,
+    With(Path),
+    Merge(MergeStrategyAttr),
+    Delta {
+        checked: bool,
+    },
+    /// Excluded from the patch, with the value of `default = expr` and the function of
+    /// `rehydrate = path` if given.
+    Skip {
+        default: Option<Expr>,
+        rehydrate: Option<Path>,
+    },
+}
+
+impl FieldBehavior {
+    /// The name of the field parameter selecting this behavior.
+    const fn parameter_name(&self) -> &'static str {
+        match self {
+            Self::Keep | Self::Patch => "patchable",
+            Self::Skip { .. } => "skip",
+            Self::With(_) => "with",
+            Self::Merge(_) => "merge",
+            Self::Delta { .. } => "delta",
+        }
+    }
+}
+
+/// The strategy named by `#[patchable(merge = ...)]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum MergeStrategyAttr {
+    /// A built-in strategy, named with a string (e.g., `merge = "add"`).
+    Builtin(Ident),
+    /// A user-defined strategy type (e.g., `merge = my::Strategy`).
+    Custom(Path),
+}
+
+impl MergeStrategyAttr {
+    fn parse(meta: &ParseNestedMeta) -> syn::Result<Self> {
+        let value = meta.value()?;
+        if !value.peek(LitStr) {
+            return Ok(Self::Custom(value.parse()?));
+        }
+        let name: LitStr = value.parse()?;
+        MERGE_STRATEGIES
+            .iter()
+            .find(|(attr_name, _)| name.value() == *attr_name)
+            .map(|(_, type_name)| Self::Builtin(Ident::new(type_name, name.span())))
+            .ok_or_else(|| {
+                syn::Error::new_spanned(
+                    &name,
+                    "unknown merge strategy, expected one of `add`, `max`, `min`, `or`, `and`, \
+                     `extend` or `append`",
+                )
+            })
+    }
+
+    fn span(&self) -> Span {
+        match self {
+            Self::Builtin(ident) => ident.span(),
+            Self::Custom(path) => path.span(),
+        }
+    }
+
+    /// The path to the strategy type.
+    fn build_strategy_type(&self, crate_path: &TokenStream2) -> TokenStream2 {
+        match self {
+            Self::Builtin(ident) => quote! { #crate_path::merge::#ident },
+            Self::Custom(path) => quote! { #path },
+        }
+    }
 }
 
 #[derive(Debug)]
 pub(crate) struct MacroContext<'a> {
     /// The name of the struct on which the derive macro is applied.
     struct_name: &'a Ident,
+    /// The visibility of the target struct.
+    visibility: &'a Visibility,
     /// The generics definition of the target struct.
     generics: &'a Generics,
     /// The fields of the target struct.
@@ -52,11 +151,40 @@ pub(crate) struct MacroContext<'a> {
     /// The list of actions to perform for each field when generating the `patch` method and the
     /// patch struct.
     ///
-    /// This determines whether a field is copied directly (`Keep`) or recursively patched
-    /// (`Patch`).
+    /// This determines whether a field is copied directly (`Keep`), recursively patched
+    /// (`Patch`), patched through a user-provided module (`With`), or combined with its current
+    /// value (`Merge`).
     field_actions: Vec<FieldAction<'a>>,
+    /// The fields marked with `#[patchable(skip)]`.
+    skipped_fields: Vec<SkippedField<'a>>,
     /// The generated companion patch struct type (e.g., `MyStructPatch<T, ...>`).
     patch_struct_type: TokenStream2,
+    /// The foreign type patched through this definition, set with
+    /// `#[patchable(remote = "...")]`.
+    remote: Option<Path>,
+    /// The hand-written patch type, set with `#[patchable(patch = Type)]`.
+    custom_patch_type: Option<Type>,
+    /// Whether the patch type is the patch type of the only field, set with
+    /// `#[patchable(transparent)]`.
+    transparent: bool,
+    /// Whether every field of the patch struct is optional, set with `#[patchable(partial)]`.
+    partial: bool,
+    /// The context type of `PatchWithContext`, set with `#[patchable(context = Type)]`.
+    context: Option<Type>,
+    /// The function called with `&mut Self` after the fields are patched, set with
+    /// `#[patchable(after_patch = path)]`.
+    after_patch: Option<Path>,
+    /// The function checking `&Self` after the fields are patched, set with
+    /// `#[patchable(invariant = path)]`.
+    invariant: Option<Path>,
+    /// Whether a failed `try_patch` restores the previous value, set with
+    /// `#[patchable(rollback)]`.
+    rollback: bool,
+    /// Whether a field is marked with `#[patchable(delta = "checked")]` or the struct has an
+    /// invariant, in which case `#[derive(Patch)]` implements `TryPatch` instead of `Patch`.
+    fallible: bool,
+    /// Path to the `patchable` crate.
+    crate_path: TokenStream2,
     /// Fully qualified path to the `Patchable` trait.
     patchable_trait: TokenStream2,
     /// Fully qualified path to the `Patch` trait.
@@ -66,26 +194,147 @@ pub(crate) struct MacroContext<'a> {
 impl<'a> MacroContext<'a> {
     pub(crate) fn new(input: &'a DeriveInput) -> syn::Result<Self> {
         Self::validate_generics(input)?;
+        let container_options = ContainerOptions::parse(&input.attrs)?;
         let fields = Self::extract_struct_fields(input)?;
-        let (preserved_types, field_actions) = Self::collect_field_actions(fields)?;
-        let patch_struct_type =
-            Self::build_patch_struct_type(&input.ident, &input.generics, &preserved_types);
-        let crate_path = crate_path();
+        let crate_path = container_options
+            .crate_path
+            .as_ref()
+            .map_or_else(crate_path, |path| quote! { #path });
+        let (preserved_types, field_actions, skipped_fields) =
+            Self::collect_field_actions(fields, &crate_path)?;
+        let fallible = container_options.invariant.is_some()
+            || field_actions.iter().any(FieldAction::is_checked_delta);
+        Self::validate_hooks(&container_options, fallible)?;
+        Self::validate_partial(&container_options)?;
+        let custom_patch_type = container_options.patch_type;
+        let transparent = container_options.transparent.is_some();
+        let patch_struct_type = match (&custom_patch_type, &container_options.transparent) {
+            (Some(patch_type), None) => {
+                Self::validate_custom_patch_type(patch_type)?;
+                quote! { #patch_type }
+            }
+            (None, Some(span)) => Self::build_transparent_patch_type(*span, &field_actions)?,
+            (None, None) => {
+                Self::build_patch_struct_type(&input.ident, &input.generics, &preserved_types)
+            }
+            (Some(_), Some(span)) => {
+                return Err(syn::Error::new(
+                    *span,
+                    "`transparent` cannot be combined with `patch`",
+                ));
+            }
+        };
+        let remote = container_options
+            .remote
+            .map(|remote| Self::complete_remote_path(remote, &input.generics));
         let patchable_trait = quote! { #crate_path :: Patchable };
         let patch_trait = quote! { #crate_path :: Patch };
 
         Ok(Self {
             struct_name: &input.ident,
+            visibility: &input.vis,
             generics: &input.generics,
             fields,
             preserved_types,
             field_actions,
+            skipped_fields,
             patch_struct_type,
+            remote,
+            custom_patch_type,
+            transparent,
+            partial: container_options.partial.is_some(),
+            context: container_options.context,
+            after_patch: container_options.after_patch,
+            invariant: container_options.invariant,
+            rollback: container_options.rollback.is_some(),
+            fallible,
+            crate_path,
             patchable_trait,
             patch_trait,
         })
     }
 
+    /// Rejects `rollback` on an infallible patch and hooks on a remote definition, whose `Self` is
+    /// not the patched type.
+    fn validate_hooks(options: &ContainerOptions, fallible: bool) -> syn::Result<()> {
+        if let Some(span) = options.rollback
+            && !fallible
+        {
+            return Err(syn::Error::new(
+                span,
+                "`rollback` requires `invariant` or a `delta = \"checked\"` field",
+            ));
+        }
+        if options.remote.is_some() {
+            let hooks = [
+                ("after_patch", &options.after_patch),
+                ("invariant", &options.invariant),
+            ];
+            if let Some((name, Some(path))) = hooks.into_iter().find(|(_, path)| path.is_some()) {
+                return Err(syn::Error::new_spanned(
+                    path,
+                    format!("`{name}` cannot be combined with `remote`"),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Rejects `partial` with a hand-written or transparent patch type, which is not generated.
+    fn validate_partial(options: &ContainerOptions) -> syn::Result<()> {
+        let Some(span) = options.partial else {
+            return Ok(());
+        };
+        if options.patch_type.is_some() {
+            return Err(syn::Error::new(
+                span,
+                "`partial` cannot be combined with `patch`",
+            ));
+        }
+        if options.transparent.is_some() {
+            return Err(syn::Error::new(
+                span,
+                "`partial` cannot be combined with `transparent`",
+            ));
+        }
+        Ok(())
+    }
+
+    /// The foreign type patched through this definition, if any.
+    pub(crate) const fn remote(&self) -> Option<&Path> {
+        self.remote.as_ref()
+    }
+
+    /// Whether `#[derive(Patchable)]` emits a companion patch struct.
+    ///
+    /// No struct is generated for a hand-written patch type or a transparent struct.
+    pub(crate) const fn generates_patch_struct(&self) -> bool {
+        self.custom_patch_type.is_none() && !self.transparent
+    }
+
+    fn validate_custom_patch_type(patch_type: &Type) -> syn::Result<()> {
+        match patch_type {
+            Type::Path(type_path) if type_path.qself.is_none() => Ok(()),
+            _ => Err(syn::Error::new_spanned(
+                patch_type,
+                "`patch` must name a struct type",
+            )),
+        }
+    }
+
+    fn build_transparent_patch_type(
+        span: Span,
+        field_actions: &[FieldAction<'a>],
+    ) -> syn::Result<TokenStream2> {
+        match field_actions {
+            [action] => Ok(action.build_patch_field_type()),
+            _ => Err(syn::Error::new(
+                span,
+                "`transparent` requires exactly one non-skipped field",
+            )),
+        }
+    }
+
     fn validate_generics(input: &DeriveInput) -> syn::Result<()> {
         if input
             .generics
@@ -113,53 +362,104 @@ impl<'a> MacroContext<'a> {
         }
     }
 
+    #[allow(clippy::type_complexity)]
     fn collect_field_actions(
         fields: &'a Fields,
-    ) -> syn::Result<(HashMap<&'a Ident, TypeUsage>, Vec<FieldAction<'a>>)> {
+        crate_path: &TokenStream2,
+    ) -> syn::Result<(
+        HashMap<&'a Ident, TypeUsage>,
+        Vec<FieldAction<'a>>,
+        Vec<SkippedField<'a>>,
+    )> {
         let mut preserved_types = HashMap::new();
         let mut field_actions = Vec::with_capacity(fields.len());
+        let mut skipped_fields = Vec::new();
 
         for (index, field) in fields.iter().enumerate() {
-            Self::collect_field_action(index, field, &mut preserved_types, &mut field_actions)?;
+            Self::collect_field_action(
+                index,
+                field,
+                crate_path,
+                &mut preserved_types,
+                &mut field_actions,
+                &mut skipped_fields,
+            )?;
         }
 
-        Ok((preserved_types, field_actions))
+        Ok((preserved_types, field_actions, skipped_fields))
     }
 
     fn collect_field_action(
         index: usize,
         field: &'a Field,
+        crate_path: &TokenStream2,
         preserved_types: &mut HashMap<&'a Ident, TypeUsage>,
         field_actions: &mut Vec<FieldAction<'a>>,
+        skipped_fields: &mut Vec<SkippedField<'a>>,
     ) -> syn::Result<()> {
-        if let Some(field_behavior) = Self::determine_field_behavior(field)? {
-            let member = Self::field_member(field, index);
-            let field_type = &field.ty;
-            match field_behavior {
-                FieldBehavior::Patch => {
-                    let type_name = Self::extract_patchable_type_name(field_type)?;
-                    // `Patchable` usage overrides `NotPatchable` usage.
-                    preserved_types.insert(type_name, TypeUsage::Patchable);
-                    field_actions.push(FieldAction::Patch {
-                        member,
-                        ty: field_type,
-                    });
-                }
-                FieldBehavior::Keep => {
-                    Self::record_non_patchable_type_usage(field_type, preserved_types);
-                    field_actions.push(FieldAction::Keep {
-                        member,
-                        ty: field_type,
-                    });
-                }
+        let member = Self::field_member(field, index);
+        let field_type = &field.ty;
+        match Self::determine_field_behavior(field)? {
+            FieldBehavior::Patch => {
+                let type_name = Self::extract_patchable_type_name(field_type)?;
+                // `Patchable` usage overrides `NotPatchable` usage.
+                preserved_types.insert(type_name, TypeUsage::Patchable);
+                field_actions.push(FieldAction::Patch {
+                    member,
+                    ty: field_type,
+                });
             }
+            FieldBehavior::Keep => {
+                Self::record_non_patchable_type_usage(field_type, preserved_types);
+                field_actions.push(FieldAction::Keep {
+                    member,
+                    ty: field_type,
+                });
+            }
+            // The patch type comes from the module, so the field type is not recorded.
+            FieldBehavior::With(module) => field_actions.push(FieldAction::With {
+                member,
+                ty: field_type,
+                module,
+            }),
+            FieldBehavior::Merge(strategy) => {
+                // The patch carries a value of the field type, as for `Keep`.
+                Self::record_non_patchable_type_usage(field_type, preserved_types);
+                let strategy_type = strategy.build_strategy_type(crate_path);
+                let merge_fn = quote! {
+                    <#strategy_type as #crate_path::MergeStrategy<#field_type>>::merge
+                };
+                field_actions.push(FieldAction::Merge {
+                    member,
+                    ty: field_type,
+                    merge_fn,
+                });
+            }
+            FieldBehavior::Delta { checked } => {
+                Self::record_non_patchable_type_usage(field_type, preserved_types);
+                field_actions.push(FieldAction::Delta {
+                    member,
+                    ty: field_type,
+                    delta_trait: quote! { #crate_path::Delta },
+                    checked,
+                });
+            }
+            FieldBehavior::Skip { default, rehydrate } => skipped_fields.push(SkippedField {
+                member,
+                field,
+                default,
+                rehydrate,
+            }),
         }
         Ok(())
     }
 
-    fn determine_field_behavior(field: &Field) -> syn::Result<Option<FieldBehavior>> {
+    fn determine_field_behavior(field: &Field) -> syn::Result<FieldBehavior> {
         let mut saw_patchable_attr = false;
         let mut saw_skip = false;
+        let mut behavior = None;
+        let mut default = None;
+        let mut rehydrate = None;
 
         for attr in field.attrs.iter().filter(|attr| is_patchable_attr(attr)) {
             saw_patchable_attr = true;
@@ -169,6 +469,22 @@ impl<'a> MacroContext<'a> {
                     if meta.path.is_ident("skip") {
                         saw_skip = true;
                         Ok(())
+                    } else if meta.path.is_ident("with") {
+                        let module = parse_path_value(&meta)?;
+                        let span = module.span();
+                        set_behavior(&meta, &mut behavior, span, FieldBehavior::With(module))
+                    } else if meta.path.is_ident("merge") {
+                        let strategy = MergeStrategyAttr::parse(&meta)?;
+                        let span = strategy.span();
+                        set_behavior(&meta, &mut behavior, span, FieldBehavior::Merge(strategy))
+                    } else if meta.path.is_ident("delta") {
+                        let checked = parse_delta_mode(&meta)?;
+                        let span = meta.path.span();
+                        set_behavior(&meta, &mut behavior, span, FieldBehavior::Delta { checked })
+                    } else if meta.path.is_ident("default") {
+                        set_once(&meta, &mut default, meta.value()?.parse::<Expr>()?)
+                    } else if meta.path.is_ident("rehydrate") {
+                        set_once(&meta, &mut rehydrate, meta.value()?.parse::<Path>()?)
                     } else {
                         Err(meta.error("unrecognized `patchable` parameter"))
                     }
@@ -182,13 +498,34 @@ impl<'a> MacroContext<'a> {
             }
         }
 
-        Ok(if saw_skip {
-            None
-        } else if saw_patchable_attr {
-            Some(FieldBehavior::Patch)
-        } else {
-            Some(FieldBehavior::Keep)
-        })
+        if !saw_skip {
+            if let Some(default) = &default {
+                return Err(syn::Error::new_spanned(
+                    default,
+                    "`default` requires `skip`",
+                ));
+            }
+            if let Some(rehydrate) = &rehydrate {
+                return Err(syn::Error::new_spanned(
+                    rehydrate,
+                    "`rehydrate` requires `skip`",
+                ));
+            }
+        }
+
+        match behavior {
+            Some((span, behavior)) if saw_skip => Err(syn::Error::new(
+                span,
+                format!(
+                    "`{}` cannot be combined with `skip`",
+                    behavior.parameter_name()
+                ),
+            )),
+            Some((_, behavior)) => Ok(behavior),
+            None if saw_skip => Ok(FieldBehavior::Skip { default, rehydrate }),
+            None if saw_patchable_attr => Ok(FieldBehavior::Patch),
+            None => Ok(FieldBehavior::Keep),
+        }
     }
 
     fn field_member(field: &'a Field, index: usize) -> FieldMember<'a> {
@@ -220,6 +557,18 @@ impl<'a> MacroContext<'a> {
         }
     }
 
+    /// Applies the generics of the definition struct to a remote path written without any.
+    fn complete_remote_path(mut remote: Path, generics: &Generics) -> Path {
+        if let Some(last_segment) = remote.segments.last_mut()
+            && matches!(last_segment.arguments, PathArguments::None)
+            && generics.type_params().next().is_some()
+        {
+            let (_, type_generics, _) = generics.split_for_impl();
+            last_segment.arguments = PathArguments::AngleBracketed(parse_quote! { #type_generics });
+        }
+        remote
+    }
+
     fn build_patch_struct_type(
         struct_name: &Ident,
         generics: &Generics,
@@ -235,6 +584,82 @@ impl<'a> MacroContext<'a> {
     }
 }
 
+/// Options collected from container-level `#[patchable(...)]` attributes.
+#[derive(Debug, Default)]
+struct ContainerOptions {
+    /// Custom path to the `patchable` crate, set with `#[patchable(crate = "...")]`.
+    crate_path: Option<Path>,
+    /// Foreign type to patch, set with `#[patchable(remote = "...")]`.
+    remote: Option<Path>,
+    /// Hand-written patch type, set with `#[patchable(patch = Type)]`.
+    patch_type: Option<Type>,
+    /// Location of a `#[patchable(transparent)]` flag.
+    transparent: Option<Span>,
+    /// Location of a `#[patchable(partial)]` flag.
+    partial: Option<Span>,
+    /// Context type of `PatchWithContext`, set with `#[patchable(context = Type)]`.
+    context: Option<Type>,
+    /// Post-patch hook, set with `#[patchable(after_patch = path)]`.
+    after_patch: Option<Path>,
+    /// Invariant check, set with `#[patchable(invariant = path)]`.
+    invariant: Option<Path>,
+    /// Location of a `#[patchable(rollback)]` flag.
+    rollback: Option<Span>,
+}
+
+impl ContainerOptions {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut options = Self::default();
+
+        for attr in attrs.iter().filter(|attr| is_patchable_attr(attr)) {
+            match &attr.meta {
+                Meta::List(_) => attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("crate") {
+                        set_once(&meta, &mut options.crate_path, parse_path_value(&meta)?)
+                    } else if meta.path.is_ident("remote") {
+                        set_once(&meta, &mut options.remote, parse_path_value(&meta)?)
+                    } else if meta.path.is_ident("patch") {
+                        set_once(&meta, &mut options.patch_type, meta.value()?.parse()?)
+                    } else if meta.path.is_ident("transparent") {
+                        set_once(&meta, &mut options.transparent, meta.path.span())
+                    } else if meta.path.is_ident("partial") {
+                        set_once(&meta, &mut options.partial, meta.path.span())
+                    } else if meta.path.is_ident("context") {
+                        set_once(&meta, &mut options.context, meta.value()?.parse()?)
+                    } else if meta.path.is_ident("after_patch") {
+                        set_once(&meta, &mut options.after_patch, meta.value()?.parse()?)
+                    } else if meta.path.is_ident("invariant") {
+                        set_once(&meta, &mut options.invariant, meta.value()?.parse()?)
+                    } else if meta.path.is_ident("rollback") {
+                        set_once(&meta, &mut options.rollback, meta.path.span())
+                    } else {
+                        Err(meta.error("unrecognized `patchable` parameter"))
+                    }
+                })?,
+                Meta::Path(_) | Meta::NameValue(_) => {
+                    return Err(syn::Error::new_spanned(
+                        attr,
+                        "unrecognized `patchable` parameter",
+                    ));
+                }
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+/// A field marked with `#[patchable(skip)]`.
+#[derive(Debug)]
+struct SkippedField<'a> {
+    member: FieldMember<'a>,
+    field: &'a Field,
+    /// The value given with `#[patchable(skip, default = expr)]`.
+    default: Option<Expr>,
+    /// The function given with `#[patchable(skip, rehydrate = path)]`.
+    rehydrate: Option<Path>,
+}
+
 #[derive(Debug)]
 enum FieldMember<'a> {
     Named(&'a Ident),
@@ -251,6 +676,52 @@ impl<'a> FieldMember<'a> {
             }
         }
     }
+
+    /// The name of the field, or its index for tuple structs.
+    fn name(&self) -> String {
+        match self {
+            FieldMember::Named(name) => name.unraw().to_string(),
+            FieldMember::Unnamed(index) => index.index.to_string(),
+        }
+    }
+
+    /// The variant of this field in the generated field and event enums: its name in
+    /// `UpperCamelCase`, or `Field{index}` for tuple structs.
+    fn variant(&self) -> Ident {
+        match self {
+            FieldMember::Named(name) => {
+                let camel_case: String = name
+                    .unraw()
+                    .to_string()
+                    .split('_')
+                    .filter(|word| !word.is_empty())
+                    .flat_map(|word| {
+                        let mut chars = word.chars();
+                        let first = chars.next().map(|first| first.to_ascii_uppercase());
+                        first.into_iter().chain(chars)
+                    })
+                    .collect();
+                format_ident!("{}", camel_case, span = name.span())
+            }
+            FieldMember::Unnamed(index) => {
+                format_ident!("Field{}", index.index, span = index.span)
+            }
+        }
+    }
+
+    /// The local variable name this field is bound to when a value is destructured.
+    ///
+    /// The binding keeps the span of the field, so type errors point at the field.
+    fn binding(&self, prefix: &str) -> Ident {
+        match self {
+            FieldMember::Named(name) => {
+                format_ident!("__{}_{}", prefix, name, span = name.span())
+            }
+            FieldMember::Unnamed(index) => {
+                format_ident!("__{}_{}", prefix, index.index, span = index.span)
+            }
+        }
+    }
 }
 
 impl<'a> ToTokens for FieldMember<'a> {
@@ -272,61 +743,189 @@ enum FieldAction<'a> {
         member: FieldMember<'a>,
         ty: &'a Type,
     },
+    /// Patched through the functions of a `#[patchable(with = "...")]` module.
+    With {
+        member: FieldMember<'a>,
+        ty: &'a Type,
+        module: Path,
+    },
+    /// Combined with the current value through a `#[patchable(merge = ...)]` strategy.
+    Merge {
+        member: FieldMember<'a>,
+        ty: &'a Type,
+        /// The fully qualified `MergeStrategy::merge` function of the strategy.
+        merge_fn: TokenStream2,
+    },
+    /// Carries a difference that is added to the current value, set with
+    /// `#[patchable(delta)]`.
+    Delta {
+        member: FieldMember<'a>,
+        ty: &'a Type,
+        /// Fully qualified path to the `Delta` trait.
+        delta_trait: TokenStream2,
+        /// Whether the delta is applied with overflow checks (`delta = "checked"`).
+        checked: bool,
+    },
 }
 
 impl<'a> FieldAction<'a> {
-    fn build_field(&self) -> TokenStream2 {
+    /// Builds the declaration of this field in the patch struct, wrapped in an `Option` in a
+    /// partial patch, after the given attributes.
+    fn build_field(&self, partial: bool, attrs: Option<TokenStream2>) -> TokenStream2 {
         let member = self.member();
-        let ty = self.ty();
-        let field_ty = if self.is_patch() {
-            quote! { #ty::Patch }
+        let field_ty = self.build_patch_field_type();
+        let field_ty = if partial {
+            quote! { ::core::option::Option<#field_ty> }
         } else {
-            quote! { #ty }
+            field_ty
         };
         match member {
-            FieldMember::Named(name) => quote! { #name : #field_ty },
-            FieldMember::Unnamed(_) => quote! { #field_ty },
+            FieldMember::Named(name) => quote! { #attrs #name : #field_ty },
+            FieldMember::Unnamed(_) => quote! { #attrs #field_ty },
+        }
+    }
+
+    /// The type of this field in the patch.
+    fn build_patch_field_type(&self) -> TokenStream2 {
+        let ty = self.ty();
+        match self {
+            FieldAction::Keep { .. } | FieldAction::Merge { .. } => quote! { #ty },
+            FieldAction::Patch { .. } => quote! { #ty::Patch },
+            FieldAction::With { module, .. } => quote! { #module::Patch },
+            FieldAction::Delta { delta_trait, .. } => quote! { <#ty as #delta_trait>::Delta },
         }
     }
 
     fn build_update_statement(
         &self,
         patch_trait: &TokenStream2,
-        patch_index: usize,
+        target: &TokenStream2,
+        value: &TokenStream2,
     ) -> TokenStream2 {
-        let member = self.member();
-        let patch_member = member.patch_member(patch_index);
         match self {
             FieldAction::Keep { .. } => {
-                quote! { self.#member = patch.#patch_member; }
+                quote! { #target = #value; }
             }
             FieldAction::Patch { .. } => {
-                quote! { #patch_trait::patch(&mut self.#member, patch.#patch_member); }
+                quote! { #patch_trait::patch(&mut #target, #value); }
+            }
+            FieldAction::With { module, .. } => {
+                quote! { #module::patch(&mut #target, #value); }
+            }
+            FieldAction::Merge { merge_fn, .. } => {
+                quote! { #merge_fn(&mut #target, #value); }
+            }
+            FieldAction::Delta { delta_trait, .. } => {
+                quote! { #delta_trait::apply_delta(&mut #target, #value); }
             }
         }
     }
 
     const fn member(&self) -> &FieldMember<'a> {
         match self {
-            FieldAction::Keep { member, .. } | FieldAction::Patch { member, .. } => member,
+            FieldAction::Keep { member, .. }
+            | FieldAction::Patch { member, .. }
+            | FieldAction::With { member, .. }
+            | FieldAction::Merge { member, .. }
+            | FieldAction::Delta { member, .. } => member,
         }
     }
 
     const fn ty(&self) -> &'a Type {
         match self {
-            FieldAction::Keep { ty, .. } | FieldAction::Patch { ty, .. } => ty,
+            FieldAction::Keep { ty, .. }
+            | FieldAction::Patch { ty, .. }
+            | FieldAction::With { ty, .. }
+            | FieldAction::Merge { ty, .. }
+            | FieldAction::Delta { ty, .. } => ty,
         }
     }
 
-    const fn is_patch(&self) -> bool {
-        matches!(self, FieldAction::Patch { .. })
+    /// Builds the patch value holding the current state of the field found at `place`.
+    ///
+    /// A delta is the difference from the default, and a merged value is merged into the target
+    /// rather than assigned, so the value only rebuilds such fields from their default, and for
+    /// `max`, `min` and `and` only when merging into the default keeps it.
+    fn build_snapshot_expr(
+        &self,
+        snapshot_trait: &TokenStream2,
+        place: &TokenStream2,
+    ) -> TokenStream2 {
+        match self {
+            FieldAction::Keep { .. } | FieldAction::Merge { .. } => {
+                quote! { ::core::clone::Clone::clone(&#place) }
+            }
+            FieldAction::Patch { .. } => quote! { #snapshot_trait::snapshot(&#place) },
+            FieldAction::With { module, .. } => {
+                quote! { #module::from(::core::clone::Clone::clone(&#place)) }
+            }
+            FieldAction::Delta { delta_trait, .. } => quote! {
+                #delta_trait::delta(&::core::default::Default::default(), &#place)
+            },
+        }
     }
 
-    fn build_initializer_expr(&self) -> TokenStream2 {
-        let member = self.member();
+    /// Builds the value of this field in the composition of the patches `first` and `later`.
+    ///
+    /// Checked deltas are summed like unchecked ones, so an overflow between the two patches is
+    /// not detected.
+    ///
+    /// Not available for `#[patchable(with = "...")]` fields.
+    fn build_compose_expr(
+        &self,
+        compose_trait: &TokenStream2,
+        first: &TokenStream2,
+        later: &TokenStream2,
+    ) -> TokenStream2 {
         match self {
-            FieldAction::Keep { .. } => quote! { value.#member },
-            FieldAction::Patch { .. } => quote! { ::core::convert::From::from(value.#member) },
+            FieldAction::Keep { .. } => quote! { #later },
+            FieldAction::Patch { .. } => quote! { #compose_trait::compose(#first, #later) },
+            FieldAction::Merge { merge_fn, .. } => quote! {{
+                let mut composed = #first;
+                #merge_fn(&mut composed, #later);
+                composed
+            }},
+            FieldAction::Delta {
+                ty, delta_trait, ..
+            } => quote! {
+                <#ty as #delta_trait>::compose_delta(#first, #later)
+            },
+            FieldAction::With { .. } => unreachable!("patches of `with` fields are not composed"),
+        }
+    }
+
+    /// Builds the overflow-checked new value of a `#[patchable(delta = "checked")]` field,
+    /// returning `PatchError::Overflow` from the enclosing function on overflow.
+    fn build_checked_delta_expr(
+        &self,
+        patch_error: &TokenStream2,
+        target: &TokenStream2,
+        value: &TokenStream2,
+    ) -> TokenStream2 {
+        let FieldAction::Delta { delta_trait, .. } = self else {
+            unreachable!("only delta fields are checked");
+        };
+        let field_name = self.member().name();
+        quote! {
+            #delta_trait::checked_apply_delta(&#target, #value)
+                .ok_or(#patch_error::Overflow { field: #field_name })?
+        }
+    }
+
+    /// Whether this field carries a `#[patchable(delta = "checked")]` delta.
+    const fn is_checked_delta(&self) -> bool {
+        matches!(self, FieldAction::Delta { checked: true, .. })
+    }
+
+    fn build_initializer_expr(&self, value: &TokenStream2) -> TokenStream2 {
+        match self {
+            FieldAction::Keep { .. } | FieldAction::Merge { .. } => quote! { #value },
+            FieldAction::Patch { .. } => quote! { ::core::convert::From::from(#value) },
+            FieldAction::With { module, .. } => quote! { #module::from(#value) },
+            // The state is expressed as the difference from the default value.
+            FieldAction::Delta { delta_trait, .. } => quote! {
+                #delta_trait::delta(&::core::default::Default::default(), &#value)
+            },
         }
     }
 }
@@ -336,6 +935,67 @@ pub(super) fn crate_path() -> TokenStream2 {
     quote! { ::patchable }
 }
 
+/// Parses the string value of a `name = "..."` parameter into a path.
+fn parse_path_value(meta: &ParseNestedMeta) -> syn::Result<Path> {
+    let path: LitStr = meta.value()?.parse()?;
+    path.parse()
+}
+
+/// Parses `delta` (wrapping) or `delta = "checked"`, returning whether the delta is checked.
+fn parse_delta_mode(meta: &ParseNestedMeta) -> syn::Result<bool> {
+    if !meta.input.peek(Token![=]) {
+        return Ok(false);
+    }
+    let mode: LitStr = meta.value()?.parse()?;
+    if mode.value() == "checked" {
+        Ok(true)
+    } else {
+        Err(syn::Error::new_spanned(
+            mode,
+            "unknown delta mode, expected `delta` or `delta = \"checked\"`",
+        ))
+    }
+}
+
+/// Records the behavior selected by a field parameter, rejecting a second one.
+fn set_behavior(
+    meta: &ParseNestedMeta,
+    slot: &mut Option<(Span, FieldBehavior)>,
+    span: Span,
+    behavior: FieldBehavior,
+) -> syn::Result<()> {
+    let name = behavior.parameter_name();
+    match slot {
+        Some((_, previous)) if previous.parameter_name() == name => {
+            Err(meta.error("duplicate `patchable` parameter"))
+        }
+        Some((_, previous)) => Err(syn::Error::new(
+            span,
+            format!(
+                "`{name}` cannot be combined with `{}`",
+                previous.parameter_name()
+            ),
+        )),
+        None => {
+            *slot = Some((span, behavior));
+            Ok(())
+        }
+    }
+}
+
+/// Stores `value` in `slot`, rejecting a parameter that was already given.
+pub(super) fn set_once<T>(
+    meta: &ParseNestedMeta,
+    slot: &mut Option<T>,
+    value: T,
+) -> syn::Result<()> {
+    if slot.is_some() {
+        return Err(meta.error("duplicate `patchable` parameter"));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
 #[inline]
 fn is_patchable_attr(attr: &Attribute) -> bool {
     attr.path().is_ident(PATCHABLE)
@@ -350,6 +1010,8 @@ pub fn has_patchable_skip_attr(field: &Field) -> bool {
         let _ = attr.parse_nested_meta(|meta| {
             if meta.path.is_ident("skip") {
                 has_skip = true;
+            } else if meta.input.peek(Token![=]) {
+                meta.value()?.parse::<Expr>()?;
             }
             Ok(())
         });
@@ -0,0 +1,381 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{Fields, GenericArgument, PathArguments, Type, WherePredicate, parse_quote};
+
+use crate::context::{FieldAction, MacroContext, collect_used_simple_types};
+
+/// How the flag of a plain field sets its patch value.
+enum ArgValue<'t> {
+    /// A single value.
+    One(&'t Type),
+    /// A single value, wrapped in `Some` for an `Option` field.
+    Some(&'t Type),
+    /// Every value given, for a `Vec` field.
+    Many(&'t Type),
+    /// Every value given, wrapped in `Some` for an `Option<Vec<T>>` field.
+    SomeMany(&'t Type),
+}
+
+impl<'t> ArgValue<'t> {
+    fn of(ty: &'t Type) -> Self {
+        if let Some(inner) = single_type_argument(ty, "Option") {
+            match single_type_argument(inner, "Vec") {
+                Some(item) => ArgValue::SomeMany(item),
+                None => ArgValue::Some(inner),
+            }
+        } else if let Some(inner) = single_type_argument(ty, "Vec") {
+            ArgValue::Many(inner)
+        } else {
+            ArgValue::One(ty)
+        }
+    }
+
+    /// The type of the values parsed from the command line.
+    fn value_type(&self) -> &'t Type {
+        match self {
+            ArgValue::One(ty)
+            | ArgValue::Some(ty)
+            | ArgValue::Many(ty)
+            | ArgValue::SomeMany(ty) => ty,
+        }
+    }
+
+    /// Whether the flag takes a value per occurrence.
+    fn is_many(&self) -> bool {
+        matches!(self, ArgValue::Many(_) | ArgValue::SomeMany(_))
+    }
+
+    /// Whether the flag is a switch, setting `true` when given without a value.
+    fn is_switch(&self) -> bool {
+        match self {
+            ArgValue::One(ty) | ArgValue::Some(ty) => {
+                matches!(ty, Type::Path(type_path) if type_path.qself.is_none()
+                    && type_path.path.is_ident("bool"))
+            }
+            ArgValue::Many(_) | ArgValue::SomeMany(_) => false,
+        }
+    }
+}
+
+/// Returns the type argument of `ty` if it is spelled `name<T>`, such as `Option<T>`.
+fn single_type_argument<'t>(ty: &'t Type, name: &str) -> Option<&'t Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    if type_path.qself.is_some() || segment.ident != name {
+        return None;
+    }
+    let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None;
+    };
+    match arguments.args.first() {
+        Some(GenericArgument::Type(inner)) if arguments.args.len() == 1 => Some(inner),
+        _ => None,
+    }
+}
+
+impl<'a> MacroContext<'a> {
+    // ============================================================
+    // pub struct OriginalStructArgs<T, ...> { ... }
+    // impl<T, ...> PatchArgs for OriginalStruct<T, ...>
+    // ============================================================
+
+    /// Builds the `<StructName>Args` struct with its `clap` implementations, and the `PatchArgs`
+    /// implementation of the struct.
+    ///
+    /// Flags are added and read at runtime, since their names depend on the prefix of the
+    /// enclosing fields. Values are parsed with `clap::value_parser!`, or with `FromStr` for
+    /// types using a type parameter, which the macro cannot inspect.
+    pub(crate) fn build_patch_args_impls(&self) -> syn::Result<TokenStream2> {
+        self.validate_patch_args()?;
+
+        let crate_path = &self.crate_path;
+        let private = quote! { #crate_path::cli::__private };
+        let clap = quote! { #private::clap };
+        let patch_args_trait = quote! { #crate_path::cli::PatchArgs };
+        let patchable_trait = &self.patchable_trait;
+        let field_path = quote! { #crate_path::partial::FieldPath };
+        let visibility = self.visibility;
+        let (impl_generics, type_generics, _) = self.generics.split_for_impl();
+        let input_struct_name = self.struct_name;
+        let input_struct_type = quote! { #input_struct_name #type_generics };
+        let args_struct_ident = format_ident!("{}Args", input_struct_name);
+        let args_struct_type = quote! { #args_struct_ident #type_generics };
+        let patch_type = quote! { <#input_struct_type as #patchable_trait>::Patch };
+        let doc = format!(
+            "Command-line flags setting the fields of [`{input_struct_name}`], generated by \
+             `PatchArgs`."
+        );
+
+        let struct_where_clause = self.extend_where_clause(&[parse_quote! {
+            #input_struct_type: #patchable_trait
+        }]);
+        let args_where_clause = self.extend_where_clause(&[parse_quote! {
+            #input_struct_type: #patch_args_trait
+        }]);
+        let trait_where_clause = self.extend_where_clause(&self.build_patch_args_trait_bounds());
+
+        let (augments, updates): (Vec<_>, Vec<_>) = self
+            .field_actions
+            .iter()
+            .enumerate()
+            .map(|(patch_index, action)| {
+                let name = action.member().name();
+                let patch_member = action.member().patch_member(patch_index);
+                let path = quote! { &#field_path::new(#name, prefix) };
+                if let FieldAction::Patch { ty, .. } = action {
+                    return (
+                        quote! {
+                            let command = <#ty as #patch_args_trait>::augment_patch_args(
+                                command,
+                                ::core::option::Option::Some(#path),
+                            );
+                        },
+                        quote! {
+                            let mut nested = patch.#patch_member.take().unwrap_or_default();
+                            <#ty as #patch_args_trait>::update_patch_from_arg_matches(
+                                &mut nested,
+                                matches,
+                                ::core::option::Option::Some(#path),
+                            );
+                            patch.#patch_member = #private::nested(nested);
+                        },
+                    );
+                }
+
+                let field_ty = action.build_patch_field_type();
+                let field_ty: Type = parse_quote! { #field_ty };
+                let arg_value = ArgValue::of(&field_ty);
+                let value_ty = arg_value.value_type();
+                let parser = if self.uses_type_param(value_ty) {
+                    quote! { #private::parse::<#value_ty> }
+                } else {
+                    quote! { #clap::value_parser!(#value_ty) }
+                };
+                let many = arg_value.is_many();
+                let switch = arg_value.is_switch();
+                // The help describes what the patch value does to the field.
+                let verb = match action {
+                    FieldAction::Merge { .. } => "Merges the value into",
+                    FieldAction::Delta { .. } => "Adds the value to",
+                    FieldAction::With { .. } => "Patches",
+                    FieldAction::Keep { .. } | FieldAction::Patch { .. } => "Sets",
+                };
+                let value = match arg_value {
+                    ArgValue::One(_) => quote! { #private::one::<#value_ty>(matches, #path) },
+                    ArgValue::Some(_) => quote! {
+                        #private::one::<#value_ty>(matches, #path)
+                            .map(::core::option::Option::Some)
+                    },
+                    ArgValue::Many(_) => quote! { #private::many::<#value_ty>(matches, #path) },
+                    ArgValue::SomeMany(_) => quote! {
+                        #private::many::<#value_ty>(matches, #path)
+                            .map(::core::option::Option::Some)
+                    },
+                };
+                (
+                    quote! {
+                        let command = command.arg(
+                            #private::arg(#path, #many, #switch, #verb).value_parser(#parser),
+                        );
+                    },
+                    quote! {
+                        if let ::core::option::Option::Some(value) = #value {
+                            patch.#patch_member = ::core::option::Option::Some(value);
+                        }
+                    },
+                )
+            })
+            .unzip();
+        let flags = self.field_actions.iter().map(|action| {
+            let name = action.member().name().replace('_', "-");
+            let nested = match action {
+                FieldAction::Patch { ty, .. } => quote! {
+                    ::core::option::Option::Some(<#ty as #patch_args_trait>::PATCH_FLAGS)
+                },
+                _ => quote! { ::core::option::Option::None },
+            };
+            quote! { #private::Flag { name: #name, nested: #nested } }
+        });
+        let flag_checks = self.build_flag_checks(&private);
+        let (prefix_param_name, patch_param_name, matches_param_name) =
+            if self.field_actions.is_empty() {
+                (quote! { _prefix }, quote! { _patch }, quote! { _matches })
+            } else {
+                (quote! { prefix }, quote! { patch }, quote! { matches })
+            };
+
+        Ok(quote! {
+            #[doc = #doc]
+            #visibility struct #args_struct_ident #impl_generics #struct_where_clause {
+                patch: #patch_type,
+            }
+
+            const _: () = {
+                #[automatically_derived]
+                impl #impl_generics #patch_args_trait for #input_struct_type #trait_where_clause {
+                    const PATCH_FLAGS: &'static [#private::Flag] = &[#(#flags),*];
+
+                    fn augment_patch_args(
+                        command: #clap::Command,
+                        #prefix_param_name: ::core::option::Option<&#field_path<'_>>,
+                    ) -> #clap::Command {
+                        #(#flag_checks)*
+                        #(#augments)*
+                        command
+                    }
+
+                    fn update_patch_from_arg_matches(
+                        #patch_param_name: &mut Self::Patch,
+                        #matches_param_name: &#clap::ArgMatches,
+                        #prefix_param_name: ::core::option::Option<&#field_path<'_>>,
+                    ) {
+                        #(#updates)*
+                    }
+                }
+
+                #[automatically_derived]
+                impl #impl_generics #args_struct_type #struct_where_clause {
+                    /// Returns the patch setting the fields whose flag was given.
+                    #[inline(always)]
+                    #visibility fn into_patch(self) -> #patch_type {
+                        self.patch
+                    }
+                }
+
+                #[automatically_derived]
+                impl #impl_generics #clap::FromArgMatches for #args_struct_type
+                #args_where_clause {
+                    fn from_arg_matches(
+                        matches: &#clap::ArgMatches,
+                    ) -> ::core::result::Result<Self, #clap::Error> {
+                        let mut patch = ::core::default::Default::default();
+                        <#input_struct_type as #patch_args_trait>::update_patch_from_arg_matches(
+                            &mut patch,
+                            matches,
+                            ::core::option::Option::None,
+                        );
+                        ::core::result::Result::Ok(Self { patch })
+                    }
+
+                    fn update_from_arg_matches(
+                        &mut self,
+                        matches: &#clap::ArgMatches,
+                    ) -> ::core::result::Result<(), #clap::Error> {
+                        <#input_struct_type as #patch_args_trait>::update_patch_from_arg_matches(
+                            &mut self.patch,
+                            matches,
+                            ::core::option::Option::None,
+                        );
+                        ::core::result::Result::Ok(())
+                    }
+                }
+
+                #[automatically_derived]
+                impl #impl_generics #clap::Args for #args_struct_type #args_where_clause {
+                    fn augment_args(command: #clap::Command) -> #clap::Command {
+                        <#input_struct_type as #patch_args_trait>::augment_patch_args(
+                            command,
+                            ::core::option::Option::None,
+                        )
+                    }
+
+                    fn augment_args_for_update(command: #clap::Command) -> #clap::Command {
+                        <Self as #clap::Args>::augment_args(command)
+                    }
+                }
+            };
+        })
+    }
+
+    /// Checks at compile time that no two fields get flags with the same name, which can only
+    /// happen through a nested field, such as `limits_timeout` next to a nested `limits` with a
+    /// `timeout` field. The flags of nested fields depend on their type, so the check runs once the
+    /// implementation is used.
+    fn build_flag_checks(&self, private: &TokenStream2) -> Vec<TokenStream2> {
+        let mut checks = Vec::new();
+        for (second, second_action) in self.field_actions.iter().enumerate() {
+            for (first, first_action) in self.field_actions[..second].iter().enumerate() {
+                if !matches!(first_action, FieldAction::Patch { .. })
+                    && !matches!(second_action, FieldAction::Patch { .. })
+                {
+                    continue;
+                }
+                let message = format!(
+                    "`PatchArgs` gives a flag of `{}` and a flag of `{}` the same name",
+                    first_action.member().name(),
+                    second_action.member().name(),
+                );
+                checks.push(quote_spanned! {second_action.ty().span()=>
+                    const {
+                        ::core::assert!(
+                            !#private::clashes(&Self::PATCH_FLAGS[#first], &Self::PATCH_FLAGS[#second]),
+                            #message,
+                        );
+                    }
+                });
+            }
+        }
+        checks
+    }
+
+    fn validate_patch_args(&self) -> syn::Result<()> {
+        self.reject_remote("PatchArgs")?;
+        if !self.partial {
+            return Err(syn::Error::new_spanned(
+                self.struct_name,
+                "`PatchArgs` can only be derived for a `partial` struct",
+            ));
+        }
+        if let Fields::Unnamed(_) = self.fields {
+            return Err(syn::Error::new_spanned(
+                self.struct_name,
+                "`PatchArgs` cannot be derived for a tuple struct, whose fields have no name to \
+                 give their flags",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Requires `PatchArgs` for nested fields, and parsing with `FromStr` for the values of
+    /// other fields whose type uses a type parameter.
+    fn build_patch_args_trait_bounds(&self) -> Vec<WherePredicate> {
+        let crate_path = &self.crate_path;
+        let nested_bounds = self.build_trait_bounds(&quote! { #crate_path::cli::PatchArgs });
+        let value_bounds = self
+            .field_actions
+            .iter()
+            .filter(|action| !matches!(action, FieldAction::Patch { .. }))
+            .filter_map(|action| {
+                let field_ty = action.build_patch_field_type();
+                let field_ty: Type = parse_quote! { #field_ty };
+                let value_ty = ArgValue::of(&field_ty).value_type().clone();
+                self.uses_type_param(&value_ty).then_some(value_ty)
+            })
+            .flat_map(|value_ty| -> [WherePredicate; 2] {
+                [
+                    parse_quote! {
+                        #value_ty: ::core::str::FromStr
+                            + ::core::clone::Clone
+                            + ::core::marker::Send
+                            + ::core::marker::Sync
+                            + 'static
+                    },
+                    parse_quote! {
+                        <#value_ty as ::core::str::FromStr>::Err: ::core::fmt::Display
+                    },
+                ]
+            });
+        nested_bounds.into_iter().chain(value_bounds).collect()
+    }
+
+    /// Whether `ty` uses a type parameter of the struct.
+    fn uses_type_param(&self, ty: &Type) -> bool {
+        let used_types = collect_used_simple_types(ty);
+        self.generics
+            .type_params()
+            .any(|param| used_types.contains(&&param.ident))
+    }
+}
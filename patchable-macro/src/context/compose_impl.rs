@@ -0,0 +1,92 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Fields, WherePredicate, parse_quote};
+
+use crate::context::{FieldAction, MacroContext};
+
+impl<'a> MacroContext<'a> {
+    // ============================================================
+    // impl<T, ...> ComposePatch for OriginalStructPatch<T, ...>
+    // ============================================================
+
+    /// Whether `#[derive(Patchable)]` implements `ComposePatch` for the patch struct.
+    ///
+    /// Patches of `#[patchable(with = "...")]` fields cannot be composed.
+    pub(crate) fn generates_compose_impl(&self) -> bool {
+        self.generates_patch_struct()
+            && !self
+                .field_actions
+                .iter()
+                .any(|action| matches!(action, FieldAction::With { .. }))
+    }
+
+    pub(crate) fn build_compose_patch_impl(&self) -> TokenStream2 {
+        let crate_path = &self.crate_path;
+        let compose_trait = quote! { #crate_path::ComposePatch };
+        let patch_struct_type = &self.patch_struct_type;
+        let impl_generics = self
+            .generics
+            .type_params()
+            .filter(|param| self.preserved_types.contains_key(&param.ident))
+            .map(|param| &param.ident);
+
+        let patchable_trait = &self.patchable_trait;
+        let bounds: Vec<WherePredicate> = self
+            .iter_patchable_type_params()
+            .flat_map(|ty| -> [WherePredicate; 2] {
+                [
+                    parse_quote! { #ty: #patchable_trait },
+                    parse_quote! { <#ty as #patchable_trait>::Patch: #compose_trait },
+                ]
+            })
+            .collect();
+        let where_clause = (!bounds.is_empty()).then(|| quote! { where #(#bounds),* });
+
+        let composed_fields = self
+            .field_actions
+            .iter()
+            .enumerate()
+            .map(|(patch_index, action)| {
+                let patch_member = action.member().patch_member(patch_index);
+                let first = quote! { self.#patch_member };
+                let later = quote! { later.#patch_member };
+                if !self.partial {
+                    return action.build_compose_expr(&compose_trait, &first, &later);
+                }
+                // The fields of partial patches are composed when both are set.
+                if matches!(action, FieldAction::Keep { .. }) {
+                    return quote! { ::core::option::Option::or(#later, #first) };
+                }
+                let composed =
+                    action.build_compose_expr(&compose_trait, &quote! { first }, &quote! { later });
+                quote! {
+                    #crate_path::partial::__private::compose(#first, #later, |first, later| {
+                        #composed
+                    })
+                }
+            });
+        let body = match &self.fields {
+            Fields::Named(_) => {
+                let members = self.field_actions.iter().map(FieldAction::member);
+                quote! { Self { #(#members: #composed_fields),* } }
+            }
+            Fields::Unnamed(_) => quote! { Self(#(#composed_fields),*) },
+            Fields::Unit => quote! { Self },
+        };
+        let later_param_name = if self.field_actions.is_empty() {
+            quote! { _later }
+        } else {
+            quote! { later }
+        };
+
+        quote! {
+            impl <#(#impl_generics),*> #compose_trait for #patch_struct_type
+            #where_clause {
+                #[inline(always)]
+                fn compose(self, #later_param_name: Self) -> Self {
+                    #body
+                }
+            }
+        }
+    }
+}
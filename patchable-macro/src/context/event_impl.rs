@@ -0,0 +1,130 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::Ident;
+
+use crate::IS_SERDE_ENABLED;
+use crate::context::MacroContext;
+
+impl<'a> MacroContext<'a> {
+    // ============================================================
+    // #[derive(::serde::Serialize, ::serde::Deserialize)]
+    // pub enum OriginalStructEvent<T, ...> { ... }
+    // ============================================================
+
+    /// Builds the event enum, with a variant per non-skipped field carrying its patch value.
+    pub(crate) fn build_event_enum(&self) -> TokenStream2 {
+        let derive_attr = IS_SERDE_ENABLED.then_some(quote! {
+            #[derive(::serde::Serialize, ::serde::Deserialize)]
+        });
+        let visibility = self.visibility;
+        let event_enum_type = self.build_event_enum_type();
+        let doc = format!(
+            "A change of a single field of [`{}`], applied with `PatchEvents::apply_event`.",
+            self.struct_name
+        );
+
+        let bounded_types = self.build_trait_bounds(&self.patchable_trait);
+        let where_clause = if bounded_types.is_empty() {
+            quote! {}
+        } else {
+            quote! { where #(#bounded_types),* }
+        };
+        let variants = self.field_actions.iter().map(|action| {
+            let variant = action.member().variant();
+            let field_ty = action.build_patch_field_type();
+            quote! { #variant(#field_ty) }
+        });
+
+        quote! {
+            #[doc = #doc]
+            #derive_attr
+            #visibility enum #event_enum_type #where_clause {
+                #(#variants),*
+            }
+        }
+    }
+
+    // ============================================================
+    // impl<T, ...> PatchEvents for OriginalStruct<T, ...
+    // ============================================================
+
+    pub(crate) fn build_patch_events_trait_impl(&self) -> syn::Result<TokenStream2> {
+        self.validate_patch_events()?;
+
+        let crate_path = &self.crate_path;
+        let patch_trait = &self.patch_trait;
+        let (impl_generics, type_generics, _) = self.generics.split_for_impl();
+        let where_clause = self.extend_where_clause(&self.build_trait_bounds(patch_trait));
+
+        let input_struct_name = self.struct_name;
+        let event_enum_name = self.event_enum_name();
+        let event_enum_type = self.build_event_enum_type();
+        let event_count = self.field_actions.len();
+        let patch_param_name = self.patch_param_name();
+
+        let (destructure, values) = self.build_patch_values();
+        let events = self
+            .field_actions
+            .iter()
+            .zip(values)
+            .map(|(action, value)| {
+                let variant = action.member().variant();
+                quote! { #event_enum_name::#variant(#value) }
+            });
+        let arms = self.field_actions.iter().map(|action| {
+            let member = action.member();
+            let variant = member.variant();
+            let update = action.build_update_statement(
+                patch_trait,
+                &quote! { self.#member },
+                &quote! { value },
+            );
+            quote! { #event_enum_name::#variant(value) => { #update } }
+        });
+        let after_patch = self.build_after_patch_call(&quote! { self });
+
+        Ok(quote! {
+            impl #impl_generics #crate_path::PatchEvents
+                for #input_struct_name #type_generics
+            #where_clause {
+                type Event = #event_enum_type;
+                type Events = ::core::array::IntoIter<Self::Event, #event_count>;
+
+                #[inline(always)]
+                fn apply_event(&mut self, event: Self::Event) {
+                    match event {
+                        #(#arms)*
+                    }
+                    #after_patch
+                }
+
+                #[inline(always)]
+                fn into_events(#patch_param_name: Self::Patch) -> Self::Events {
+                    #destructure
+                    ::core::iter::IntoIterator::into_iter([#(#events),*])
+                }
+            }
+        })
+    }
+
+    fn validate_patch_events(&self) -> syn::Result<()> {
+        self.reject_unsupported("PatchEvents")?;
+        self.reject_partial("PatchEvents")?;
+        Ok(())
+    }
+
+    fn event_enum_name(&self) -> Ident {
+        format_ident!("{}Event", self.struct_name)
+    }
+
+    /// The event enum type, with the type parameters used by the patch.
+    fn build_event_enum_type(&self) -> TokenStream2 {
+        let event_enum_name = self.event_enum_name();
+        let params = self
+            .generics
+            .type_params()
+            .map(|param| &param.ident)
+            .filter(|ident| self.preserved_types.contains_key(ident));
+        quote! { #event_enum_name <#(#params),*> }
+    }
+}
@@ -2,7 +2,7 @@ use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
 use syn::{Fields, WherePredicate, parse_quote};
 
-use crate::context::MacroContext;
+use crate::context::{FieldMember, MacroContext};
 
 impl<'a> MacroContext<'a> {
     // ======================================================================
@@ -15,35 +15,60 @@ impl<'a> MacroContext<'a> {
 
         let input_struct_name = self.struct_name;
         let patch_struct_type = &self.patch_struct_type;
-        let from_method_body = self.build_from_method_body();
+
+        // A remote definition converts from the remote type, destructured field by field.
+        let (source_type, from_method_body) = match &self.remote {
+            Some(remote) => {
+                let remote_pattern = self.build_remote_pattern(remote);
+                let body = self.build_from_method_body(|member| {
+                    let binding = member.binding("field");
+                    quote! { #binding }
+                });
+                (
+                    quote! { #remote },
+                    quote! {
+                        let #remote_pattern = value;
+                        #body
+                    },
+                )
+            }
+            None => (
+                quote! { #input_struct_name #type_generics },
+                self.build_from_method_body(|member| quote! { value.#member }),
+            ),
+        };
 
         quote! {
-            impl #impl_generics ::core::convert::From<#input_struct_name #type_generics>
+            impl #impl_generics ::core::convert::From<#source_type>
                 for #patch_struct_type
             #where_clause {
                 #[inline(always)]
-                fn from(value: #input_struct_name #type_generics) -> Self {
+                fn from(value: #source_type) -> Self {
                     #from_method_body
                 }
             }
         }
     }
 
-    fn build_from_method_body(&self) -> TokenStream2 {
+    fn build_from_method_body<F>(&self, source: F) -> TokenStream2
+    where
+        F: Fn(&FieldMember<'a>) -> TokenStream2,
+    {
         match &self.fields {
             Fields::Named(_) => {
                 let field_initializers = self.field_actions.iter().map(|action| {
                     let member = action.member();
-                    let value = action.build_initializer_expr();
+                    let value = action.build_initializer_expr(&source(member));
+                    let value = self.wrap_partial_value(value);
                     quote! { #member: #value }
                 });
                 quote! { Self { #(#field_initializers),* } }
             }
             Fields::Unnamed(_) => {
-                let field_values = self
-                    .field_actions
-                    .iter()
-                    .map(|action| action.build_initializer_expr());
+                let field_values = self.field_actions.iter().map(|action| {
+                    let value = action.build_initializer_expr(&source(action.member()));
+                    self.wrap_partial_value(value)
+                });
                 quote! { Self(#(#field_values),*) }
             }
             Fields::Unit => {
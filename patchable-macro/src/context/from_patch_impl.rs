@@ -0,0 +1,268 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{Fields, Index, Path, PathArguments, WherePredicate, parse_quote};
+
+use crate::context::{
+    FieldAction, FieldMember, MacroContext, SkippedField, collect_used_simple_types,
+};
+
+impl<'a> MacroContext<'a> {
+    // ============================================================
+    // pub struct OriginalStructSkipped<T, ...> { ... }
+    // ============================================================
+
+    /// Builds the struct holding the skipped fields that have no `default`.
+    pub(crate) fn build_skipped_struct(&self) -> TokenStream2 {
+        let visibility = self.visibility;
+        let skipped_struct_type = self.build_skipped_struct_type();
+        let doc = format!(
+            "The fields of [`{}`] skipped by its patch, used to rebuild it with `FromPatch`.",
+            self.struct_name
+        );
+
+        let required_fields: Vec<_> = self.iter_required_skipped_fields().collect();
+        let body = match (&self.fields, required_fields.is_empty()) {
+            (_, true) | (Fields::Unit, _) => quote! {;},
+            (Fields::Named(_), false) => {
+                let fields = required_fields.iter().map(|skipped| {
+                    let field_vis = &skipped.field.vis;
+                    let member = &skipped.member;
+                    let ty = &skipped.field.ty;
+                    quote! { #field_vis #member: #ty }
+                });
+                quote! { { #(#fields),* } }
+            }
+            (Fields::Unnamed(_), false) => {
+                let fields = required_fields.iter().map(|skipped| {
+                    let field_vis = &skipped.field.vis;
+                    let ty = &skipped.field.ty;
+                    quote! { #field_vis #ty }
+                });
+                quote! { ( #(#fields),* ); }
+            }
+        };
+
+        quote! {
+            #[doc = #doc]
+            #visibility struct #skipped_struct_type #body
+        }
+    }
+
+    // ============================================================
+    // impl<T, ...> FromPatch for OriginalStruct<T, ...
+    // ============================================================
+
+    pub(crate) fn build_from_patch_trait_impl(&self) -> TokenStream2 {
+        let crate_path = &self.crate_path;
+        let patchable_trait = &self.patchable_trait;
+        let (impl_generics, type_generics, _) = self.generics.split_for_impl();
+        let where_clause = self.extend_where_clause(&self.build_from_patch_trait_bounds());
+
+        let input_struct_name = self.struct_name;
+        let skipped_struct_type = self.build_skipped_struct_type();
+        let patch_param_name = self.patch_param_name();
+        let skipped_param_name = self.skipped_param_name();
+        let from_patch_body = self.build_from_patch_body(&quote! { Self });
+
+        // Only generated patch structs and hand-written patch types can be converted with
+        // `From`: the patch type of a transparent struct may be the struct itself.
+        let has_required_fields = self.iter_required_skipped_fields().next().is_some();
+        let from_impl = (!has_required_fields && !self.transparent).then(|| {
+            let skipped_struct_name = format_ident!("{}Skipped", self.struct_name);
+            quote! {
+                #[automatically_derived]
+                impl #impl_generics
+                    ::core::convert::From<<Self as #patchable_trait>::Patch>
+                    for #input_struct_name #type_generics
+                #where_clause {
+                    #[inline(always)]
+                    fn from(patch: <Self as #patchable_trait>::Patch) -> Self {
+                        #crate_path::FromPatch::from_patch(patch, #skipped_struct_name)
+                    }
+                }
+            }
+        });
+
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics #crate_path::FromPatch
+                for #input_struct_name #type_generics
+            #where_clause {
+                type Skipped = #skipped_struct_type;
+
+                #[inline(always)]
+                fn from_patch(
+                    #patch_param_name: Self::Patch,
+                    #skipped_param_name: Self::Skipped,
+                ) -> Self {
+                    #from_patch_body
+                }
+            }
+
+            #from_impl
+        }
+    }
+
+    // ============================================================
+    // impl<T, ...> OriginalStruct<T, ...> {
+    //     fn from_patch(patch: ..., skipped: ...) -> RemoteStruct<T, ...> { ... }
+    // }
+    // ============================================================
+
+    pub(crate) fn build_remote_from_patch_fn(&self, remote: &Path) -> TokenStream2 {
+        let patchable_trait = &self.patchable_trait;
+        let (impl_generics, type_generics, _) = self.generics.split_for_impl();
+        let where_clause = self.extend_where_clause(&self.build_from_patch_trait_bounds());
+
+        let input_struct_name = self.struct_name;
+        let visibility = self.visibility;
+        let skipped_struct_type = self.build_skipped_struct_type();
+        let patch_param_name = self.patch_param_name();
+        let skipped_param_name = self.skipped_param_name();
+
+        let mut remote_path = remote.clone();
+        if let Some(last_segment) = remote_path.segments.last_mut() {
+            last_segment.arguments = PathArguments::None;
+        }
+        let from_patch_body = self.build_from_patch_body(&quote! { #remote_path });
+
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics #input_struct_name #type_generics
+            #where_clause {
+                /// Builds a remote value from a patch and the skipped fields.
+                #[inline(always)]
+                #visibility fn from_patch(
+                    #patch_param_name: <Self as #patchable_trait>::Patch,
+                    #skipped_param_name: #skipped_struct_type,
+                ) -> #remote {
+                    #from_patch_body
+                }
+            }
+        }
+    }
+
+    /// Builds the expression constructing `constructor` from `patch` and `skipped`.
+    ///
+    /// Plain and `merge` fields, whose patch holds a whole value, take it as is. Other fields
+    /// start from their default value and get the patch applied. Braced expressions also build
+    /// tuple structs (`Struct { 0: value }`).
+    fn build_from_patch_body(&self, constructor: &TokenStream2) -> TokenStream2 {
+        let (destructure, values) = self.build_patch_values();
+        let patched_fields = self
+            .field_actions
+            .iter()
+            .zip(values)
+            .map(|(action, value)| {
+                let member = action.member();
+                let ty = action.ty();
+                let value = match action {
+                    FieldAction::Keep { .. } | FieldAction::Merge { .. } => value,
+                    _ => {
+                        let update = action.build_update_statement(
+                            &self.patch_trait,
+                            &quote! { field },
+                            &value,
+                        );
+                        quote! {{
+                            let mut field: #ty = ::core::default::Default::default();
+                            #update
+                            field
+                        }}
+                    }
+                };
+                quote! { #member: #value }
+            });
+
+        let mut required_index = 0;
+        let skipped_fields = self.skipped_fields.iter().map(|skipped| {
+            let member = &skipped.member;
+            let value = match &skipped.default {
+                Some(default) => quote! { #default },
+                None => {
+                    let skipped_member = match member {
+                        FieldMember::Named(name) => quote! { #name },
+                        FieldMember::Unnamed(_) => {
+                            let index = Index::from(required_index);
+                            quote! { #index }
+                        }
+                    };
+                    required_index += 1;
+                    quote! { skipped.#skipped_member }
+                }
+            };
+            quote! { #member: #value }
+        });
+        let skipped_fields: Vec<_> = skipped_fields.collect();
+
+        let value = quote! { #constructor { #(#patched_fields,)* #(#skipped_fields,)* } };
+        match self.build_after_patch_call(&quote! { &mut value }) {
+            Some(after_patch) => quote! {
+                #destructure
+                let mut value = #value;
+                #after_patch
+                value
+            },
+            None => quote! {
+                #destructure
+                #value
+            },
+        }
+    }
+
+    /// Requires `Patch + Default` for nested fields, and `Default` for other patched fields
+    /// whose type uses a type parameter.
+    fn build_from_patch_trait_bounds(&self) -> Vec<WherePredicate> {
+        let patch_trait = &self.patch_trait;
+        let type_params: Vec<_> = self
+            .generics
+            .type_params()
+            .map(|param| &param.ident)
+            .collect();
+        let nested_bounds = self
+            .iter_patchable_type_params()
+            .map(|ty| parse_quote! { #ty: #patch_trait + ::core::default::Default });
+        let default_bounds = self
+            .field_actions
+            .iter()
+            .filter(|action| matches!(action, FieldAction::With { .. } | FieldAction::Delta { .. }))
+            .map(FieldAction::ty)
+            .filter(|ty| {
+                collect_used_simple_types(ty)
+                    .iter()
+                    .any(|used| type_params.contains(used))
+            })
+            .map(|ty| parse_quote! { #ty: ::core::default::Default });
+        nested_bounds.chain(default_bounds).collect()
+    }
+
+    /// The skipped struct type, with the type parameters used by its fields.
+    fn build_skipped_struct_type(&self) -> TokenStream2 {
+        let skipped_struct_name = format_ident!("{}Skipped", self.struct_name);
+        let used_types: Vec<_> = self
+            .iter_required_skipped_fields()
+            .flat_map(|skipped| collect_used_simple_types(&skipped.field.ty))
+            .collect();
+        let generic_params = self
+            .generics
+            .type_params()
+            .map(|param| &param.ident)
+            .filter(|ident| used_types.contains(ident));
+        quote! { #skipped_struct_name <#(#generic_params),*> }
+    }
+
+    fn skipped_param_name(&self) -> TokenStream2 {
+        if self.iter_required_skipped_fields().next().is_some() {
+            quote! { skipped }
+        } else {
+            quote! { _skipped }
+        }
+    }
+
+    /// The skipped fields without a `default`, which the skipped struct holds.
+    fn iter_required_skipped_fields(&self) -> impl Iterator<Item = &SkippedField<'a>> + '_ {
+        self.skipped_fields
+            .iter()
+            .filter(|skipped| skipped.default.is_none())
+    }
+}
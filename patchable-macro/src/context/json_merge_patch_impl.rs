@@ -0,0 +1,150 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{WherePredicate, parse_quote};
+
+use crate::context::{FieldAction, MacroContext, collect_used_simple_types};
+
+impl<'a> MacroContext<'a> {
+    // ============================================================
+    // impl<T, ...> JsonMergePatch for OriginalStruct<T, ...
+    // ============================================================
+
+    pub(crate) fn build_json_merge_patch_trait_impl(&self) -> syn::Result<TokenStream2> {
+        self.validate_json_merge_patch()?;
+
+        let crate_path = &self.crate_path;
+        let (impl_generics, type_generics, _) = self.generics.split_for_impl();
+        let where_clause = self.extend_where_clause(&self.build_json_merge_patch_trait_bounds());
+        let input_struct_name = self.struct_name;
+        let private = quote! { #crate_path::json_merge_patch::__private };
+
+        // A transparent struct shares the patch of its field, so the document is that patch.
+        let body = match self.field_actions.as_slice() {
+            [action] if self.transparent => {
+                let member = action.member();
+                match action {
+                    FieldAction::Patch { .. } => quote! {
+                        #crate_path::JsonMergePatch::merge_json(&mut self.#member, patch, apply)?;
+                    },
+                    _ => {
+                        let field_ty = action.build_patch_field_type();
+                        let update = action.build_update_statement(
+                            &self.patch_trait,
+                            &quote! { self.#member },
+                            &quote! { value },
+                        );
+                        quote! {
+                            let value: #field_ty = #private::document_value(patch)?;
+                            if apply {
+                                #update
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {
+                let arms = self.field_actions.iter().map(|action| {
+                    let member = action.member();
+                    let key = member.name();
+                    match action {
+                        FieldAction::Patch { .. } => quote! {
+                            #key => #private::merge_field(&mut self.#member, value, #key, apply)?,
+                        },
+                        _ => {
+                            let field_ty = action.build_patch_field_type();
+                            let update = action.build_update_statement(
+                                &self.patch_trait,
+                                &quote! { self.#member },
+                                &quote! { value },
+                            );
+                            quote! {
+                                #key => {
+                                    let value: #field_ty = #private::field_value(value, #key)?;
+                                    if apply {
+                                        #update
+                                    }
+                                }
+                            }
+                        }
+                    }
+                });
+                let value = if self.field_actions.is_empty() {
+                    quote! { _ }
+                } else {
+                    quote! { value }
+                };
+                quote! {
+                    for (key, #value) in #private::as_object(patch)? {
+                        match key.as_str() {
+                            #(#arms)*
+                            _ => return ::core::result::Result::Err(#private::unknown_field(key)),
+                        }
+                    }
+                }
+            }
+        };
+        let after_patch = self.build_after_patch_call(&quote! { self }).map(|call| {
+            quote! {
+                if apply {
+                    #call
+                }
+            }
+        });
+
+        let apply_param_name = if self.field_actions.is_empty() && after_patch.is_none() {
+            quote! { _apply }
+        } else {
+            quote! { apply }
+        };
+
+        Ok(quote! {
+            impl #impl_generics #crate_path::JsonMergePatch
+                for #input_struct_name #type_generics
+            #where_clause {
+                fn merge_json(
+                    &mut self,
+                    patch: &#private::Value,
+                    #apply_param_name: bool,
+                ) -> ::core::result::Result<(), #crate_path::MergePatchError> {
+                    #body
+                    #after_patch
+                    ::core::result::Result::Ok(())
+                }
+            }
+        })
+    }
+
+    fn validate_json_merge_patch(&self) -> syn::Result<()> {
+        self.reject_unsupported("JsonMergePatch")?;
+        self.reject_partial("JsonMergePatch")?;
+        Ok(())
+    }
+
+    /// Requires `JsonMergePatch` for nested fields, and `DeserializeOwned` for the patch values
+    /// of other fields whose type uses a type parameter.
+    fn build_json_merge_patch_trait_bounds(&self) -> Vec<WherePredicate> {
+        let crate_path = &self.crate_path;
+        let type_params: Vec<_> = self
+            .generics
+            .type_params()
+            .map(|param| &param.ident)
+            .collect();
+        let nested_bounds = self.build_trait_bounds(&quote! { #crate_path::JsonMergePatch });
+        let value_bounds = self
+            .field_actions
+            .iter()
+            .filter(|action| {
+                !matches!(action, FieldAction::Patch { .. })
+                    && collect_used_simple_types(action.ty())
+                        .iter()
+                        .any(|used| type_params.contains(used))
+            })
+            .map(|action| -> WherePredicate {
+                let field_ty = action.build_patch_field_type();
+                parse_quote! {
+                    #field_ty: #crate_path::json_merge_patch::__private::DeserializeOwned
+                }
+            });
+        nested_bounds.into_iter().chain(value_bounds).collect()
+    }
+}
@@ -0,0 +1,271 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Type, WherePredicate, parse_quote};
+
+use crate::context::{FieldAction, MacroContext, collect_used_simple_types};
+
+impl<'a> MacroContext<'a> {
+    // ============================================================
+    // impl<T, ...> JsonPatch for OriginalStruct<T, ...
+    // ============================================================
+
+    pub(crate) fn build_json_patch_trait_impl(&self) -> syn::Result<TokenStream2> {
+        self.reject_unsupported("JsonPatch")?;
+
+        let crate_path = &self.crate_path;
+        let (impl_generics, type_generics, _) = self.generics.split_for_impl();
+        let where_clause = self.extend_where_clause(&self.build_json_patch_trait_bounds());
+        let input_struct_name = self.struct_name;
+        let private = quote! { #crate_path::json_patch::__private };
+        let error_kind = quote! { #crate_path::json_patch::JsonPatchErrorKind };
+        let after_patch = self.build_after_patch_call(&quote! { self });
+
+        let (get_body, set_body, remove_body) = match self.field_actions.as_slice() {
+            // A transparent struct is represented by its field, which the whole path addresses.
+            [action] if self.transparent => {
+                let set = self.build_pointer_set(action, &quote! { path }, &quote! { value });
+                let remove = self.build_pointer_remove(action);
+                (
+                    self.build_pointer_get(action, &quote! { path }),
+                    quote! { #set? },
+                    quote! {
+                        let removed = #remove?;
+                        #after_patch
+                        ::core::result::Result::Ok(removed)
+                    },
+                )
+            }
+            _ => self.build_pointer_dispatch(),
+        };
+        let insert_param_name = if self.field_actions.is_empty() {
+            quote! { _insert }
+        } else {
+            quote! { insert }
+        };
+
+        Ok(quote! {
+            impl #impl_generics #crate_path::JsonPatch
+                for #input_struct_name #type_generics
+            #where_clause {
+                fn pointer_get(
+                    &self,
+                    path: &[#private::String],
+                ) -> ::core::result::Result<#private::Value, #error_kind> {
+                    #get_body
+                }
+
+                fn pointer_set(
+                    &mut self,
+                    path: &[#private::String],
+                    value: #private::Value,
+                    #insert_param_name: bool,
+                ) -> ::core::result::Result<(), #error_kind> {
+                    #set_body;
+                    #after_patch
+                    ::core::result::Result::Ok(())
+                }
+
+                fn pointer_remove(
+                    &mut self,
+                    path: &[#private::String],
+                ) -> ::core::result::Result<#private::Value, #error_kind> {
+                    #remove_body
+                }
+            }
+        })
+    }
+
+    /// Builds the bodies of the pointer methods of a struct, which match the first segment of
+    /// the path against the names of the fields.
+    ///
+    /// An empty path addresses the struct itself, as an object with a member per field.
+    fn build_pointer_dispatch(&self) -> (TokenStream2, TokenStream2, TokenStream2) {
+        let crate_path = &self.crate_path;
+        let private = quote! { #crate_path::json_patch::__private };
+        let not_found = quote! {
+            return ::core::result::Result::Err(#private::not_found())
+        };
+        let keys: Vec<_> = self
+            .field_actions
+            .iter()
+            .map(|action| action.member().name())
+            .collect();
+        let bindings: Vec<_> = self
+            .field_actions
+            .iter()
+            .map(|action| action.member().binding("value"))
+            .collect();
+        let (object_mut, segment) = if self.field_actions.is_empty() {
+            (quote! {}, quote! { _ })
+        } else {
+            (quote! { mut }, quote! { (key, path) })
+        };
+
+        let root_gets = self
+            .field_actions
+            .iter()
+            .map(|action| self.build_pointer_get(action, &quote! { &[] }));
+        let gets = self
+            .field_actions
+            .iter()
+            .map(|action| self.build_pointer_get(action, &quote! { path }));
+        let get_dispatch = self.build_segment_match(&keys, gets, &not_found);
+        let get_body = quote! {
+            match path.split_first() {
+                ::core::option::Option::None => {
+                    let #object_mut object = #private::Map::new();
+                    #(object.insert(::core::convert::From::from(#keys), #root_gets?);)*
+                    ::core::result::Result::Ok(#private::Value::Object(object))
+                }
+                ::core::option::Option::Some(#segment) => #get_dispatch,
+            }
+        };
+
+        let root_sets = self
+            .field_actions
+            .iter()
+            .zip(&bindings)
+            .map(|(action, binding)| {
+                let set = self.build_pointer_set(action, &quote! { &[] }, &quote! { #binding });
+                quote! { #set? }
+            });
+        let sets = self.field_actions.iter().map(|action| {
+            let set = self.build_pointer_set(action, &quote! { path }, &quote! { value });
+            quote! { #set? }
+        });
+        let set_dispatch = self.build_segment_match(&keys, sets, &not_found);
+        let set_body = quote! {
+            match path.split_first() {
+                ::core::option::Option::None => {
+                    let #object_mut object = #private::into_object(value)?;
+                    #(let #bindings = #private::take_field(&mut object, #keys)?;)*
+                    #private::deny_unknown_fields(&object)?;
+                    #(#root_sets;)*
+                }
+                ::core::option::Option::Some(#segment) => #set_dispatch,
+            }
+        };
+
+        let remove_struct = quote! {
+            ::core::result::Result::Err(#private::remove_struct())
+        };
+        let remove_body = if self.field_actions.is_empty() {
+            quote! {
+                match path.split_first() {
+                    ::core::option::Option::None => #remove_struct,
+                    ::core::option::Option::Some(_) => {
+                        ::core::result::Result::Err(#private::not_found())
+                    }
+                }
+            }
+        } else {
+            let removes = self.field_actions.iter().map(|action| {
+                let remove = self.build_pointer_remove(action);
+                quote! { #remove? }
+            });
+            let remove_dispatch = self.build_segment_match(&keys, removes, &not_found);
+            let after_patch = self.build_after_patch_call(&quote! { self });
+            quote! {
+                let removed = match path.split_first() {
+                    ::core::option::Option::None => return #remove_struct,
+                    ::core::option::Option::Some(#segment) => #remove_dispatch,
+                };
+                #after_patch
+                ::core::result::Result::Ok(removed)
+            }
+        };
+
+        (get_body, set_body, remove_body)
+    }
+
+    /// Matches `key` against the names of the fields, with `not_found` for other names.
+    fn build_segment_match(
+        &self,
+        keys: &[String],
+        arms: impl Iterator<Item = TokenStream2>,
+        not_found: &TokenStream2,
+    ) -> TokenStream2 {
+        if keys.is_empty() {
+            return quote! { #not_found };
+        }
+        quote! {
+            match key.as_str() {
+                #(#keys => #arms,)*
+                _ => #not_found,
+            }
+        }
+    }
+
+    fn build_pointer_get(&self, action: &FieldAction<'a>, path: &TokenStream2) -> TokenStream2 {
+        let crate_path = &self.crate_path;
+        let member = action.member();
+        match action {
+            FieldAction::Patch { .. } => quote! {
+                #crate_path::JsonPatch::pointer_get(&self.#member, #path)
+            },
+            _ => quote! {
+                #crate_path::json_patch::__private::leaf_get(&self.#member, #path)
+            },
+        }
+    }
+
+    fn build_pointer_set(
+        &self,
+        action: &FieldAction<'a>,
+        path: &TokenStream2,
+        value: &TokenStream2,
+    ) -> TokenStream2 {
+        let crate_path = &self.crate_path;
+        let member = action.member();
+        match action {
+            FieldAction::Patch { .. } => quote! {
+                #crate_path::JsonPatch::pointer_set(&mut self.#member, #path, #value, insert)
+            },
+            _ => quote! {
+                #crate_path::json_patch::__private::leaf_set(
+                    &mut self.#member, #path, #value, insert,
+                )
+            },
+        }
+    }
+
+    fn build_pointer_remove(&self, action: &FieldAction<'a>) -> TokenStream2 {
+        let crate_path = &self.crate_path;
+        let member = action.member();
+        match action {
+            FieldAction::Patch { .. } => quote! {
+                #crate_path::JsonPatch::pointer_remove(&mut self.#member, path)
+            },
+            _ => quote! {
+                #crate_path::json_patch::__private::leaf_remove(&mut self.#member, path)
+            },
+        }
+    }
+
+    /// Requires `JsonPatch` for nested fields, and `Serialize` and `DeserializeOwned` for other
+    /// fields whose type uses a type parameter.
+    fn build_json_patch_trait_bounds(&self) -> Vec<WherePredicate> {
+        let crate_path = &self.crate_path;
+        let private = quote! { #crate_path::json_patch::__private };
+        let type_params: Vec<_> = self
+            .generics
+            .type_params()
+            .map(|param| &param.ident)
+            .collect();
+        let nested_bounds = self.build_trait_bounds(&quote! { #crate_path::JsonPatch });
+        let value_bounds = self
+            .field_actions
+            .iter()
+            .filter(|action| {
+                !matches!(action, FieldAction::Patch { .. })
+                    && collect_used_simple_types(action.ty())
+                        .iter()
+                        .any(|used| type_params.contains(used))
+            })
+            .map(|action| -> WherePredicate {
+                let ty: &Type = action.ty();
+                parse_quote! { #ty: #private::Serialize + #private::DeserializeOwned }
+            });
+        nested_bounds.into_iter().chain(value_bounds).collect()
+    }
+}
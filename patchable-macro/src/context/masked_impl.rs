@@ -0,0 +1,359 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::Ident;
+
+use crate::context::MacroContext;
+
+/// The number of fields a mask can hold, given by its `u64` bits.
+const MAX_MASKED_FIELDS: usize = 64;
+
+impl<'a> MacroContext<'a> {
+    // ============================================================
+    // pub enum OriginalStructField { ... }
+    // pub struct OriginalStructFieldMask(u64);
+    // ============================================================
+
+    /// Builds the field enum and the field mask, with their operations.
+    pub(crate) fn build_field_mask_types(&self) -> TokenStream2 {
+        let crate_path = &self.crate_path;
+        let visibility = self.visibility;
+        let field_enum = self.field_enum_name();
+        let field_mask = self.field_mask_name();
+        let enum_doc = format!(
+            "The fields of [`{}`] carried by its patch, selected by [`{field_mask}`].",
+            self.struct_name
+        );
+        let mask_doc = format!(
+            "A set of [`{field_enum}`] values, used to apply part of a patch with `MaskedPatch`."
+        );
+
+        let variants: Vec<_> = self
+            .field_actions
+            .iter()
+            .map(|action| action.member().variant())
+            .collect();
+        let names = self
+            .field_actions
+            .iter()
+            .map(|action| action.member().name());
+        let field_count = variants.len();
+        let indexes = 0..field_count;
+        let all_bits = if field_count == MAX_MASKED_FIELDS {
+            u64::MAX
+        } else {
+            (1_u64 << field_count) - 1
+        };
+
+        quote! {
+            #[doc = #enum_doc]
+            #[derive(
+                ::core::fmt::Debug,
+                ::core::clone::Clone,
+                ::core::marker::Copy,
+                ::core::cmp::PartialEq,
+                ::core::cmp::Eq,
+                ::core::hash::Hash,
+                ::core::cmp::PartialOrd,
+                ::core::cmp::Ord,
+            )]
+            #visibility enum #field_enum {
+                #(#variants),*
+            }
+
+            #[doc = #mask_doc]
+            #[derive(
+                ::core::clone::Clone,
+                ::core::marker::Copy,
+                ::core::cmp::PartialEq,
+                ::core::cmp::Eq,
+                ::core::hash::Hash,
+                ::core::default::Default,
+            )]
+            #visibility struct #field_mask(u64);
+
+            const _: () = {
+                #[automatically_derived]
+                impl #field_enum {
+                    /// Every field, in declaration order.
+                    pub const ALL: [Self; #field_count] = [#(Self::#variants),*];
+
+                    /// Returns the name of the field (or its index, for tuple structs).
+                    pub const fn name(self) -> &'static str {
+                        match self {
+                            #(Self::#variants => #names,)*
+                        }
+                    }
+
+                    const fn bit(self) -> u64 {
+                        match self {
+                            #(Self::#variants => 1 << #indexes,)*
+                        }
+                    }
+                }
+
+                #[automatically_derived]
+                impl #field_mask {
+                    /// The mask selecting every field.
+                    pub const ALL: Self = Self(#all_bits);
+
+                    /// The mask selecting no field.
+                    pub const NONE: Self = Self(0);
+
+                    /// Returns the mask selecting only `field`.
+                    pub const fn of(field: #field_enum) -> Self {
+                        Self(field.bit())
+                    }
+
+                    /// Returns whether `field` is selected.
+                    pub const fn contains(self, field: #field_enum) -> bool {
+                        self.0 & field.bit() != 0
+                    }
+
+                    /// Returns whether no field is selected.
+                    pub const fn is_empty(self) -> bool {
+                        self.0 == 0
+                    }
+
+                    /// Returns the number of selected fields.
+                    pub const fn len(self) -> usize {
+                        self.0.count_ones() as usize
+                    }
+
+                    /// Returns the mask with `field` selected too.
+                    #[must_use]
+                    pub const fn with(self, field: #field_enum) -> Self {
+                        Self(self.0 | field.bit())
+                    }
+
+                    /// Returns the mask with `field` deselected.
+                    #[must_use]
+                    pub const fn without(self, field: #field_enum) -> Self {
+                        Self(self.0 & !field.bit())
+                    }
+
+                    /// Returns the fields selected by either mask.
+                    #[must_use]
+                    pub const fn union(self, other: Self) -> Self {
+                        Self(self.0 | other.0)
+                    }
+
+                    /// Returns the fields selected by both masks.
+                    #[must_use]
+                    pub const fn intersection(self, other: Self) -> Self {
+                        Self(self.0 & other.0)
+                    }
+
+                    /// Returns the fields selected by `self` but not by `other`.
+                    #[must_use]
+                    pub const fn difference(self, other: Self) -> Self {
+                        Self(self.0 & !other.0)
+                    }
+
+                    /// Returns the fields not selected by `self`.
+                    #[must_use]
+                    pub const fn complement(self) -> Self {
+                        Self(Self::ALL.0 & !self.0)
+                    }
+
+                    /// Selects `field`.
+                    pub const fn insert(&mut self, field: #field_enum) {
+                        self.0 |= field.bit();
+                    }
+
+                    /// Deselects `field`.
+                    pub const fn remove(&mut self, field: #field_enum) {
+                        self.0 &= !field.bit();
+                    }
+
+                    /// Returns the selected fields, in declaration order.
+                    pub fn iter(self) -> impl ::core::iter::Iterator<Item = #field_enum> {
+                        #field_enum::ALL
+                            .into_iter()
+                            .filter(move |field| self.contains(*field))
+                    }
+                }
+
+                #[automatically_derived]
+                impl ::core::convert::From<#field_enum> for #field_mask {
+                    #[inline(always)]
+                    fn from(field: #field_enum) -> Self {
+                        Self::of(field)
+                    }
+                }
+
+                #[automatically_derived]
+                impl ::core::iter::FromIterator<#field_enum> for #field_mask {
+                    fn from_iter<I: ::core::iter::IntoIterator<Item = #field_enum>>(
+                        fields: I,
+                    ) -> Self {
+                        fields.into_iter().fold(Self::NONE, Self::with)
+                    }
+                }
+
+                #[automatically_derived]
+                impl ::core::ops::BitOr for #field_mask {
+                    type Output = Self;
+
+                    #[inline(always)]
+                    fn bitor(self, other: Self) -> Self {
+                        self.union(other)
+                    }
+                }
+
+                #[automatically_derived]
+                impl ::core::ops::BitOrAssign for #field_mask {
+                    #[inline(always)]
+                    fn bitor_assign(&mut self, other: Self) {
+                        *self = self.union(other);
+                    }
+                }
+
+                #[automatically_derived]
+                impl ::core::ops::BitAnd for #field_mask {
+                    type Output = Self;
+
+                    #[inline(always)]
+                    fn bitand(self, other: Self) -> Self {
+                        self.intersection(other)
+                    }
+                }
+
+                #[automatically_derived]
+                impl ::core::ops::BitAndAssign for #field_mask {
+                    #[inline(always)]
+                    fn bitand_assign(&mut self, other: Self) {
+                        *self = self.intersection(other);
+                    }
+                }
+
+                #[automatically_derived]
+                impl ::core::ops::Sub for #field_mask {
+                    type Output = Self;
+
+                    #[inline(always)]
+                    fn sub(self, other: Self) -> Self {
+                        self.difference(other)
+                    }
+                }
+
+                #[automatically_derived]
+                impl ::core::ops::SubAssign for #field_mask {
+                    #[inline(always)]
+                    fn sub_assign(&mut self, other: Self) {
+                        *self = self.difference(other);
+                    }
+                }
+
+                #[automatically_derived]
+                impl ::core::ops::Not for #field_mask {
+                    type Output = Self;
+
+                    #[inline(always)]
+                    fn not(self) -> Self {
+                        self.complement()
+                    }
+                }
+
+                #[automatically_derived]
+                impl ::core::fmt::Debug for #field_mask {
+                    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        f.debug_set().entries(self.iter()).finish()
+                    }
+                }
+
+                #[automatically_derived]
+                impl #crate_path::FieldMask for #field_mask {
+                    type Field = #field_enum;
+
+                    const ALL: Self = Self::ALL;
+                    const NONE: Self = Self::NONE;
+
+                    #[inline(always)]
+                    fn contains(&self, field: #field_enum) -> bool {
+                        Self::contains(*self, field)
+                    }
+                }
+            };
+        }
+    }
+
+    // ============================================================
+    // impl<T, ...> MaskedPatch for OriginalStruct<T, ...
+    // ============================================================
+
+    pub(crate) fn build_masked_patch_trait_impl(&self) -> syn::Result<TokenStream2> {
+        self.validate_masked_patch()?;
+
+        let crate_path = &self.crate_path;
+        let patch_trait = &self.patch_trait;
+        let (impl_generics, type_generics, _) = self.generics.split_for_impl();
+        let where_clause = self.extend_where_clause(&self.build_trait_bounds(patch_trait));
+
+        let input_struct_name = self.struct_name;
+        let field_enum = self.field_enum_name();
+        let field_mask = self.field_mask_name();
+        let patch_param_name = self.patch_param_name();
+        let mask_param_name = if self.field_actions.is_empty() {
+            quote! { _mask }
+        } else {
+            quote! { mask }
+        };
+
+        let (destructure, values) = self.build_patch_values();
+        let statements = self
+            .field_actions
+            .iter()
+            .zip(values)
+            .map(|(action, value)| {
+                let member = action.member();
+                let variant = member.variant();
+                let update =
+                    action.build_update_statement(patch_trait, &quote! { self.#member }, &value);
+                quote! {
+                    if #field_mask::contains(mask, #field_enum::#variant) {
+                        #update
+                    }
+                }
+            });
+        let after_patch = self.build_after_patch_call(&quote! { self });
+
+        Ok(quote! {
+            impl #impl_generics #crate_path::MaskedPatch
+                for #input_struct_name #type_generics
+            #where_clause {
+                type Mask = #field_mask;
+
+                #[inline(always)]
+                fn patch_masked(
+                    &mut self,
+                    #patch_param_name: Self::Patch,
+                    #mask_param_name: Self::Mask,
+                ) {
+                    #destructure
+                    #(#statements)*
+                    #after_patch
+                }
+            }
+        })
+    }
+
+    fn validate_masked_patch(&self) -> syn::Result<()> {
+        self.reject_unsupported("MaskedPatch")?;
+        if self.field_actions.len() > MAX_MASKED_FIELDS {
+            return Err(syn::Error::new_spanned(
+                self.struct_name,
+                format!("`MaskedPatch` supports at most {MAX_MASKED_FIELDS} non-skipped fields"),
+            ));
+        }
+        self.reject_partial("MaskedPatch")?;
+        Ok(())
+    }
+
+    fn field_enum_name(&self) -> Ident {
+        format_ident!("{}Field", self.struct_name)
+    }
+
+    fn field_mask_name(&self) -> Ident {
+        format_ident!("{}FieldMask", self.struct_name)
+    }
+}
@@ -0,0 +1,193 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Type, WherePredicate, parse_quote};
+
+use crate::context::snapshot_impl::strip_bounds;
+use crate::context::{FieldAction, MacroContext, collect_used_simple_types};
+
+impl<'a> MacroContext<'a> {
+    // ============================================================
+    // impl<T, ...> Merge3 for OriginalStruct<T, ...>
+    // ============================================================
+
+    pub(crate) fn build_merge3_trait_impl(&self) -> syn::Result<TokenStream2> {
+        self.validate_merge3()?;
+
+        let crate_path = &self.crate_path;
+        let merge3_trait = quote! { #crate_path::merge3::Merge3 };
+        let private = quote! { #crate_path::merge3::__private };
+        let field_path = quote! { #crate_path::partial::FieldPath };
+        let (impl_generics, type_generics, _) = self.generics.split_for_impl();
+        let where_clause = self.extend_where_clause(&self.build_merge3_trait_bounds());
+        let input_struct_name = self.struct_name;
+
+        let field_values = self
+            .field_actions
+            .iter()
+            .enumerate()
+            .map(|(patch_index, action)| {
+                let member = action.member();
+                let name = member.name();
+                let patch_member = member.patch_member(patch_index);
+                let path = quote! { &#field_path::new(#name, prefix) };
+                let (base, ours, theirs) = (
+                    quote! { &self.#member },
+                    quote! { &ours.#patch_member },
+                    quote! { &theirs.#patch_member },
+                );
+                let value = match action {
+                    FieldAction::Keep { .. } if self.partial => quote! {
+                        #private::keep_partial(merger, #path, #base, #ours, #theirs)
+                    },
+                    FieldAction::Keep { .. } => quote! {
+                        #private::keep(merger, #path, #base, #ours, #theirs)
+                    },
+                    FieldAction::Patch { ty, .. } if self.partial => quote! {
+                        #private::nested_partial::<#ty>(merger, #path, #base, #ours, #theirs)
+                    },
+                    FieldAction::Patch { ty, .. } => quote! {
+                        <#ty as #merge3_trait>::merge3_below(
+                            #base,
+                            #ours,
+                            #theirs,
+                            merger,
+                            ::core::option::Option::Some(#path),
+                        )
+                    },
+                    // Merge strategies and deltas combine the changes of both sides.
+                    _ => {
+                        let (first, later) = (
+                            quote! { ::core::clone::Clone::clone(#ours) },
+                            quote! { ::core::clone::Clone::clone(#theirs) },
+                        );
+                        let compose_trait = quote! { #crate_path::ComposePatch };
+                        if self.partial {
+                            let composed = action.build_compose_expr(
+                                &compose_trait,
+                                &quote! { first },
+                                &quote! { later },
+                            );
+                            quote! {
+                                #crate_path::partial::__private::compose(
+                                    #first,
+                                    #later,
+                                    |first, later| #composed,
+                                )
+                            }
+                        } else {
+                            action.build_compose_expr(&compose_trait, &first, &later)
+                        }
+                    }
+                };
+                quote! { #patch_member: #value }
+            });
+
+        // The patch struct is private to the `Patchable` derive, so it is named through an alias
+        // of the associated type, as for `Snapshot`.
+        let patchable_trait = &self.patchable_trait;
+        let alias_generics = strip_bounds(self.generics);
+        let (alias_params, alias_args, _) = alias_generics.split_for_impl();
+        let alias_turbofish = alias_args.as_turbofish();
+        let (ours_param_name, theirs_param_name, merger_param_name, prefix_param_name) =
+            if self.field_actions.is_empty() {
+                (
+                    quote! { _ours },
+                    quote! { _theirs },
+                    quote! { _merger },
+                    quote! { _prefix },
+                )
+            } else {
+                (
+                    quote! { ours },
+                    quote! { theirs },
+                    quote! { merger },
+                    quote! { prefix },
+                )
+            };
+
+        Ok(quote! {
+            impl #impl_generics #merge3_trait for #input_struct_name #type_generics
+            #where_clause {
+                fn merge3_below(
+                    &self,
+                    #ours_param_name: &Self::Patch,
+                    #theirs_param_name: &Self::Patch,
+                    #merger_param_name: &mut #private::Merger<'_>,
+                    #prefix_param_name: ::core::option::Option<&#field_path<'_>>,
+                ) -> Self::Patch {
+                    type __Merge3Patch #alias_params =
+                        <#input_struct_name #alias_args as #patchable_trait>::Patch;
+                    __Merge3Patch #alias_turbofish { #(#field_values),* }
+                }
+            }
+        })
+    }
+
+    fn validate_merge3(&self) -> syn::Result<()> {
+        self.reject_remote("Merge3")?;
+        if !self.generates_patch_struct() {
+            return Err(syn::Error::new_spanned(
+                self.struct_name,
+                "`Merge3` cannot be derived for a struct with a `transparent` or hand-written \
+                 patch type",
+            ));
+        }
+        if let Some(action) = self
+            .field_actions
+            .iter()
+            .find(|action| matches!(action, FieldAction::With { .. }))
+        {
+            return Err(syn::Error::new_spanned(
+                action.ty(),
+                "`Merge3` cannot be derived for a struct with `with` fields",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Requires `Merge3` for nested fields (and an empty patch in partial structs), the
+    /// comparison of plain fields, and cloning the patch values of other fields, when their
+    /// type uses a type parameter.
+    fn build_merge3_trait_bounds(&self) -> Vec<WherePredicate> {
+        let crate_path = &self.crate_path;
+        let patchable_trait = &self.patchable_trait;
+        let type_params: Vec<_> = self
+            .generics
+            .type_params()
+            .map(|param| &param.ident)
+            .collect();
+        let nested_bounds =
+            self.iter_patchable_type_params()
+                .flat_map(|ty| -> Vec<WherePredicate> {
+                    let mut bounds = vec![parse_quote! { #ty: #crate_path::merge3::Merge3 }];
+                    if self.partial {
+                        bounds.push(parse_quote! {
+                            <#ty as #patchable_trait>::Patch: ::core::default::Default
+                        });
+                    }
+                    bounds
+                });
+        let value_bounds = self
+            .field_actions
+            .iter()
+            .filter(|action| {
+                !matches!(action, FieldAction::Patch { .. })
+                    && collect_used_simple_types(action.ty())
+                        .iter()
+                        .any(|used| type_params.contains(used))
+            })
+            .map(|action| -> WherePredicate {
+                match action {
+                    FieldAction::Keep { ty, .. } => parse_quote! {
+                        #ty: ::core::cmp::PartialEq + ::core::clone::Clone
+                    },
+                    _ => {
+                        let field_ty = action.build_patch_field_type();
+                        let field_ty: Type = parse_quote! { #field_ty };
+                        parse_quote! { #field_ty: ::core::clone::Clone }
+                    }
+                }
+            });
+        nested_bounds.chain(value_bounds).collect()
+    }
+}
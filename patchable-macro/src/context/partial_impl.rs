@@ -0,0 +1,135 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Fields, WherePredicate, parse_quote};
+
+use crate::context::{FieldAction, MacroContext};
+
+impl<'a> MacroContext<'a> {
+    // ============================================================
+    // impl<T, ...> PartialPatch for OriginalStructPatch<T, ...>
+    // impl<T, ...> Default for OriginalStructPatch<T, ...>
+    // ============================================================
+
+    /// Builds the `PartialPatch` and `Default` implementations of the patch struct of a
+    /// `#[patchable(partial)]` struct, or nothing for other structs.
+    pub(crate) fn build_partial_patch_impls(&self) -> Option<TokenStream2> {
+        if !self.partial {
+            return None;
+        }
+
+        let crate_path = &self.crate_path;
+        let partial_trait = quote! { #crate_path::PartialPatch };
+        let field_path = quote! { #crate_path::partial::FieldPath };
+        let patchable_trait = &self.patchable_trait;
+        let patch_struct_type = &self.patch_struct_type;
+        let impl_generics: Vec<_> = self
+            .generics
+            .type_params()
+            .filter(|param| self.preserved_types.contains_key(&param.ident))
+            .map(|param| &param.ident)
+            .collect();
+
+        let struct_bounds = self.build_trait_bounds(patchable_trait);
+        let default_where_clause =
+            (!struct_bounds.is_empty()).then(|| quote! { where #(#struct_bounds),* });
+        let partial_bounds: Vec<WherePredicate> = self
+            .iter_patchable_type_params()
+            .flat_map(|ty| -> [WherePredicate; 2] {
+                [
+                    parse_quote! { #ty: #patchable_trait },
+                    parse_quote! { <#ty as #patchable_trait>::Patch: #partial_trait },
+                ]
+            })
+            .collect();
+        let partial_where_clause =
+            (!partial_bounds.is_empty()).then(|| quote! { where #(#partial_bounds),* });
+
+        let visits = self
+            .field_actions
+            .iter()
+            .enumerate()
+            .map(|(patch_index, action)| {
+                let member = action.member();
+                let patch_member = member.patch_member(patch_index);
+                let name = member.name();
+                match action {
+                    FieldAction::Patch { .. } => quote! {
+                        if let ::core::option::Option::Some(value) = &self.#patch_member {
+                            let path = #field_path::new(#name, parent);
+                            #partial_trait::visit_set_fields(
+                                value,
+                                ::core::option::Option::Some(&path),
+                                visit,
+                            );
+                        }
+                    },
+                    _ => quote! {
+                        if self.#patch_member.is_some() {
+                            visit(&#field_path::new(#name, parent));
+                        }
+                    },
+                }
+            });
+        let (parent_param_name, visit_param_name) = if self.field_actions.is_empty() {
+            (quote! { _parent }, quote! { _visit })
+        } else {
+            (quote! { parent }, quote! { visit })
+        };
+
+        let nones = self
+            .field_actions
+            .iter()
+            .map(|_| quote! { ::core::option::Option::None });
+        let default_body = match &self.fields {
+            Fields::Named(_) => {
+                let members = self.field_actions.iter().map(FieldAction::member);
+                quote! { Self { #(#members: #nones),* } }
+            }
+            Fields::Unnamed(_) => quote! { Self(#(#nones),*) },
+            Fields::Unit => quote! { Self },
+        };
+
+        Some(quote! {
+            #[automatically_derived]
+            impl <#(#impl_generics),*> #partial_trait for #patch_struct_type
+            #partial_where_clause {
+                fn visit_set_fields(
+                    &self,
+                    #parent_param_name: ::core::option::Option<&#field_path<'_>>,
+                    #visit_param_name: &mut dyn ::core::ops::FnMut(&#field_path<'_>),
+                ) {
+                    #(#visits)*
+                }
+            }
+
+            #[automatically_derived]
+            impl <#(#impl_generics),*> ::core::default::Default for #patch_struct_type
+            #default_where_clause {
+                #[inline(always)]
+                fn default() -> Self {
+                    #default_body
+                }
+            }
+        })
+    }
+
+    /// Wraps a field value of the patch struct in `Some` for a partial patch.
+    pub(super) fn wrap_partial_value(&self, value: TokenStream2) -> TokenStream2 {
+        if self.partial {
+            quote! { ::core::option::Option::Some(#value) }
+        } else {
+            value
+        }
+    }
+
+    /// Rejects `#[patchable(partial)]` for derives that need every field of the patch.
+    pub(crate) fn reject_partial(&self, derive_name: &str) -> syn::Result<()> {
+        if self.partial {
+            return Err(syn::Error::new_spanned(
+                self.struct_name,
+                format!("`{derive_name}` cannot be derived for a `partial` struct"),
+            ));
+        }
+        Ok(())
+    }
+}
@@ -1,7 +1,9 @@
 use proc_macro2::TokenStream as TokenStream2;
-use quote::quote;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{PathArguments, Type, WherePredicate, parse_quote};
 
-use crate::context::MacroContext;
+use crate::context::{FieldAction, FieldMember, MacroContext};
 
 impl<'a> MacroContext<'a> {
     // ============================================================
@@ -9,6 +11,10 @@ impl<'a> MacroContext<'a> {
     // ============================================================
 
     pub(crate) fn build_patch_trait_impl(&self) -> TokenStream2 {
+        if self.fallible {
+            return self.build_try_patch_trait_impl();
+        }
+
         let patch_trait = &self.patch_trait;
         let (impl_generics, type_generics, _) = self.generics.split_for_impl();
         let extra_trait_bounds = self.build_trait_bounds(patch_trait);
@@ -16,13 +22,9 @@ impl<'a> MacroContext<'a> {
 
         let input_struct_name = self.struct_name;
 
-        let patch_param_name = if self.field_actions.is_empty() {
-            quote! { _patch }
-        } else {
-            quote! { patch }
-        };
-
-        let patch_method_body = self.generate_patch_method_body();
+        let patch_param_name = self.patch_param_name();
+        let patch_method_body = self.build_patch_statements(|member| quote! { self.#member });
+        let after_patch = self.build_after_patch_call(&quote! { self });
         quote! {
             impl #impl_generics #patch_trait
                 for #input_struct_name #type_generics
@@ -30,20 +32,257 @@ impl<'a> MacroContext<'a> {
                 #[inline(always)]
                 fn patch(&mut self, #patch_param_name: Self::Patch) {
                     #patch_method_body
+                    #after_patch
+                }
+            }
+        }
+    }
+
+    // ============================================================
+    // impl<T, ...> TryPatch for OriginalStruct<T, ...
+    // ============================================================
+
+    /// Builds the `TryPatch` implementation used when a field is marked with
+    /// `#[patchable(delta = "checked")]` or the struct has an invariant.
+    ///
+    /// With `#[patchable(rollback)]`, the fields are patched in a closure, and a clone of `self`
+    /// taken beforehand is restored if it fails.
+    fn build_try_patch_trait_impl(&self) -> TokenStream2 {
+        let crate_path = &self.crate_path;
+        let (impl_generics, type_generics, _) = self.generics.split_for_impl();
+        let input_struct_name = self.struct_name;
+        let mut extra_trait_bounds = self.build_checked_trait_bounds();
+        if self.rollback {
+            extra_trait_bounds
+                .push(parse_quote! { #input_struct_name #type_generics: ::core::clone::Clone });
+        }
+        let where_clause = self.extend_where_clause(&extra_trait_bounds);
+
+        let patch_param_name = self.patch_param_name();
+        let patch_statements = self.build_patch_statements(|member| quote! { self.#member });
+        let after_patch = self.build_after_patch_call(&quote! { &mut *self });
+        let invariant_check = self.invariant.as_ref().map(|invariant| {
+            quote! {
+                #invariant(&*self)
+                    .map_err(|message| #crate_path::PatchError::Invariant { message })?;
+            }
+        });
+        let patch_method_body = if self.rollback {
+            quote! {
+                let previous = ::core::clone::Clone::clone(self);
+                let result = (|| -> ::core::result::Result<(), #crate_path::PatchError> {
+                    #patch_statements
+                    #after_patch
+                    #invariant_check
+                    ::core::result::Result::Ok(())
+                })();
+                if result.is_err() {
+                    *self = previous;
+                }
+                result
+            }
+        } else {
+            quote! {
+                #patch_statements
+                #after_patch
+                #invariant_check
+                ::core::result::Result::Ok(())
+            }
+        };
+        quote! {
+            impl #impl_generics #crate_path::TryPatch
+                for #input_struct_name #type_generics
+            #where_clause {
+                type Error = #crate_path::PatchError;
+
+                #[inline(always)]
+                fn try_patch(
+                    &mut self,
+                    #patch_param_name: Self::Patch,
+                ) -> ::core::result::Result<(), Self::Error> {
+                    #patch_method_body
                 }
             }
         }
     }
 
-    fn generate_patch_method_body(&self) -> TokenStream2 {
-        let statements = self
+    /// Bounds nested fields of a checked struct by `TryPatch`, with errors convertible into
+    /// `PatchError`.
+    pub(super) fn build_checked_trait_bounds(&self) -> Vec<WherePredicate> {
+        let crate_path = &self.crate_path;
+        self.iter_patchable_type_params()
+            .flat_map(|ty| -> [WherePredicate; 2] {
+                [
+                    parse_quote! { #ty: #crate_path::TryPatch },
+                    parse_quote! {
+                        #crate_path::PatchError:
+                            ::core::convert::From<<#ty as #crate_path::TryPatch>::Error>
+                    },
+                ]
+            })
+            .collect()
+    }
+
+    /// Builds the call to the `after_patch` hook with the patched value, if there is one.
+    pub(super) fn build_after_patch_call(&self, target: &TokenStream2) -> Option<TokenStream2> {
+        let after_patch = self.after_patch.as_ref()?;
+        Some(quote! { #after_patch(#target); })
+    }
+
+    pub(super) fn patch_param_name(&self) -> TokenStream2 {
+        if self.field_actions.is_empty() && self.custom_patch_type.is_none() {
+            quote! { _patch }
+        } else {
+            quote! { patch }
+        }
+    }
+
+    /// Builds the statements moving each field of `patch` into the place returned by `target`.
+    ///
+    /// In a checked struct, all checked deltas are computed before any field is modified, so an
+    /// overflow leaves the target untouched. Nested fields are then applied with `try_patch`.
+    pub(super) fn build_patch_statements<F>(&self, target: F) -> TokenStream2
+    where
+        F: Fn(&FieldMember<'a>) -> TokenStream2,
+    {
+        self.build_patch_statements_in(target, None)
+    }
+
+    /// Like [`Self::build_patch_statements`], applying nested fields with `patch_with` and the
+    /// `context` expression when one is given.
+    pub(super) fn build_patch_statements_in<F>(
+        &self,
+        target: F,
+        context: Option<&TokenStream2>,
+    ) -> TokenStream2
+    where
+        F: Fn(&FieldMember<'a>) -> TokenStream2,
+    {
+        let (destructure, values) = self.build_patch_values();
+
+        let crate_path = &self.crate_path;
+        let patch_error = quote! { #crate_path::PatchError };
+        let mut checked_statements = Vec::new();
+        let mut statements = Vec::with_capacity(self.field_actions.len());
+        for (action, value) in self.field_actions.iter().zip(values) {
+            let member = action.member();
+            let target = target(member);
+            // The field of a partial patch is bound to its value when it is set.
+            let (value, optional_value) = if self.partial {
+                let binding = member.binding("value");
+                (quote! { #binding }, Some(value))
+            } else {
+                (value, None)
+            };
+
+            let statement = if action.is_checked_delta() {
+                let binding = member.binding("checked");
+                let checked_value = action.build_checked_delta_expr(&patch_error, &target, &value);
+                match &optional_value {
+                    Some(optional_value) => {
+                        checked_statements.push(quote! {
+                            let #binding = match #optional_value {
+                                ::core::option::Option::Some(#value) => {
+                                    ::core::option::Option::Some(#checked_value)
+                                }
+                                ::core::option::Option::None => ::core::option::Option::None,
+                            };
+                        });
+                        statements.push(quote! {
+                            if let ::core::option::Option::Some(#binding) = #binding {
+                                #target = #binding;
+                            }
+                        });
+                        continue;
+                    }
+                    None => {
+                        checked_statements.push(quote! { let #binding = #checked_value; });
+                        quote! { #target = #binding; }
+                    }
+                }
+            } else if let (Some(context), FieldAction::Patch { .. }) = (context, action) {
+                quote! {
+                    #crate_path::PatchWithContext::patch_with(&mut #target, #value, #context);
+                }
+            } else if self.fallible && matches!(action, FieldAction::Patch { .. }) {
+                quote! {
+                    #crate_path::TryPatch::try_patch(&mut #target, #value)?;
+                }
+            } else {
+                action.build_update_statement(&self.patch_trait, &target, &value)
+            };
+            statements.push(match optional_value {
+                Some(optional_value) => quote! {
+                    if let ::core::option::Option::Some(#value) = #optional_value {
+                        #statement
+                    }
+                },
+                None => statement,
+            });
+        }
+
+        quote! {
+            #destructure
+            #(#checked_statements)*
+            #(#statements)*
+        }
+    }
+
+    /// Builds the expression of each field value in `patch`, in the order of the field actions.
+    ///
+    /// A hand-written patch is destructured first, by the returned statement.
+    pub(super) fn build_patch_values(&self) -> (Option<TokenStream2>, Vec<TokenStream2>) {
+        let destructure = self
+            .custom_patch_type
+            .as_ref()
+            .map(|patch_type| self.build_custom_patch_destructure(patch_type));
+        let values = self
+            .field_actions
+            .iter()
+            .enumerate()
+            .map(|(patch_index, action)| {
+                let member = action.member();
+                if self.transparent {
+                    quote! { patch }
+                } else if destructure.is_some() {
+                    let binding = member.binding("patch");
+                    quote! { #binding }
+                } else {
+                    let patch_member = member.patch_member(patch_index);
+                    quote! { patch.#patch_member }
+                }
+            })
+            .collect();
+        (destructure, values)
+    }
+
+    /// Destructures a hand-written patch into one binding per field.
+    ///
+    /// The pattern ends with a `..` rest: the field sets of the struct and the patch type are
+    /// compared by the `Patchable` derive, with
+    /// [`build_field_set_checks`](Self::build_field_set_checks).
+    fn build_custom_patch_destructure(&self, patch_type: &Type) -> TokenStream2 {
+        let mut patch_path = match patch_type {
+            Type::Path(type_path) => type_path.path.clone(),
+            _ => unreachable!("validated when building the context"),
+        };
+        if let Some(last_segment) = patch_path.segments.last_mut() {
+            last_segment.arguments = PathArguments::None;
+        }
+
+        let bound_fields = self
             .field_actions
             .iter()
             .enumerate()
             .map(|(patch_index, action)| {
-                action.build_update_statement(&self.patch_trait, patch_index)
+                let member = action.member();
+                let patch_member = member.patch_member(patch_index);
+                let binding = member.binding("patch");
+                quote! { #patch_member: #binding }
             });
 
-        quote! { #(#statements)* }
+        quote_spanned! {patch_type.span()=>
+            let #patch_path { #(#bound_fields,)* .. } = patch;
+        }
     }
 }
@@ -1,8 +1,9 @@
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn::Fields;
+use syn::{Fields, LitStr};
 
-use crate::{IS_SERDE_ENABLED, context::MacroContext};
+use crate::IS_SERDE_ENABLED;
+use crate::context::{FieldAction, MacroContext, collect_used_simple_types};
 
 impl<'a> MacroContext<'a> {
     // ============================================================
@@ -20,7 +21,11 @@ impl<'a> MacroContext<'a> {
         } else {
             quote! { where #(#bounded_types),* }
         };
-        let patch_fields = self.field_actions.iter().map(|action| action.build_field());
+        let patch_fields = self.field_actions.iter().map(|action| {
+            let attrs =
+                (self.partial && IS_SERDE_ENABLED).then(|| self.build_partial_serde_attr(action));
+            action.build_field(self.partial, attrs)
+        });
         let body = match &self.fields {
             Fields::Named(_) => quote! { #where_clause { #(#patch_fields),* } },
             Fields::Unnamed(_) => quote! { ( #(#patch_fields),* ) #where_clause; },
@@ -32,4 +37,34 @@ impl<'a> MacroContext<'a> {
             pub struct #patch_struct_type #body
         }
     }
+
+    /// Builds the serde attribute of a field of a partial patch, which is `None` when missing
+    /// and `Some` when present, even if its value is `null`.
+    ///
+    /// Since `deserialize_with` disables the inferred bound of the field, it is restated for
+    /// field types using a type parameter.
+    fn build_partial_serde_attr(&self, action: &FieldAction<'a>) -> TokenStream2 {
+        let crate_path = &self.crate_path;
+        let deserialize_with = LitStr::new(
+            &quote! { #crate_path::partial::__private::deserialize_some }.to_string(),
+            proc_macro2::Span::call_site(),
+        );
+        let type_params: Vec<_> = self
+            .generics
+            .type_params()
+            .map(|param| &param.ident)
+            .collect();
+        let uses_type_param = collect_used_simple_types(action.ty())
+            .iter()
+            .any(|used| type_params.contains(used));
+        let bound = uses_type_param.then(|| {
+            let field_ty = action.build_patch_field_type();
+            let bound = LitStr::new(
+                &quote! { #field_ty: ::serde::Deserialize<'de> }.to_string(),
+                proc_macro2::Span::call_site(),
+            );
+            quote! { , bound(deserialize = #bound) }
+        });
+        quote! { #[serde(default, deserialize_with = #deserialize_with #bound)] }
+    }
 }
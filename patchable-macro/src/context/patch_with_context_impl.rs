@@ -0,0 +1,104 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{GenericParam, Type, WherePredicate, parse_quote};
+
+use crate::context::MacroContext;
+
+impl<'a> MacroContext<'a> {
+    // ============================================================
+    // impl<T, ...> PatchWithContext<Ctx> for OriginalStruct<T, ...
+    // ============================================================
+
+    /// Builds the `PatchWithContext` implementation.
+    ///
+    /// With `#[patchable(context = Type)]`, it is implemented for that context only. Otherwise it
+    /// is implemented for any context, so the struct can be nested in a struct with a context.
+    pub(crate) fn build_patch_with_context_trait_impl(&self) -> syn::Result<TokenStream2> {
+        self.validate_patch_with_context()?;
+
+        let crate_path = &self.crate_path;
+        let mut generics = self.generics.clone();
+        let context_type: Type = match &self.context {
+            Some(context) => context.clone(),
+            None => {
+                generics
+                    .params
+                    .push(parse_quote! { __Ctx: ?::core::marker::Sized });
+                parse_quote! { __Ctx }
+            }
+        };
+        let (impl_generics, _, _) = generics.split_for_impl();
+        let (_, type_generics, _) = self.generics.split_for_impl();
+        let trait_bounds: Vec<WherePredicate> = self
+            .iter_patchable_type_params()
+            .map(|ty| parse_quote! { #ty: #crate_path::PatchWithContext<#context_type> })
+            .collect();
+        let where_clause = self.extend_where_clause(&trait_bounds);
+
+        let input_struct_name = self.struct_name;
+        let patch_param_name = self.patch_param_name();
+        let uses_context = self.iter_patchable_type_params().next().is_some()
+            || self
+                .skipped_fields
+                .iter()
+                .any(|skipped| skipped.rehydrate.is_some());
+        let context_param_name = if uses_context {
+            quote! { ctx }
+        } else {
+            quote! { _ctx }
+        };
+
+        let patch_statements =
+            self.build_patch_statements_in(|member| quote! { self.#member }, Some(&quote! { ctx }));
+        let rehydrate_statements = self.skipped_fields.iter().filter_map(|skipped| {
+            let rehydrate = skipped.rehydrate.as_ref()?;
+            let member = &skipped.member;
+            Some(quote! { #rehydrate(&mut self.#member, ctx); })
+        });
+        let after_patch = self.build_after_patch_call(&quote! { self });
+
+        Ok(quote! {
+            impl #impl_generics #crate_path::PatchWithContext<#context_type>
+                for #input_struct_name #type_generics
+            #where_clause {
+                #[inline(always)]
+                fn patch_with(
+                    &mut self,
+                    #patch_param_name: Self::Patch,
+                    #context_param_name: &#context_type,
+                ) {
+                    #patch_statements
+                    #(#rehydrate_statements)*
+                    #after_patch
+                }
+            }
+        })
+    }
+
+    fn validate_patch_with_context(&self) -> syn::Result<()> {
+        self.reject_unsupported("PatchWithContext")?;
+        if self.context.is_none()
+            && let Some(rehydrate) = self
+                .skipped_fields
+                .iter()
+                .find_map(|skipped| skipped.rehydrate.as_ref())
+        {
+            return Err(syn::Error::new_spanned(
+                rehydrate,
+                "`rehydrate` requires a `#[patchable(context = Type)]` container attribute",
+            ));
+        }
+        if self
+            .generics
+            .params
+            .iter()
+            .any(|param| matches!(param, GenericParam::Type(param) if param.ident == "__Ctx"))
+        {
+            return Err(syn::Error::new_spanned(
+                self.generics,
+                "the type parameter name `__Ctx` is reserved",
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,274 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Type, WherePredicate, parse_quote};
+
+use crate::context::{FieldAction, MacroContext, collect_used_simple_types};
+
+impl<'a> MacroContext<'a> {
+    // ============================================================
+    // impl<T, ...> PatchPath for OriginalStruct<T, ...
+    // ============================================================
+
+    pub(crate) fn build_patch_path_trait_impl(&self) -> syn::Result<TokenStream2> {
+        self.reject_unsupported("PatchPath")?;
+
+        let crate_path = &self.crate_path;
+        let (impl_generics, type_generics, _) = self.generics.split_for_impl();
+        let where_clause = self.extend_where_clause(&self.build_patch_path_trait_bounds());
+        let input_struct_name = self.struct_name;
+        let private = quote! { #crate_path::path::__private };
+        let path_error = quote! { #crate_path::path::PathError };
+        let after_patch = self.build_after_patch_call(&quote! { self });
+        let deserialize_patch = quote! {
+            #private::deserialize_patch(self, deserializer)
+        };
+        let not_found = quote! {
+            ::core::result::Result::Err(#path_error::NotFound)
+        };
+
+        let (has_body, serialize_body, deserialize_body) = match self.field_actions.as_slice() {
+            [] => (
+                quote! { path.is_none() },
+                quote! {
+                    match path {
+                        ::core::option::Option::None => {
+                            #private::SerializeMap::end(serializer.serialize_map(
+                                ::core::option::Option::Some(0),
+                            )?)
+                        }
+                        ::core::option::Option::Some(_) => ::core::result::Result::Err(
+                            <__S::Error as #private::Error>::custom("no field at this path"),
+                        ),
+                    }
+                },
+                quote! {
+                    match path {
+                        ::core::option::Option::None => #deserialize_patch,
+                        ::core::option::Option::Some(_) => #not_found,
+                    }
+                },
+            ),
+            // A transparent struct is addressed as its field.
+            [action] if self.transparent => {
+                let member = action.member();
+                match action {
+                    FieldAction::Patch { ty, .. } => (
+                        quote! { <#ty as #crate_path::PatchPath>::has_path(path) },
+                        quote! {
+                            #crate_path::PatchPath::serialize_path(&self.#member, path, serializer)
+                        },
+                        quote! {
+                            let ::core::option::Option::Some(path) = path else {
+                                return #deserialize_patch;
+                            };
+                            #crate_path::PatchPath::deserialize_path(
+                                &mut self.#member,
+                                ::core::option::Option::Some(path),
+                                deserializer,
+                            )?;
+                            #after_patch
+                            ::core::result::Result::Ok(())
+                        },
+                    ),
+                    _ => (
+                        quote! { path.is_none() },
+                        quote! {
+                            match path {
+                                ::core::option::Option::None => {
+                                    #private::Serialize::serialize(&self.#member, serializer)
+                                }
+                                ::core::option::Option::Some(_) => ::core::result::Result::Err(
+                                    <__S::Error as #private::Error>::custom(
+                                        "no field at this path",
+                                    ),
+                                ),
+                            }
+                        },
+                        quote! {
+                            match path {
+                                ::core::option::Option::None => #deserialize_patch,
+                                ::core::option::Option::Some(_) => #not_found,
+                            }
+                        },
+                    ),
+                }
+            }
+            _ => self.build_path_dispatch(&deserialize_patch, &not_found),
+        };
+
+        Ok(quote! {
+            impl #impl_generics #crate_path::PatchPath for #input_struct_name #type_generics
+            #where_clause {
+                fn has_path(path: ::core::option::Option<&str>) -> bool {
+                    #has_body
+                }
+
+                fn serialize_path<__S: #private::Serializer>(
+                    &self,
+                    path: ::core::option::Option<&str>,
+                    serializer: __S,
+                ) -> ::core::result::Result<__S::Ok, __S::Error> {
+                    #serialize_body
+                }
+
+                fn deserialize_path<'__de, __D: #private::Deserializer<'__de>>(
+                    &mut self,
+                    path: ::core::option::Option<&str>,
+                    deserializer: __D,
+                ) -> ::core::result::Result<(), #path_error<__D::Error>> {
+                    #deserialize_body
+                }
+            }
+        })
+    }
+
+    /// Builds the bodies of the path methods of a struct, which match the first segment of the
+    /// path against the names of the fields.
+    ///
+    /// An empty path addresses the struct itself, serialized as a map with an entry per field.
+    fn build_path_dispatch(
+        &self,
+        deserialize_patch: &TokenStream2,
+        not_found: &TokenStream2,
+    ) -> (TokenStream2, TokenStream2, TokenStream2) {
+        let crate_path = &self.crate_path;
+        let private = quote! { #crate_path::path::__private };
+        let path_error = quote! { #crate_path::path::PathError };
+        let after_patch = self.build_after_patch_call(&quote! { self });
+        let keys: Vec<_> = self
+            .field_actions
+            .iter()
+            .map(|action| action.member().name())
+            .collect();
+        let field_count = keys.len();
+
+        let has_arms = self.field_actions.iter().map(|action| match action {
+            FieldAction::Patch { ty, .. } => quote! {
+                <#ty as #crate_path::PatchPath>::has_path(rest)
+            },
+            _ => quote! { rest.is_none() },
+        });
+        let has_body = quote! {
+            let ::core::option::Option::Some(path) = path else {
+                return true;
+            };
+            let (key, rest) = #private::split(path);
+            match key {
+                #(#keys => #has_arms,)*
+                _ => false,
+            }
+        };
+
+        let entries = self.field_actions.iter().map(|action| {
+            let member = action.member();
+            match action {
+                FieldAction::Patch { .. } => quote! {
+                    &#crate_path::path::PathValue::whole(&self.#member)
+                },
+                _ => quote! { &self.#member },
+            }
+        });
+        let serialize_arms = self.field_actions.iter().map(|action| {
+            let member = action.member();
+            match action {
+                FieldAction::Patch { .. } => quote! {
+                    #crate_path::PatchPath::serialize_path(&self.#member, rest, serializer)
+                },
+                _ => quote! {
+                    if rest.is_none() {
+                        #private::Serialize::serialize(&self.#member, serializer)
+                    } else {
+                        not_found
+                    }
+                },
+            }
+        });
+        let serialize_body = quote! {
+            let ::core::option::Option::Some(path) = path else {
+                let mut map = serializer.serialize_map(
+                    ::core::option::Option::Some(#field_count),
+                )?;
+                #(#private::SerializeMap::serialize_entry(&mut map, #keys, #entries)?;)*
+                return #private::SerializeMap::end(map);
+            };
+            let not_found = ::core::result::Result::Err(
+                <__S::Error as #private::Error>::custom("no field at this path"),
+            );
+            let (key, rest) = #private::split(path);
+            match key {
+                #(#keys => #serialize_arms,)*
+                _ => not_found,
+            }
+        };
+
+        let deserialize_arms = self.field_actions.iter().map(|action| {
+            let member = action.member();
+            match action {
+                FieldAction::Patch { .. } => quote! {
+                    #crate_path::PatchPath::deserialize_path(
+                        &mut self.#member,
+                        rest,
+                        deserializer,
+                    )?
+                },
+                _ => quote! {
+                    {
+                        if rest.is_some() {
+                            return #not_found;
+                        }
+                        self.#member = #private::Deserialize::deserialize(deserializer)
+                            .map_err(#path_error::Value)?;
+                    }
+                },
+            }
+        });
+        let deserialize_body = quote! {
+            let ::core::option::Option::Some(path) = path else {
+                return #deserialize_patch;
+            };
+            let (key, rest) = #private::split(path);
+            match key {
+                #(#keys => #deserialize_arms,)*
+                _ => return #not_found,
+            }
+            #after_patch
+            ::core::result::Result::Ok(())
+        };
+
+        (has_body, serialize_body, deserialize_body)
+    }
+
+    /// Requires `PatchPath` for nested fields, `Serialize` and `DeserializeOwned` for other
+    /// fields whose type uses a type parameter, and `DeserializeOwned` for the patch type.
+    fn build_patch_path_trait_bounds(&self) -> Vec<WherePredicate> {
+        let crate_path = &self.crate_path;
+        let private = quote! { #crate_path::path::__private };
+        let type_params: Vec<_> = self
+            .generics
+            .type_params()
+            .map(|param| &param.ident)
+            .collect();
+        let nested_bounds = self.build_trait_bounds(&quote! { #crate_path::PatchPath });
+        let value_bounds = self
+            .field_actions
+            .iter()
+            .filter(|action| {
+                !matches!(action, FieldAction::Patch { .. })
+                    && collect_used_simple_types(action.ty())
+                        .iter()
+                        .any(|used| type_params.contains(used))
+            })
+            .map(|action| -> WherePredicate {
+                let ty: &Type = action.ty();
+                parse_quote! { #ty: #private::Serialize + #private::DeserializeOwned }
+            });
+        let patch_bound: WherePredicate = parse_quote! {
+            <Self as #crate_path::Patchable>::Patch: #private::DeserializeOwned
+        };
+        nested_bounds
+            .into_iter()
+            .chain(value_bounds)
+            .chain([patch_bound])
+            .collect()
+    }
+}
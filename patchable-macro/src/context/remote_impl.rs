@@ -0,0 +1,171 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{ToTokens, format_ident, quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{Ident, Path, PathArguments, Type};
+
+use crate::context::MacroContext;
+
+impl<'a> MacroContext<'a> {
+    // ============================================================
+    // impl<T, ...> OriginalStruct<T, ...> {
+    //     fn patch(remote: &mut RemoteStruct<T, ...>, patch: ...) { ... }
+    // }
+    // ============================================================
+
+    pub(crate) fn build_remote_patch_fn(&self, remote: &Path) -> TokenStream2 {
+        let patch_trait = &self.patch_trait;
+        let patchable_trait = &self.patchable_trait;
+        let (impl_generics, type_generics, _) = self.generics.split_for_impl();
+        let extra_trait_bounds = if self.fallible {
+            self.build_checked_trait_bounds()
+        } else {
+            self.build_trait_bounds(patch_trait)
+        };
+        let where_clause = self.extend_where_clause(&extra_trait_bounds);
+
+        let input_struct_name = self.struct_name;
+        let visibility = self.visibility;
+        let remote_pattern = self.build_remote_pattern(remote);
+
+        let patch_param_name = self.patch_param_name();
+        let statements = self.build_patch_statements(|member| {
+            let binding = member.binding("field");
+            quote! { *#binding }
+        });
+
+        let crate_path = &self.crate_path;
+        let (return_type, return_value) = if self.fallible {
+            (
+                quote! { -> ::core::result::Result<(), #crate_path::PatchError> },
+                quote! { ::core::result::Result::Ok(()) },
+            )
+        } else {
+            (quote! {}, quote! {})
+        };
+
+        quote! {
+            impl #impl_generics #input_struct_name #type_generics
+            #where_clause {
+                /// Applies `patch` to the remote value, field by field.
+                #[inline(always)]
+                #visibility fn patch(
+                    remote: &mut #remote,
+                    #patch_param_name: <Self as #patchable_trait>::Patch,
+                ) #return_type {
+                    let #remote_pattern = remote;
+                    #statements
+                    #return_value
+                }
+            }
+        }
+    }
+
+    /// Builds a function that reads every field of the definition struct.
+    ///
+    /// A remote definition is never constructed, so without it every field would trigger a
+    /// `dead_code` warning.
+    pub(crate) fn build_remote_field_reader(&self) -> TokenStream2 {
+        let (impl_generics, type_generics, where_clause) = self.generics.split_for_impl();
+        let input_struct_name = self.struct_name;
+        let members = self
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| Self::field_member(field, index));
+
+        quote! {
+            #[allow(dead_code)]
+            fn read_remote_definition_fields #impl_generics (
+                definition: &#input_struct_name #type_generics,
+            ) #where_clause {
+                let _ = (#(&definition.#members,)*);
+            }
+        }
+    }
+
+    /// Builds the functions checking that the remote type and the hand-written patch type have
+    /// exactly the fields of the definition struct, when it has either of them.
+    ///
+    /// Each check builds the type with a struct literal, which must set every field: a field the
+    /// type lacks is reported at that field of the struct, and a field the struct lacks is
+    /// reported at the attribute naming the type.
+    pub(crate) fn build_field_set_checks(&self) -> TokenStream2 {
+        let remote_check = self.remote.as_ref().map(|remote| {
+            let members = self
+                .fields
+                .iter()
+                .enumerate()
+                .map(|(index, field)| Self::field_member(field, index).to_token_stream());
+            self.build_field_set_check(
+                &format_ident!("check_remote_fields"),
+                remote,
+                members.collect(),
+            )
+        });
+        let patch_type_check = self.custom_patch_type.as_ref().map(|patch_type| {
+            let Type::Path(type_path) = patch_type else {
+                unreachable!("validated when building the context")
+            };
+            let members = self
+                .field_actions
+                .iter()
+                .enumerate()
+                .map(|(patch_index, action)| action.member().patch_member(patch_index));
+            self.build_field_set_check(
+                &format_ident!("check_patch_type_fields"),
+                &type_path.path,
+                members.collect(),
+            )
+        });
+
+        quote! {
+            #remote_check
+            #patch_type_check
+        }
+    }
+
+    fn build_field_set_check(
+        &self,
+        name: &Ident,
+        target: &Path,
+        members: Vec<TokenStream2>,
+    ) -> TokenStream2 {
+        let (impl_generics, _, _) = self.generics.split_for_impl();
+        let where_clause =
+            self.extend_where_clause(&self.build_trait_bounds(&self.patchable_trait));
+        let mut target_path = target.clone();
+        if let Some(last_segment) = target_path.segments.last_mut() {
+            last_segment.arguments = PathArguments::None;
+        }
+        let literal_path = quote_spanned! {target.span()=> #target_path };
+
+        quote! {
+            #[allow(dead_code, unreachable_code)]
+            fn #name #impl_generics () -> #target #where_clause {
+                #literal_path { #(#members: ::core::unreachable!(),)* }
+            }
+        }
+    }
+
+    /// Builds a pattern that destructures the fields of the remote type patched by this
+    /// definition.
+    ///
+    /// The pattern ends with a `..` rest: a field missing from the definition is reported once,
+    /// by [`build_field_set_checks`](Self::build_field_set_checks).
+    pub(super) fn build_remote_pattern(&self, remote: &Path) -> TokenStream2 {
+        let mut remote_path = remote.clone();
+        if let Some(last_segment) = remote_path.segments.last_mut() {
+            last_segment.arguments = PathArguments::None;
+        }
+
+        let bound_fields = self.field_actions.iter().map(|action| {
+            let member = action.member();
+            let binding = member.binding("field");
+            quote! { #member: #binding }
+        });
+
+        quote_spanned! {remote.span()=>
+            #remote_path { #(#bound_fields,)* .. }
+        }
+    }
+}
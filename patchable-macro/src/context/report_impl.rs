@@ -0,0 +1,293 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{Fields, Type, WherePredicate, parse_quote};
+
+use crate::context::{FieldAction, MacroContext, collect_used_simple_types};
+
+impl<'a> MacroContext<'a> {
+    // ============================================================
+    // pub struct OriginalStructChanges<T, ...> { ... }
+    // ============================================================
+
+    /// Builds the set of changed fields, with a flag per plain field and the report of each
+    /// nested field.
+    ///
+    /// `Clone`, `Debug` and friends are implemented by hand, since derives would require them
+    /// from the nested type parameters instead of their changes.
+    pub(crate) fn build_changes_struct(&self) -> TokenStream2 {
+        let crate_path = &self.crate_path;
+        let visibility = self.visibility;
+        let changes_struct_name = self.changes_struct_name();
+        let changes_struct_ident = format_ident!("{}", changes_struct_name);
+        let changes_struct_type = self.build_changes_struct_type();
+        let params: Vec<_> = self.iter_patchable_type_params().collect();
+        let generics = quote! { <#(#params),*> };
+        let where_clause = if params.is_empty() {
+            quote! {}
+        } else {
+            quote! { where #(#params: #crate_path::ReportPatch),* }
+        };
+        let doc = format!(
+            "The fields of [`{}`] changed by a patch, reported by `ReportPatch`.",
+            self.struct_name
+        );
+
+        let members = self.build_changes_members();
+        let field_types = self.field_actions.iter().map(|action| match action {
+            FieldAction::Patch { ty, .. } => quote! { #crate_path::PatchReport<#ty> },
+            _ => quote! { bool },
+        });
+        let (body, debug_body) = match &self.fields {
+            Fields::Named(_) => {
+                let names = self
+                    .field_actions
+                    .iter()
+                    .map(|action| action.member().name());
+                (
+                    quote! { #where_clause { #(pub #members: #field_types),* } },
+                    quote! {
+                        f.debug_struct(#changes_struct_name)
+                            #(.field(#names, &self.#members))*
+                            .finish()
+                    },
+                )
+            }
+            Fields::Unnamed(_) => (
+                quote! { ( #(pub #field_types),* ) #where_clause; },
+                quote! {
+                    f.debug_tuple(#changes_struct_name)
+                        #(.field(&self.#members))*
+                        .finish()
+                },
+            ),
+            Fields::Unit => (quote! {;}, quote! { f.write_str(#changes_struct_name) }),
+        };
+        let is_changed =
+            self.field_actions
+                .iter()
+                .zip(&members)
+                .map(|(action, member)| match action {
+                    FieldAction::Patch { .. } => {
+                        quote! { #crate_path::ChangeSet::is_changed(&self.#member) }
+                    }
+                    _ => quote! { self.#member },
+                });
+
+        quote! {
+            #[doc = #doc]
+            #visibility struct #changes_struct_type #body
+
+            const _: () = {
+                #[automatically_derived]
+                impl #generics ::core::clone::Clone for #changes_struct_type #where_clause {
+                    #[inline(always)]
+                    fn clone(&self) -> Self {
+                        *self
+                    }
+                }
+
+                #[automatically_derived]
+                impl #generics ::core::marker::Copy for #changes_struct_type #where_clause {}
+
+                #[automatically_derived]
+                impl #generics ::core::default::Default for #changes_struct_type #where_clause {
+                    #[inline(always)]
+                    fn default() -> Self {
+                        #changes_struct_ident {
+                            #(#members: ::core::default::Default::default(),)*
+                        }
+                    }
+                }
+
+                #[automatically_derived]
+                impl #generics ::core::cmp::PartialEq for #changes_struct_type #where_clause {
+                    #[inline(always)]
+                    fn eq(&self, _other: &Self) -> bool {
+                        true #(&& self.#members == _other.#members)*
+                    }
+                }
+
+                #[automatically_derived]
+                impl #generics ::core::cmp::Eq for #changes_struct_type #where_clause {}
+
+                #[automatically_derived]
+                impl #generics ::core::fmt::Debug for #changes_struct_type #where_clause {
+                    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        #debug_body
+                    }
+                }
+
+                #[automatically_derived]
+                impl #generics #crate_path::ChangeSet for #changes_struct_type #where_clause {
+                    #[inline(always)]
+                    fn is_changed(&self) -> bool {
+                        false #(|| #is_changed)*
+                    }
+                }
+            };
+        }
+    }
+
+    // ============================================================
+    // impl<T, ...> ReportPatch for OriginalStruct<T, ...
+    // ============================================================
+
+    pub(crate) fn build_report_patch_trait_impl(&self) -> syn::Result<TokenStream2> {
+        self.validate_report_patch()?;
+
+        let crate_path = &self.crate_path;
+        let (impl_generics, type_generics, _) = self.generics.split_for_impl();
+        let where_clause = self.extend_where_clause(&self.build_report_patch_trait_bounds());
+
+        let input_struct_name = self.struct_name;
+        let changes_struct_type = self.build_changes_struct_type();
+        let changes_struct_name = format_ident!("{}", self.changes_struct_name());
+        let patch_param_name = self.patch_param_name();
+        let private = quote! { #crate_path::report::__private };
+
+        let (destructure, values) = self.build_patch_values();
+        let statements = self
+            .field_actions
+            .iter()
+            .zip(values)
+            .map(|(action, value)| {
+                let member = action.member();
+                let changed = member.binding("changed");
+                // The field of a partial patch is bound to its value when it is set, and reported
+                // as unchanged otherwise.
+                let (value, optional_value) = if self.partial {
+                    let binding = member.binding("value");
+                    (quote! { #binding }, Some(value))
+                } else {
+                    (value, None)
+                };
+                let statement = match action {
+                    FieldAction::Keep { .. } => quote! {
+                        let #changed = (&#private::Probe(&self.#member)).differs(&#value);
+                        self.#member = #value;
+                    },
+                    FieldAction::Patch { .. } => quote! {
+                        let #changed =
+                            #crate_path::ReportPatch::patch_report(&mut self.#member, #value);
+                    },
+                    _ => {
+                        let previous = member.binding("previous");
+                        let update = action.build_update_statement(
+                            &self.patch_trait,
+                            &quote! { self.#member },
+                            &value,
+                        );
+                        quote! {
+                            let #previous = (&#private::Probe(&self.#member)).previous();
+                            #update
+                            let #changed = match #previous {
+                                ::core::option::Option::Some(previous) => {
+                                    (&#private::Probe(&previous)).differs(&self.#member)
+                                }
+                                ::core::option::Option::None => true,
+                            };
+                        }
+                    }
+                };
+                match optional_value {
+                    Some(optional_value) => quote! {
+                        let #changed = match #optional_value {
+                            ::core::option::Option::Some(#value) => {
+                                #statement
+                                #changed
+                            }
+                            ::core::option::Option::None => ::core::default::Default::default(),
+                        };
+                    },
+                    None => statement,
+                }
+            });
+        let changes = self
+            .field_actions
+            .iter()
+            .zip(self.build_changes_members())
+            .map(|(action, changes_member)| {
+                let changed = action.member().binding("changed");
+                quote! { #changes_member: #changed }
+            });
+        let after_patch = self.build_after_patch_call(&quote! { self });
+
+        Ok(quote! {
+            impl #impl_generics #crate_path::ReportPatch
+                for #input_struct_name #type_generics
+            #where_clause {
+                type Changes = #changes_struct_type;
+
+                #[inline(always)]
+                fn patch_report(
+                    &mut self,
+                    #patch_param_name: Self::Patch,
+                ) -> #crate_path::PatchReport<Self> {
+                    #[allow(unused_imports)]
+                    use #private::{AssumeChanged as _, Compare as _, DropPrevious as _, KeepPrevious as _};
+
+                    #destructure
+                    #(#statements)*
+                    #after_patch
+                    #crate_path::PatchReport::new(#changes_struct_name { #(#changes),* })
+                }
+            }
+        })
+    }
+
+    fn validate_report_patch(&self) -> syn::Result<()> {
+        self.reject_unsupported("ReportPatch")?;
+        Ok(())
+    }
+
+    /// Requires `ReportPatch` for nested fields, and the comparisons used by the report for other
+    /// fields whose type uses a type parameter.
+    fn build_report_patch_trait_bounds(&self) -> Vec<WherePredicate> {
+        let crate_path = &self.crate_path;
+        let type_params: Vec<_> = self
+            .generics
+            .type_params()
+            .map(|param| &param.ident)
+            .collect();
+        let nested_bounds = self
+            .iter_patchable_type_params()
+            .map(|ty| parse_quote! { #ty: #crate_path::ReportPatch });
+        let comparison_bounds = self
+            .field_actions
+            .iter()
+            .filter(|action| {
+                !matches!(action, FieldAction::Patch { .. })
+                    && collect_used_simple_types(action.ty())
+                        .iter()
+                        .any(|used| type_params.contains(used))
+            })
+            .map(|action| -> WherePredicate {
+                let ty: &Type = action.ty();
+                match action {
+                    FieldAction::Keep { .. } => parse_quote! { #ty: ::core::cmp::PartialEq },
+                    _ => parse_quote! { #ty: ::core::cmp::PartialEq + ::core::clone::Clone },
+                }
+            });
+        nested_bounds.chain(comparison_bounds).collect()
+    }
+
+    /// The members of the changes struct, which has no slot for skipped fields.
+    fn build_changes_members(&self) -> Vec<TokenStream2> {
+        self.field_actions
+            .iter()
+            .enumerate()
+            .map(|(index, action)| action.member().patch_member(index))
+            .collect()
+    }
+
+    fn changes_struct_name(&self) -> String {
+        format!("{}Changes", self.struct_name)
+    }
+
+    /// The changes struct type, with the type parameters of the nested fields.
+    fn build_changes_struct_type(&self) -> TokenStream2 {
+        let changes_struct_name = format_ident!("{}", self.changes_struct_name());
+        let params = self.iter_patchable_type_params();
+        quote! { #changes_struct_name <#(#params),*> }
+    }
+}
@@ -0,0 +1,160 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{GenericParam, Generics, Path, Type, WherePredicate, parse_quote};
+
+use crate::context::{FieldAction, FieldMember, MacroContext, collect_used_simple_types};
+
+impl<'a> MacroContext<'a> {
+    // ============================================================
+    // impl<T, ...> Snapshot for OriginalStruct<T, ...
+    // ============================================================
+
+    pub(crate) fn build_snapshot_trait_impl(&self) -> TokenStream2 {
+        let crate_path = &self.crate_path;
+        let (impl_generics, type_generics, _) = self.generics.split_for_impl();
+        let where_clause = self.extend_where_clause(&self.build_snapshot_trait_bounds());
+
+        let input_struct_name = self.struct_name;
+        let snapshot_body = self.build_snapshot_body(|member| quote! { self.#member });
+
+        quote! {
+            impl #impl_generics #crate_path::Snapshot
+                for #input_struct_name #type_generics
+            #where_clause {
+                #[inline(always)]
+                fn snapshot(&self) -> Self::Patch {
+                    #snapshot_body
+                }
+            }
+        }
+    }
+
+    // ============================================================
+    // impl<T, ...> OriginalStruct<T, ...> {
+    //     fn snapshot(remote: &RemoteStruct<T, ...>) -> ... { ... }
+    // }
+    // ============================================================
+
+    pub(crate) fn build_remote_snapshot_fn(&self, remote: &Path) -> TokenStream2 {
+        let patchable_trait = &self.patchable_trait;
+        let (impl_generics, type_generics, _) = self.generics.split_for_impl();
+        let where_clause = self.extend_where_clause(&self.build_snapshot_trait_bounds());
+
+        let input_struct_name = self.struct_name;
+        let visibility = self.visibility;
+        let remote_pattern = self.build_remote_pattern(remote);
+        let snapshot_body = self.build_snapshot_body(|member| {
+            let binding = member.binding("field");
+            quote! { (*#binding) }
+        });
+
+        quote! {
+            impl #impl_generics #input_struct_name #type_generics
+            #where_clause {
+                /// Builds a patch holding the current state of the remote value.
+                #[inline(always)]
+                #visibility fn snapshot(remote: &#remote) -> <Self as #patchable_trait>::Patch {
+                    let #remote_pattern = remote;
+                    #snapshot_body
+                }
+            }
+        }
+    }
+
+    /// Requires `Snapshot` for nested fields, and `Clone` for cloned fields whose type uses a
+    /// type parameter.
+    fn build_snapshot_trait_bounds(&self) -> Vec<WherePredicate> {
+        let crate_path = &self.crate_path;
+        let type_params: Vec<_> = self
+            .generics
+            .type_params()
+            .map(|param| &param.ident)
+            .collect();
+        let nested_bounds = self
+            .iter_patchable_type_params()
+            .map(|ty| parse_quote! { #ty: #crate_path::Snapshot });
+        let clone_bounds = self
+            .field_actions
+            .iter()
+            .filter(|action| {
+                matches!(
+                    action,
+                    FieldAction::Keep { .. } | FieldAction::Merge { .. } | FieldAction::With { .. }
+                )
+            })
+            .map(FieldAction::ty)
+            .filter(|ty| {
+                collect_used_simple_types(ty)
+                    .iter()
+                    .any(|used| type_params.contains(used))
+            })
+            .map(|ty: &Type| parse_quote! { #ty: ::core::clone::Clone });
+        nested_bounds.chain(clone_bounds).collect()
+    }
+
+    /// Builds the patch holding the state of the fields found at the places returned by `place`.
+    fn build_snapshot_body<F>(&self, place: F) -> TokenStream2
+    where
+        F: Fn(&FieldMember<'a>) -> TokenStream2,
+    {
+        let snapshot_trait = {
+            let crate_path = &self.crate_path;
+            quote! { #crate_path::Snapshot }
+        };
+        let mut values = self
+            .field_actions
+            .iter()
+            .enumerate()
+            .map(|(index, action)| {
+                let value = action.build_snapshot_expr(&snapshot_trait, &place(action.member()));
+                (
+                    action.member().patch_member(index),
+                    self.wrap_partial_value(value),
+                )
+            });
+
+        if self.transparent {
+            let (_, value) = values.next().expect("validated when building the context");
+            return value;
+        }
+
+        // The patch struct is private to the `Patchable` derive, so it is named through an alias
+        // of the associated type. Braced expressions also build tuple structs (`P { 0: value }`).
+        let patchable_trait = &self.patchable_trait;
+        let input_struct_name = self.struct_name;
+        let alias_generics = strip_bounds(self.generics);
+        let (alias_params, alias_args, _) = alias_generics.split_for_impl();
+        let alias_turbofish = alias_args.as_turbofish();
+        let field_values = values.map(|(patch_member, value)| quote! { #patch_member: #value });
+        quote! {
+            type __SnapshotPatch #alias_params =
+                <#input_struct_name #alias_args as #patchable_trait>::Patch;
+            __SnapshotPatch #alias_turbofish { #(#field_values),* }
+        }
+    }
+}
+
+/// Returns `generics` without bounds, defaults and where clause, as accepted by type aliases.
+pub(super) fn strip_bounds(generics: &Generics) -> Generics {
+    let mut generics = generics.clone();
+    generics.where_clause = None;
+    for param in &mut generics.params {
+        match param {
+            GenericParam::Type(param) => {
+                param.colon_token = None;
+                param.bounds.clear();
+                param.eq_token = None;
+                param.default = None;
+            }
+            GenericParam::Const(param) => {
+                param.eq_token = None;
+                param.default = None;
+            }
+            GenericParam::Lifetime(param) => {
+                param.colon_token = None;
+                param.bounds.clear();
+            }
+        }
+    }
+    generics
+}
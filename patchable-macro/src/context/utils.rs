@@ -33,4 +33,31 @@ impl<'a> MacroContext<'a> {
             .then_some(&param.ident)
         })
     }
+
+    /// Rejects remote definitions for derives that implement a trait on the struct itself.
+    pub(crate) fn reject_remote(&self, derive_name: &str) -> syn::Result<()> {
+        if let Some(remote) = &self.remote {
+            return Err(syn::Error::new_spanned(
+                remote,
+                format!("`{derive_name}` cannot be derived for a remote definition"),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Rejects remote definitions, and structs whose `Patch` derive implements `TryPatch`, for
+    /// derives that apply the patch infallibly.
+    pub(crate) fn reject_unsupported(&self, derive_name: &str) -> syn::Result<()> {
+        self.reject_remote(derive_name)?;
+        if self.fallible {
+            return Err(syn::Error::new_spanned(
+                self.struct_name,
+                format!(
+                    "`{derive_name}` cannot be derived for a struct with `delta = \"checked\"` \
+                     fields or an `invariant`"
+                ),
+            ));
+        }
+        Ok(())
+    }
 }
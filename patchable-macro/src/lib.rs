@@ -10,11 +10,49 @@
 //!
 //! - `#[derive(Patchable)]`: generates the companion `<Struct>Patch` type and the
 //!   `Patchable` impl; with the `impl_from` Cargo feature it also generates
-//!   `From<Struct>` for the patch type.
+//!   `From<Struct>` for the patch type. With `#[patchable(partial)]`, every field of
+//!   the patch is optional and the patch implements `Default` and `PartialPatch`.
 //!
 //! - `#[derive(Patch)]`: generates the `Patch` implementation and recursively
 //!   patches fields annotated with `#[patchable]`.
 //!
+//! - `#[derive(Snapshot)]`: generates the `Snapshot` implementation, which builds
+//!   a patch from `&self` by cloning the non-skipped fields.
+//!
+//! - `#[derive(FromPatch)]`: generates the `<Struct>Skipped` struct and the
+//!   `FromPatch` implementation, which rebuilds a value from a patch and the
+//!   skipped fields.
+//!
+//! - `#[derive(PatchWithContext)]`: generates the `PatchWithContext` implementation,
+//!   which patches with a context and rehydrates skipped fields from it.
+//!
+//! - `#[derive(ReportPatch)]`: generates the `ReportPatch` implementation and a
+//!   `<StructName>Changes` struct reporting the fields changed by a patch.
+//!
+//! - `#[derive(MaskedPatch)]`: generates the `MaskedPatch` implementation, a
+//!   `<StructName>Field` enum and a `<StructName>FieldMask` bitset.
+//!
+//! - `#[derive(PatchEvents)]`: generates the `PatchEvents` implementation and a
+//!   `<StructName>Event` enum with a variant per field.
+//!
+//! - `#[derive(JsonMergePatch)]`: generates the `JsonMergePatch` implementation, which
+//!   applies JSON Merge Patch documents field by field.
+//!
+//! - `#[derive(JsonPatch)]`: generates the `JsonPatch` implementation, which resolves the
+//!   JSON Pointer paths of JSON Patch operations against the fields.
+//!
+//! - `#[derive(PatchPath)]`: generates the `PatchPath` implementation, which reads and
+//!   writes fields by a dotted path through nested `#[patchable]` fields.
+//!
+//! - `#[derive(PatchArgs)]`: generates the `PatchArgs` implementation and a
+//!   `<StructName>Args` struct with a command-line flag per field of a partial patch.
+//!
+//! - `#[derive(Merge3)]`: generates the `Merge3` implementation, which merges two
+//!   concurrent patches against a common base and reports conflicting fields.
+//!
+//! All macros accept `crate = "..."` (as `#[patchable(crate = "...")]` on the
+//! struct for the derives) to refer to `patchable` through a re-export.
+//!
 //! Feature flags are evaluated in the `patchable-macro` crate itself. See `context`
 //! for details about the generated patch struct and trait implementations.
 
@@ -22,13 +60,13 @@ use proc_macro::TokenStream;
 
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn::{Fields, ItemStruct, parse_macro_input, parse_quote};
+use syn::{Fields, ItemStruct, LitStr, Path, parse_macro_input, parse_quote};
 
 mod context;
 
 use syn::DeriveInput;
 
-use crate::context::{IS_SERDE_ENABLED, crate_path, has_patchable_skip_attr};
+use crate::context::{IS_SERDE_ENABLED, crate_path, has_patchable_skip_attr, set_once};
 
 const IS_IMPL_FROM_ENABLED: bool = cfg!(feature = "impl_from");
 
@@ -40,10 +78,26 @@ const IS_IMPL_FROM_ENABLED: bool = cfg!(feature = "impl_from");
 ///   `#[derive(serde::Serialize)]`.
 /// - For fields annotated with `#[patchable(skip)]`, it injects `#[serde(skip)]`
 ///   to keep serde output aligned with patching behavior.
+/// - `#[patchable_model(crate = "path::to::patchable")]` uses a custom path to the
+///   `patchable` crate and forwards it to the derives as `#[patchable(crate = "...")]`.
 ///
 /// This macro preserves the original struct shape and only mutates attributes.
-pub fn patchable_model(_attr: TokenStream, item: TokenStream) -> TokenStream {
-    let crate_path = crate_path();
+pub fn patchable_model(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut custom_crate_path = None;
+    let attr_parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("crate") {
+            let lit: LitStr = meta.value()?.parse()?;
+            let path: Path = lit.parse()?;
+            set_once(&meta, &mut custom_crate_path, (lit, path))
+        } else {
+            Err(meta.error("unrecognized `patchable_model` parameter"))
+        }
+    });
+    parse_macro_input!(attr with attr_parser);
+
+    let crate_path = custom_crate_path
+        .as_ref()
+        .map_or_else(crate_path, |(_, path)| quote! { #path });
     let derives = if IS_SERDE_ENABLED {
         parse_quote! {
             #[derive(#crate_path::Patchable, #crate_path::Patch, ::serde::Serialize)]
@@ -56,6 +110,11 @@ pub fn patchable_model(_attr: TokenStream, item: TokenStream) -> TokenStream {
 
     let mut input = parse_macro_input!(item as ItemStruct);
     input.attrs.push(derives);
+    if let Some((lit, _)) = custom_crate_path {
+        input
+            .attrs
+            .push(parse_quote! { #[patchable(crate = #lit)] });
+    }
 
     if IS_SERDE_ENABLED {
         add_serde_skip_attrs(&mut input.fields);
@@ -77,11 +136,34 @@ pub fn patchable_model(_attr: TokenStream, item: TokenStream) -> TokenStream {
 /// any required generic bounds.
 ///
 /// When the `impl_from` feature is enabled for the macro crate, a
-/// `From<Struct>` implementation is also generated for the patch type.
+/// `From<Struct>` implementation is also generated for the patch type (`From<Remote>`
+/// for a `#[patchable(remote = "...")]` definition).
+///
+/// `ComposePatch` is implemented for the patch struct, unless a field uses
+/// `#[patchable(with = "...")]`.
+///
+/// With `#[patchable(patch = Type)]`, only the `Patchable` impl is generated, using the
+/// given hand-written patch type. With `#[patchable(transparent)]`, only the `Patchable`
+/// impl is generated too, using the patch type of the only non-skipped field.
 pub fn derive_patchable(input: TokenStream) -> TokenStream {
     expand(input, |ctx| {
-        let patch_struct_def = ctx.build_patch_struct();
         let patchable_trait_impl = ctx.build_patchable_trait_impl();
+        let remote_field_reader = ctx.remote().map(|_| ctx.build_remote_field_reader());
+        let field_set_checks = ctx.build_field_set_checks();
+        if !ctx.generates_patch_struct() {
+            return quote! {
+                const _: () = {
+                    #field_set_checks
+
+                    #[automatically_derived]
+                    #patchable_trait_impl
+
+                    #remote_field_reader
+                };
+            };
+        }
+
+        let patch_struct_def = ctx.build_patch_struct();
         let from_struct_impl = IS_IMPL_FROM_ENABLED.then(|| {
             let from_struct_impl = ctx.build_from_trait_impl();
             quote! {
@@ -89,9 +171,19 @@ pub fn derive_patchable(input: TokenStream) -> TokenStream {
                 #from_struct_impl
             }
         });
+        let compose_patch_impl = ctx.generates_compose_impl().then(|| {
+            let compose_patch_impl = ctx.build_compose_patch_impl();
+            quote! {
+                #[automatically_derived]
+                #compose_patch_impl
+            }
+        });
+        let partial_patch_impls = ctx.build_partial_patch_impls();
 
         quote! {
             const _: () = {
+                #field_set_checks
+
                 #[automatically_derived]
                 #patch_struct_def
 
@@ -99,6 +191,12 @@ pub fn derive_patchable(input: TokenStream) -> TokenStream {
                 #patchable_trait_impl
 
                 #from_struct_impl
+
+                #compose_patch_impl
+
+                #partial_patch_impls
+
+                #remote_field_reader
             };
         }
     })
@@ -110,10 +208,37 @@ pub fn derive_patchable(input: TokenStream) -> TokenStream {
 /// The generated `patch` method:
 /// - assigns fields directly by default,
 /// - recursively calls `patch` on fields marked with `#[patchable]`,
+/// - calls `module::patch` on fields marked with `#[patchable(with = "module")]`,
+/// - combines fields marked with `#[patchable(merge = ...)]` with their current value
+///   through the named `MergeStrategy`,
+/// - adds the difference carried by fields marked with `#[patchable(delta)]`,
 /// - respects `#[patchable(skip)]` by omitting those fields from patching.
+///
+/// With `#[patchable(remote = "...")]`, an associated `patch` function taking the
+/// remote type is generated instead of the `Patch` implementation. With
+/// `#[patchable(patch = Type)]`, the fields are moved out of the hand-written patch type,
+/// whose field set must match the non-skipped fields exactly. With
+/// `#[patchable(transparent)]`, the whole patch is applied to the only non-skipped field.
+///
+/// If a field is marked with `#[patchable(delta = "checked")]`, `TryPatch` (with
+/// `PatchError`) is implemented instead of `Patch`, and a remote `patch` function returns a
+/// `Result`.
+///
+/// After the fields are patched, the `#[patchable(after_patch = path)]` function is called
+/// with `&mut self`. With `#[patchable(invariant = path)]`, the function is then called with
+/// `&self`, `TryPatch` is implemented, and an `Err(message)` is returned as
+/// `PatchError::Invariant`. `#[patchable(rollback)]` restores a clone of the previous value
+/// when `try_patch` fails.
+///
+/// A struct implementing `TryPatch` can only be nested in a struct implementing `TryPatch`
+/// too, and `ReportPatch`, `MaskedPatch`, `PatchEvents`, `PatchWithContext`, `JsonMergePatch`,
+/// `JsonPatch` and `PatchPath` cannot be derived for it.
 pub fn derive_patch(input: TokenStream) -> TokenStream {
     expand(input, |ctx| {
-        let patch_trait_impl = ctx.build_patch_trait_impl();
+        let patch_trait_impl = match ctx.remote() {
+            Some(remote) => ctx.build_remote_patch_fn(remote),
+            None => ctx.build_patch_trait_impl(),
+        };
 
         quote! {
             const _: () = {
@@ -124,6 +249,333 @@ pub fn derive_patch(input: TokenStream) -> TokenStream {
     })
 }
 
+#[proc_macro_derive(Snapshot, attributes(patchable))]
+/// Derive macro that generates the `Snapshot` trait implementation.
+///
+/// The generated `snapshot` method builds the patch from `&self`:
+/// - clones plain fields (requiring `Clone` for field types using a type parameter),
+/// - recursively calls `snapshot` on fields marked with `#[patchable]`,
+/// - calls `module::from` on a clone of fields marked with `#[patchable(with = "module")]`,
+/// - stores the difference from the default value for fields marked with
+///   `#[patchable(delta)]`,
+/// - omits fields marked with `#[patchable(skip)]`.
+///
+/// Since `merge` and `delta` fields apply their patch value as usual, the snapshot reproduces
+/// them only on a target where they hold their default value, and fields merged with `"max"`,
+/// `"min"` or `"and"` only for values that merging into the default keeps (see
+/// `patchable::Snapshot`).
+///
+/// With `#[patchable(remote = "...")]`, an associated `snapshot` function taking a reference to
+/// the remote type is generated instead of the `Snapshot` implementation.
+pub fn derive_snapshot(input: TokenStream) -> TokenStream {
+    expand(input, |ctx| {
+        let snapshot_trait_impl = match ctx.remote() {
+            Some(remote) => ctx.build_remote_snapshot_fn(remote),
+            None => ctx.build_snapshot_trait_impl(),
+        };
+
+        quote! {
+            const _: () = {
+                #[automatically_derived]
+                #snapshot_trait_impl
+            };
+        }
+    })
+}
+
+#[proc_macro_derive(ReportPatch, attributes(patchable))]
+/// Derive macro that generates the `ReportPatch` trait implementation.
+///
+/// It also generates a `<StructName>Changes` struct, with the visibility of the target struct,
+/// holding a `bool` for each non-skipped field, or the `PatchReport` of fields marked with
+/// `#[patchable]`.
+///
+/// The generated `patch_report` method applies the patch like `patch` and flags a field as
+/// changed when:
+/// - its new value differs from the old one, for types implementing `PartialEq` (required
+///   for field types using a type parameter),
+/// - the patch touches it, for other types,
+/// - the nested report has a change, for fields marked with `#[patchable]`.
+///
+/// Fields other than plain and nested ones are compared with a clone of their old value, taken
+/// when the type implements `Clone` and `PartialEq`. With `#[patchable(partial)]`, fields the
+/// patch leaves unset are reported as unchanged.
+pub fn derive_report_patch(input: TokenStream) -> TokenStream {
+    expand(input, |ctx| {
+        let report_patch_trait_impl = match ctx.build_report_patch_trait_impl() {
+            Ok(trait_impl) => trait_impl,
+            Err(error) => return error.to_compile_error(),
+        };
+        let changes_struct_def = ctx.build_changes_struct();
+
+        quote! {
+            #changes_struct_def
+
+            const _: () = {
+                #[automatically_derived]
+                #report_patch_trait_impl
+            };
+        }
+    })
+}
+
+#[proc_macro_derive(MaskedPatch, attributes(patchable))]
+/// Derive macro that generates the `MaskedPatch` trait implementation.
+///
+/// It also generates, with the visibility of the target struct:
+/// - a `<StructName>Field` enum, with a variant per non-skipped field named in
+///   `UpperCamelCase` (`Field0`, `Field1`, ... for tuple structs),
+/// - a `<StructName>FieldMask` bitset of those fields, with `ALL` and `NONE` constants,
+///   set operations and the `FieldMask` trait.
+///
+/// The generated `patch_masked` method applies the fields of the patch selected by the mask
+/// like `patch`, and leaves the other fields unchanged.
+pub fn derive_masked_patch(input: TokenStream) -> TokenStream {
+    expand(input, |ctx| {
+        let masked_patch_trait_impl = match ctx.build_masked_patch_trait_impl() {
+            Ok(trait_impl) => trait_impl,
+            Err(error) => return error.to_compile_error(),
+        };
+        let field_mask_types = ctx.build_field_mask_types();
+
+        quote! {
+            #field_mask_types
+
+            const _: () = {
+                #[automatically_derived]
+                #masked_patch_trait_impl
+            };
+        }
+    })
+}
+
+#[proc_macro_derive(PatchEvents, attributes(patchable))]
+/// Derive macro that generates the `PatchEvents` trait implementation.
+///
+/// It also generates a `<StructName>Event` enum, with the visibility of the target struct and
+/// a variant per non-skipped field (named like the variants of `MaskedPatch`) carrying the
+/// value of that field in the patch. With the `serde` feature, the enum derives
+/// `serde::Serialize` and `serde::Deserialize`.
+///
+/// The generated `apply_event` method applies the carried value like `patch` does, and
+/// `into_events` splits a patch into one event per field.
+pub fn derive_patch_events(input: TokenStream) -> TokenStream {
+    expand(input, |ctx| {
+        let patch_events_trait_impl = match ctx.build_patch_events_trait_impl() {
+            Ok(trait_impl) => trait_impl,
+            Err(error) => return error.to_compile_error(),
+        };
+        let event_enum_def = ctx.build_event_enum();
+
+        quote! {
+            #event_enum_def
+
+            const _: () = {
+                #[automatically_derived]
+                #patch_events_trait_impl
+            };
+        }
+    })
+}
+
+#[proc_macro_derive(JsonMergePatch, attributes(patchable))]
+/// Derive macro that generates the `JsonMergePatch` trait implementation.
+///
+/// The generated `merge_json` method matches the keys of a JSON object against the names of
+/// the non-skipped fields (their indexes, for tuple structs), and rejects any other key:
+/// - the value of a field marked with `#[patchable]` is merged into it recursively,
+/// - the value of another field is deserialized into its patch value and applied like `patch`.
+///
+/// The derive is exported by `patchable` with the `json-merge-patch` feature.
+pub fn derive_json_merge_patch(input: TokenStream) -> TokenStream {
+    expand(input, |ctx| {
+        let json_merge_patch_trait_impl = match ctx.build_json_merge_patch_trait_impl() {
+            Ok(trait_impl) => trait_impl,
+            Err(error) => return error.to_compile_error(),
+        };
+
+        quote! {
+            const _: () = {
+                #[automatically_derived]
+                #json_merge_patch_trait_impl
+            };
+        }
+    })
+}
+
+#[proc_macro_derive(JsonPatch, attributes(patchable))]
+/// Derive macro that generates the `JsonPatch` trait implementation.
+///
+/// The generated methods resolve the first segment of a JSON Pointer against the names of the
+/// non-skipped fields (their indexes, for tuple structs), and the rest of it:
+/// - in the nested struct, for fields marked with `#[patchable]`,
+/// - in the serialized value of the field, for other fields.
+///
+/// An empty path addresses the struct as an object with a member per field. A
+/// `#[patchable(transparent)]` struct is addressed as its field.
+///
+/// The derive is exported by `patchable` with the `json-patch` feature.
+pub fn derive_json_patch(input: TokenStream) -> TokenStream {
+    expand(input, |ctx| {
+        let json_patch_trait_impl = match ctx.build_json_patch_trait_impl() {
+            Ok(trait_impl) => trait_impl,
+            Err(error) => return error.to_compile_error(),
+        };
+
+        quote! {
+            const _: () = {
+                #[automatically_derived]
+                #json_patch_trait_impl
+            };
+        }
+    })
+}
+
+#[proc_macro_derive(PatchPath, attributes(patchable))]
+/// Derive macro that generates the `PatchPath` trait implementation.
+///
+/// The generated methods match the first segment of a dotted path against the names of the
+/// non-skipped fields (their indexes, for tuple structs), and resolve the rest of it in the
+/// nested struct for fields marked with `#[patchable]`. Other fields end the path, and are read
+/// and written through their own `Serialize` and `Deserialize` implementations.
+///
+/// An empty path addresses the struct itself, which is set by deserializing its patch. A
+/// `#[patchable(transparent)]` struct is addressed as its field.
+///
+/// The derive is exported by `patchable` with the `serde` feature.
+pub fn derive_patch_path(input: TokenStream) -> TokenStream {
+    expand(input, |ctx| {
+        let patch_path_trait_impl = match ctx.build_patch_path_trait_impl() {
+            Ok(trait_impl) => trait_impl,
+            Err(error) => return error.to_compile_error(),
+        };
+
+        quote! {
+            const _: () = {
+                #[automatically_derived]
+                #patch_path_trait_impl
+            };
+        }
+    })
+}
+
+#[proc_macro_derive(PatchArgs, attributes(patchable))]
+/// Derive macro that generates the `PatchArgs` trait implementation, for structs marked with
+/// `#[patchable(partial)]`.
+///
+/// It also generates a `<StructName>Args` struct, with the visibility of the target struct,
+/// implementing `clap::Args` and `clap::FromArgMatches`. It adds an optional flag per
+/// non-skipped field, named after the path to the field in kebab case, and recurses into the
+/// nested struct for fields marked with `#[patchable]`. Its `into_patch` method returns the
+/// patch setting the fields whose flag was given.
+///
+/// `Option<T>` fields take a `T`, `Vec<T>` and `Option<Vec<T>>` fields take a value per occurrence
+/// of the flag, and `bool` fields are switches, which take a value only after `=`. Tuple structs
+/// are rejected, since their fields have no name to give their flags.
+///
+/// Two fields whose flags get the same name, such as `limits_timeout` next to a nested `limits`
+/// with a `timeout` field, are a compile error once the `<StructName>Args` struct is used.
+///
+/// The derive is exported by `patchable` with the `clap` feature.
+pub fn derive_patch_args(input: TokenStream) -> TokenStream {
+    expand(input, |ctx| match ctx.build_patch_args_impls() {
+        Ok(impls) => impls,
+        Err(error) => error.to_compile_error(),
+    })
+}
+
+#[proc_macro_derive(Merge3, attributes(patchable))]
+/// Derive macro that generates the `Merge3` trait implementation.
+///
+/// The generated `merge3_below` method merges two patches written against `self` field by
+/// field:
+/// - plain fields take the value of the side that changed them from the base, and conflict when
+///   both sides set different new values (field types using a type parameter require
+///   `PartialEq` and `Clone`),
+/// - fields marked with `#[patchable]` merge the nested patches recursively,
+/// - `merge` and `delta` fields combine the changes of both sides, as `ComposePatch` does.
+///
+/// In a `partial` struct, unset fields count as unchanged. The derive is exported by `patchable`
+/// with the `alloc` feature.
+pub fn derive_merge3(input: TokenStream) -> TokenStream {
+    expand(input, |ctx| {
+        let merge3_trait_impl = match ctx.build_merge3_trait_impl() {
+            Ok(trait_impl) => trait_impl,
+            Err(error) => return error.to_compile_error(),
+        };
+
+        quote! {
+            const _: () = {
+                #[automatically_derived]
+                #merge3_trait_impl
+            };
+        }
+    })
+}
+
+#[proc_macro_derive(FromPatch, attributes(patchable))]
+/// Derive macro that generates the `FromPatch` trait implementation.
+///
+/// It also generates a `<StructName>Skipped` struct, with the visibility of the target struct,
+/// holding the fields marked with `#[patchable(skip)]`. Fields marked with
+/// `#[patchable(skip, default = expr)]` are not part of it and are initialized with `expr`.
+///
+/// The generated `from_patch` function:
+/// - moves plain and `merge` fields out of the patch,
+/// - builds other patched fields from their default value and applies the patch to them,
+/// - takes the skipped fields from `<StructName>Skipped`.
+///
+/// When every skipped field has a `default`, `From<Patch>` is also implemented for the
+/// struct (except for `#[patchable(transparent)]` structs).
+///
+/// With `#[patchable(remote = "...")]`, an associated `from_patch` function returning the
+/// remote type is generated instead of the `FromPatch` implementation.
+pub fn derive_from_patch(input: TokenStream) -> TokenStream {
+    expand(input, |ctx| {
+        if let Err(error) = ctx.reject_partial("FromPatch") {
+            return error.to_compile_error();
+        }
+        let skipped_struct_def = ctx.build_skipped_struct();
+        let from_patch_trait_impl = match ctx.remote() {
+            Some(remote) => ctx.build_remote_from_patch_fn(remote),
+            None => ctx.build_from_patch_trait_impl(),
+        };
+
+        quote! {
+            #skipped_struct_def
+
+            const _: () = {
+                #from_patch_trait_impl
+            };
+        }
+    })
+}
+
+#[proc_macro_derive(PatchWithContext, attributes(patchable))]
+/// Derive macro that generates the `PatchWithContext` trait implementation.
+///
+/// The generated `patch_with` method patches the struct like `patch`, passes the context on
+/// to fields marked with `#[patchable]`, and then calls the function of each
+/// `#[patchable(skip, rehydrate = path)]` field with the field and the context.
+///
+/// The context type is set with `#[patchable(context = Type)]`, which `rehydrate` requires.
+/// Without it, `PatchWithContext` is implemented for any context.
+pub fn derive_patch_with_context(input: TokenStream) -> TokenStream {
+    expand(input, |ctx| {
+        let patch_with_context_trait_impl = match ctx.build_patch_with_context_trait_impl() {
+            Ok(trait_impl) => trait_impl,
+            Err(error) => return error.to_compile_error(),
+        };
+
+        quote! {
+            const _: () = {
+                #[automatically_derived]
+                #patch_with_context_trait_impl
+            };
+        }
+    })
+}
+
 fn expand<F>(input: TokenStream, f: F) -> TokenStream
 where
     F: FnOnce(&context::MacroContext) -> TokenStream2,
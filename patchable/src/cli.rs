@@ -0,0 +1,248 @@
+//! # Command-Line Overrides
+//!
+//! `#[derive(PatchArgs)]` on a `#[patchable(partial)]` struct generates a `<StructName>Args`
+//! struct implementing `clap::Args`, with an optional flag per field of the patch. Once parsed, it
+//! converts into the patch, which applies the flags that were given on top of a loaded value.
+//!
+//! Flags are named after the path to their field in kebab case, so that `limits.max_connections`
+//! is set with `--limits-max-connections`. Skipped fields have no flag, and `bool` fields are
+//! switches: `--enabled` sets `true`, and `--enabled=false` sets `false`. Two fields whose flags
+//! get the same name, such as `limits_timeout` next to a nested `limits` with a `timeout` field,
+//! are a compile error once the `<StructName>Args` struct is used. The flags of nested fields
+//! depend on their type, so the error is raised when building, and not by `cargo check`.
+//!
+//! This module is available with the `clap` feature.
+
+use clap::{ArgMatches, Command};
+
+use crate::partial::FieldPath;
+use crate::{PartialPatch, Patchable};
+
+/// A struct whose patch can be read from command-line arguments, with `#[derive(PatchArgs)]`.
+///
+/// ## Usage
+///
+/// ```rust
+/// use clap::Parser;
+/// use patchable::{Patch, PatchArgs, Patchable};
+///
+/// #[derive(Debug, Patchable, Patch, PatchArgs)]
+/// #[patchable(partial)]
+/// struct Limits {
+///     max_connections: u32,
+///     timeout: Option<u64>,
+/// }
+///
+/// #[derive(Debug, Patchable, Patch, PatchArgs)]
+/// #[patchable(partial)]
+/// struct Config<L> {
+///     name: String,
+///     #[patchable]
+///     limits: L,
+///     #[patchable(skip)]
+///     secret: String,
+/// }
+///
+/// #[derive(Parser)]
+/// struct Cli {
+///     #[arg(long)]
+///     config: Option<String>,
+///     #[command(flatten)]
+///     overrides: ConfigArgs<Limits>,
+/// }
+///
+/// let cli = Cli::parse_from(["app", "--limits-max-connections", "128", "--limits-timeout", "5"]);
+///
+/// let mut config = Config {
+///     name: "api".to_owned(),
+///     limits: Limits { max_connections: 16, timeout: None },
+///     secret: String::new(),
+/// };
+/// config.patch(cli.overrides.into_patch());
+/// assert_eq!(config.name, "api");
+/// assert_eq!((config.limits.max_connections, config.limits.timeout), (128, Some(5)));
+/// ```
+pub trait PatchArgs: Patchable<Patch: PartialPatch + Default> {
+    /// The flags of the fields, relative to the struct.
+    #[doc(hidden)]
+    const PATCH_FLAGS: &'static [__private::Flag];
+
+    /// Adds a flag per field of the patch to `command`, below `prefix`.
+    #[doc(hidden)]
+    fn augment_patch_args(command: Command, prefix: Option<&FieldPath<'_>>) -> Command;
+
+    /// Sets the fields of `patch` whose flag is present in `matches`, below `prefix`.
+    #[doc(hidden)]
+    fn update_patch_from_arg_matches(
+        patch: &mut Self::Patch,
+        matches: &ArgMatches,
+        prefix: Option<&FieldPath<'_>>,
+    );
+}
+
+#[doc(hidden)]
+pub mod __private {
+    use alloc::format;
+    use alloc::string::{String, ToString};
+    use alloc::vec::Vec;
+    use core::fmt::Display;
+    use core::str::FromStr;
+
+    use clap::{Arg, ArgAction, ArgMatches};
+
+    pub use clap;
+
+    use crate::PartialPatch;
+    use crate::partial::FieldPath;
+
+    /// Builds the flag of the field at `path`, taking a single value, or appending values to a
+    /// list with `many`. A `switch` flag given without a value sets `true`, and takes a value only
+    /// after `=`. The help starts with `verb`, describing what the value does to the field.
+    pub fn arg(path: &FieldPath<'_>, many: bool, switch: bool, verb: &str) -> Arg {
+        let id = path.to_string();
+        let long = id.replace(['.', '_'], "-");
+        let action = if many {
+            ArgAction::Append
+        } else {
+            ArgAction::Set
+        };
+        let arg = Arg::new(id.clone())
+            .long(long)
+            .action(action)
+            .required(false)
+            .allow_negative_numbers(true)
+            .help(format!("{verb} `{id}`"));
+        if switch {
+            arg.num_args(0..=1)
+                .require_equals(true)
+                .default_missing_value("true")
+        } else {
+            arg
+        }
+    }
+
+    /// Parses a value with `FromStr`, for field types using a type parameter.
+    pub fn parse<T>(value: &str) -> Result<T, String>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        value.parse().map_err(|error: T::Err| error.to_string())
+    }
+
+    /// Returns the value of the flag of the field at `path`, if it is present.
+    pub fn one<T>(matches: &ArgMatches, path: &FieldPath<'_>) -> Option<T>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        matches.get_one::<T>(&path.to_string()).cloned()
+    }
+
+    /// Returns the values of the flag of the field at `path`, if it is present.
+    pub fn many<T>(matches: &ArgMatches, path: &FieldPath<'_>) -> Option<Vec<T>>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        let values = matches.get_many::<T>(&path.to_string())?;
+        Some(values.cloned().collect())
+    }
+
+    /// Returns the patch of a nested field, if it sets any field.
+    pub fn nested<P: PartialPatch>(patch: P) -> Option<P> {
+        (!patch.is_empty()).then_some(patch)
+    }
+
+    /// The flag of a field in kebab case, or the flags of a nested field below its name.
+    pub struct Flag {
+        pub name: &'static str,
+        pub nested: Option<&'static [Flag]>,
+    }
+
+    /// The longest flag name checked by [`clashes`]; longer ones are skipped.
+    const MAX_FLAG_LEN: usize = 256;
+
+    /// Whether a flag of `first` has the same name as a flag of `second`.
+    pub const fn clashes(first: &Flag, second: &Flag) -> bool {
+        let mut name = [0; MAX_FLAG_LEN];
+        any_flag_in(
+            core::slice::from_ref(first),
+            &mut name,
+            0,
+            core::slice::from_ref(second),
+        )
+    }
+
+    /// Whether a flag of `flags`, named below the first `len` bytes of `name`, is a flag of
+    /// `others`.
+    const fn any_flag_in(
+        flags: &[Flag],
+        name: &mut [u8; MAX_FLAG_LEN],
+        len: usize,
+        others: &[Flag],
+    ) -> bool {
+        let mut index = 0;
+        while index < flags.len() {
+            let flag = &flags[index];
+            index += 1;
+            let segment = flag.name.as_bytes();
+            let start = if len == 0 { 0 } else { len + 1 };
+            if start + segment.len() > MAX_FLAG_LEN {
+                continue;
+            }
+            if len > 0 {
+                name[len] = b'-';
+            }
+            let mut byte = 0;
+            while byte < segment.len() {
+                name[start + byte] = segment[byte];
+                byte += 1;
+            }
+            let end = start + segment.len();
+            let found = match flag.nested {
+                Some(nested) => any_flag_in(nested, name, end, others),
+                None => has_flag(others, name.split_at(end).0),
+            };
+            if found {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Whether one of `flags` is named `name`.
+    const fn has_flag(flags: &[Flag], name: &[u8]) -> bool {
+        let mut index = 0;
+        while index < flags.len() {
+            let flag = &flags[index];
+            index += 1;
+            let segment = flag.name.as_bytes();
+            if name.len() < segment.len() || !bytes_eq(name.split_at(segment.len()).0, segment) {
+                continue;
+            }
+            let rest = name.split_at(segment.len()).1;
+            let found = match (flag.nested, rest) {
+                (None, []) => true,
+                (Some(nested), [b'-', rest @ ..]) => has_flag(nested, rest),
+                _ => false,
+            };
+            if found {
+                return true;
+            }
+        }
+        false
+    }
+
+    const fn bytes_eq(left: &[u8], right: &[u8]) -> bool {
+        if left.len() != right.len() {
+            return false;
+        }
+        let mut index = 0;
+        while index < left.len() {
+            if left[index] != right[index] {
+                return false;
+            }
+            index += 1;
+        }
+        true
+    }
+}
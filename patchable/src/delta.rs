@@ -0,0 +1,203 @@
+//! # Delta Encoding
+//!
+//! Numeric fields marked with `#[patchable(delta)]` carry the difference to apply instead of an
+//! absolute value, so patches of such fields commute and stay small on the wire.
+//!
+//! Integer deltas are applied with wrapping arithmetic. Fields marked with
+//! `#[patchable(delta = "checked")]` are applied with [`Delta::checked_apply_delta`] instead, and
+//! the derive then implements [`TryPatch`](crate::TryPatch) (with [`PatchError`](crate::PatchError)
+//! as the error) rather than [`Patch`](crate::Patch).
+
+/// A numeric type whose changes can be expressed as a difference.
+///
+/// | Type                   | `Delta`                 | Difference                                  |
+/// |------------------------|-------------------------|---------------------------------------------|
+/// | `i8` … `i128`, `isize` | `Self`                  | Signed, wrapping if it does not fit `Self`  |
+/// | `u8` … `u64`, `usize`  | `i16` … `i128`, `i128`  | Signed, in a wider type                     |
+/// | `u128`                 | `i128`                  | Signed, wrapping if it does not fit `i128`  |
+/// | `f32`, `f64`           | `Self`                  | `to - from`                                 |
+///
+/// Unsigned integers other than `u128` take a wider signed delta, so a decrease is a negative
+/// difference and `from.checked_apply_delta(Self::delta(&from, &to))` returns `Some(to)`. The
+/// differences of signed integers and `u128` wrap when they do not fit the delta type:
+/// [`apply_delta`](Delta::apply_delta) still reproduces `to`, while
+/// [`checked_apply_delta`](Delta::checked_apply_delta) reports an overflow.
+///
+/// ## Usage
+///
+/// ```rust
+/// use patchable::Delta;
+///
+/// let delta = u32::delta(&42, &40);
+/// assert_eq!(delta, -2);
+///
+/// let mut counter = 100_u32;
+/// counter.apply_delta(delta);
+/// assert_eq!(counter, 98);
+/// assert_eq!(42_u32.checked_apply_delta(delta), Some(40));
+/// assert_eq!(1_u32.checked_apply_delta(delta), None);
+/// ```
+pub trait Delta: Sized {
+    /// The difference between two values.
+    type Delta;
+
+    /// Returns the difference that turns `from` into `to`.
+    fn delta(from: &Self, to: &Self) -> Self::Delta;
+
+    /// Applies `delta` to `self`, wrapping around on integer overflow.
+    fn apply_delta(&mut self, delta: Self::Delta);
+
+    /// Returns `self` with `delta` applied, or `None` if the result overflows.
+    ///
+    /// For floats, a non-finite result counts as an overflow.
+    fn checked_apply_delta(&self, delta: Self::Delta) -> Option<Self>;
+
+    /// Combines two differences into one, as if `first` was applied before `later`.
+    ///
+    /// Integer differences are combined with wrapping arithmetic.
+    fn compose_delta(first: Self::Delta, later: Self::Delta) -> Self::Delta;
+}
+
+macro_rules! impl_integer_delta {
+    ($($ty:ty),* $(,)?) => {$(
+        impl Delta for $ty {
+            type Delta = $ty;
+
+            #[inline(always)]
+            fn delta(from: &Self, to: &Self) -> Self::Delta {
+                to.wrapping_sub(*from)
+            }
+
+            #[inline(always)]
+            fn apply_delta(&mut self, delta: Self::Delta) {
+                *self = self.wrapping_add(delta);
+            }
+
+            #[inline(always)]
+            fn checked_apply_delta(&self, delta: Self::Delta) -> Option<Self> {
+                self.checked_add(delta)
+            }
+
+            #[inline(always)]
+            fn compose_delta(first: Self::Delta, later: Self::Delta) -> Self::Delta {
+                first.wrapping_add(later)
+            }
+        }
+    )*};
+}
+
+impl_integer_delta!(i8, i16, i32, i64, i128, isize);
+
+macro_rules! impl_unsigned_delta {
+    ($($ty:ty => $delta:ty),* $(,)?) => {$(
+        impl Delta for $ty {
+            type Delta = $delta;
+
+            #[inline(always)]
+            fn delta(from: &Self, to: &Self) -> Self::Delta {
+                <$delta>::from(*to) - <$delta>::from(*from)
+            }
+
+            #[inline(always)]
+            fn apply_delta(&mut self, delta: Self::Delta) {
+                // Truncating the wider sum wraps around like the arithmetic of `Self`.
+                *self = (<$delta>::from(*self).wrapping_add(delta)) as $ty;
+            }
+
+            #[inline(always)]
+            fn checked_apply_delta(&self, delta: Self::Delta) -> Option<Self> {
+                <$delta>::from(*self)
+                    .checked_add(delta)
+                    .and_then(|value| Self::try_from(value).ok())
+            }
+
+            #[inline(always)]
+            fn compose_delta(first: Self::Delta, later: Self::Delta) -> Self::Delta {
+                first.wrapping_add(later)
+            }
+        }
+    )*};
+}
+
+impl_unsigned_delta!(u8 => i16, u16 => i32, u32 => i64, u64 => i128);
+
+impl Delta for usize {
+    type Delta = i128;
+
+    #[inline(always)]
+    fn delta(from: &Self, to: &Self) -> Self::Delta {
+        u64::delta(&(*from as u64), &(*to as u64))
+    }
+
+    #[inline(always)]
+    fn apply_delta(&mut self, delta: Self::Delta) {
+        *self = (*self as i128).wrapping_add(delta) as usize;
+    }
+
+    #[inline(always)]
+    fn checked_apply_delta(&self, delta: Self::Delta) -> Option<Self> {
+        (*self as i128)
+            .checked_add(delta)
+            .and_then(|value| Self::try_from(value).ok())
+    }
+
+    #[inline(always)]
+    fn compose_delta(first: Self::Delta, later: Self::Delta) -> Self::Delta {
+        first.wrapping_add(later)
+    }
+}
+
+impl Delta for u128 {
+    type Delta = i128;
+
+    #[inline(always)]
+    fn delta(from: &Self, to: &Self) -> Self::Delta {
+        to.wrapping_sub(*from) as i128
+    }
+
+    #[inline(always)]
+    fn apply_delta(&mut self, delta: Self::Delta) {
+        *self = self.wrapping_add_signed(delta);
+    }
+
+    #[inline(always)]
+    fn checked_apply_delta(&self, delta: Self::Delta) -> Option<Self> {
+        self.checked_add_signed(delta)
+    }
+
+    #[inline(always)]
+    fn compose_delta(first: Self::Delta, later: Self::Delta) -> Self::Delta {
+        first.wrapping_add(later)
+    }
+}
+
+macro_rules! impl_float_delta {
+    ($($ty:ty),* $(,)?) => {$(
+        impl Delta for $ty {
+            type Delta = $ty;
+
+            #[inline(always)]
+            fn delta(from: &Self, to: &Self) -> Self::Delta {
+                to - from
+            }
+
+            #[inline(always)]
+            fn apply_delta(&mut self, delta: Self::Delta) {
+                *self += delta;
+            }
+
+            #[inline(always)]
+            fn checked_apply_delta(&self, delta: Self::Delta) -> Option<Self> {
+                let value = self + delta;
+                value.is_finite().then_some(value)
+            }
+
+            #[inline(always)]
+            fn compose_delta(first: Self::Delta, later: Self::Delta) -> Self::Delta {
+                first + later
+            }
+        }
+    )*};
+}
+
+impl_float_delta!(f32, f64);
@@ -0,0 +1,388 @@
+//! # Environment Variables
+//!
+//! [`from_env`] reads the partial patch (see [`partial`](crate::partial)) of a type from the
+//! environment variables starting with a prefix and `_`. The rest of a name is the path to a
+//! named field, in upper case with `__` between the segments, so that with the prefix `APP`,
+//! `APP_LIMITS__MAX_CONNECTIONS=10` sets `limits.max_connections`.
+//!
+//! Numbers, booleans and characters are parsed with `FromStr`; sequences are split on commas;
+//! other values, such as strings and unit enum variants, are deserialized from the string with
+//! serde. Variables naming unknown fields are ignored.
+//!
+//! [`from_vars`] reads the patch from any list of variables instead of the process environment.
+//!
+//! This module is available with the `env` feature, and [`from_env`] also needs the `std` feature.
+
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use serde::de::value::{Error as ValueError, MapDeserializer, SeqDeserializer};
+use serde::de::{Deserialize, DeserializeOwned, Deserializer, Error, IntoDeserializer, Visitor};
+
+use crate::{PartialPatch, Patchable};
+
+/// Reads the patch of `T` from the environment variables of the process starting with `prefix`
+/// and `_`.
+///
+/// # Errors
+///
+/// Returns an error listing every variable whose name is malformed, clashes with another one, or
+/// whose value cannot be deserialized.
+#[cfg(feature = "std")]
+pub fn from_env<T>(prefix: &str) -> Result<T::Patch, EnvError>
+where
+    T: Patchable,
+    T::Patch: PartialPatch + DeserializeOwned,
+{
+    from_vars::<T>(
+        prefix,
+        std::env::vars_os().filter_map(|(name, value)| {
+            Some((name.into_string().ok()?, value.into_string().ok()?))
+        }),
+    )
+}
+
+/// Reads the patch of `T` from the variables of `vars` starting with `prefix` and `_`.
+///
+/// ## Usage
+///
+/// ```rust
+/// use patchable::env::from_vars;
+/// use patchable::{Patch, Patchable};
+///
+/// #[derive(Patchable, Patch)]
+/// #[patchable(partial)]
+/// struct Limits {
+///     max_connections: u32,
+///     timeout: u64,
+/// }
+///
+/// #[derive(Patchable, Patch)]
+/// #[patchable(partial)]
+/// struct Config<L> {
+///     name: String,
+///     #[patchable]
+///     limits: L,
+/// }
+///
+/// let mut config = Config {
+///     name: "api".to_owned(),
+///     limits: Limits { max_connections: 16, timeout: 30 },
+/// };
+/// let vars = [("APP_LIMITS__MAX_CONNECTIONS", "128"), ("HOME", "/root")];
+/// config.patch(from_vars::<Config<Limits>>("APP", vars)?);
+/// assert_eq!((config.limits.max_connections, config.limits.timeout), (128, 30));
+///
+/// let vars = [("APP_LIMITS__MAX_CONNECTIONS", "many"), ("APP_LIMITS__TIMEOUT", "-1")];
+/// let Err(error) = from_vars::<Config<Limits>>("APP", vars) else { unreachable!() };
+/// let names: Vec<_> = error.errors().iter().map(|error| error.name()).collect();
+/// assert_eq!(names, ["APP_LIMITS__MAX_CONNECTIONS", "APP_LIMITS__TIMEOUT"]);
+/// # Ok::<(), patchable::env::EnvError>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns an error listing every variable whose name is malformed, clashes with another one, or
+/// whose value cannot be deserialized.
+pub fn from_vars<T>(
+    prefix: &str,
+    vars: impl IntoIterator<Item = (impl AsRef<str>, impl Into<String>)>,
+) -> Result<T::Patch, EnvError>
+where
+    T: Patchable,
+    T::Patch: PartialPatch + DeserializeOwned,
+{
+    let mut errors = Vec::new();
+    let mut root = BTreeMap::new();
+    let mut accepted = Vec::new();
+    for (name, value) in vars {
+        let name = name.as_ref();
+        let Some(path) = name
+            .strip_prefix(prefix)
+            .and_then(|rest| rest.strip_prefix('_'))
+        else {
+            continue;
+        };
+        let segments: Vec<String> = path.split("__").map(str::to_lowercase).collect();
+        if segments.iter().any(String::is_empty) {
+            errors.push(EnvVarError::new(name, "malformed variable name"));
+            continue;
+        }
+
+        // Each variable is deserialized on its own first, so that every malformed one is found.
+        let value = value.into();
+        let single = EnvNode::from_segments(&segments, name, value.clone());
+        if let Err(error) = T::Patch::deserialize(single) {
+            errors.push(EnvVarError::new(name, error));
+            continue;
+        }
+        let variable = (segments.clone(), name.to_string(), value.clone());
+        match EnvNode::insert(&mut root, segments, name, value) {
+            Ok(()) => accepted.push(variable),
+            Err(other) => {
+                let message = alloc::format!("clashes with `{other}`");
+                errors.push(EnvVarError::new(name, message));
+            }
+        }
+    }
+    if !errors.is_empty() {
+        errors.sort_by(|a, b| a.name.cmp(&b.name));
+        return Err(EnvError { errors });
+    }
+
+    T::Patch::deserialize(EnvNode::Table(root)).map_err(|error| EnvError {
+        errors: vec![blame::<T>(accepted, error)],
+    })
+}
+
+/// Names the variable that makes the patch of `T` fail to deserialize along with the variables
+/// before it by name, when each of them deserializes on its own.
+///
+/// Without any variable, the error is not caused by one, and is left unnamed.
+fn blame<T>(mut vars: Vec<(Vec<String>, String, String)>, error: ValueError) -> EnvVarError
+where
+    T: Patchable,
+    T::Patch: DeserializeOwned,
+{
+    vars.sort_by(|(_, a, _), (_, b, _)| a.cmp(b));
+    let mut root = BTreeMap::new();
+    for (segments, name, value) in vars {
+        // The variables were inserted into a table once already, without clashes.
+        let _ = EnvNode::insert(&mut root, segments, &name, value);
+        if let Err(error) = T::Patch::deserialize(EnvNode::Table(root.clone())) {
+            return EnvVarError::new(&name, error);
+        }
+    }
+    EnvVarError::new("", error)
+}
+
+/// An error listing the malformed environment variables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvError {
+    errors: Vec<EnvVarError>,
+}
+
+impl EnvError {
+    /// Returns the error of each malformed variable, ordered by name.
+    pub fn errors(&self) -> &[EnvVarError] {
+        &self.errors
+    }
+}
+
+impl fmt::Display for EnvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, error) in self.errors.iter().enumerate() {
+            if index > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{error}")?;
+        }
+        Ok(())
+    }
+}
+
+impl core::error::Error for EnvError {}
+
+/// The error of a single malformed environment variable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvVarError {
+    name: String,
+    message: String,
+}
+
+impl EnvVarError {
+    fn new(name: &str, message: impl fmt::Display) -> Self {
+        Self {
+            name: name.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    /// Returns the full name of the variable, or an empty string for an error that no single
+    /// variable causes.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the description of the error.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for EnvVarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.name.is_empty() {
+            return f.write_str(&self.message);
+        }
+        write!(f, "invalid variable `{}`: {}", self.name, self.message)
+    }
+}
+
+impl core::error::Error for EnvVarError {}
+
+/// Environment variables, as a tree of tables keyed by the segments of their names.
+#[derive(Clone)]
+enum EnvNode {
+    Value { name: String, value: String },
+    Table(BTreeMap<String, EnvNode>),
+}
+
+impl EnvNode {
+    /// Builds the tree holding a single variable.
+    fn from_segments(segments: &[String], name: &str, value: String) -> Self {
+        let mut node = EnvNode::Value {
+            name: name.to_string(),
+            value,
+        };
+        for segment in segments.iter().rev() {
+            node = EnvNode::Table(BTreeMap::from([(segment.clone(), node)]));
+        }
+        node
+    }
+
+    /// Inserts a variable into `table`, or returns the name of the variable it clashes with.
+    fn insert(
+        mut table: &mut BTreeMap<String, EnvNode>,
+        segments: Vec<String>,
+        name: &str,
+        value: String,
+    ) -> Result<(), String> {
+        let last = segments.len() - 1;
+        for (index, segment) in segments.into_iter().enumerate() {
+            if index == last {
+                if let Some(other) = table.get(&segment) {
+                    return Err(other.first_name().to_string());
+                }
+                let value = EnvNode::Value {
+                    name: name.to_string(),
+                    value,
+                };
+                table.insert(segment, value);
+                return Ok(());
+            }
+            match table
+                .entry(segment)
+                .or_insert_with(|| EnvNode::Table(BTreeMap::new()))
+            {
+                EnvNode::Table(inner) => table = inner,
+                EnvNode::Value { name, .. } => return Err(name.clone()),
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the name of the first variable of this tree.
+    fn first_name(&self) -> &str {
+        match self {
+            EnvNode::Value { name, .. } => name,
+            EnvNode::Table(table) => table.values().next().map_or("", EnvNode::first_name),
+        }
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ValueError> {
+                match self {
+                    EnvNode::Value { value, .. } => match value.parse() {
+                        Ok(parsed) => visitor.$visit(parsed),
+                        Err(error) => Err(ValueError::custom(format_args!(
+                            "invalid value `{value}`: {error}"
+                        ))),
+                    },
+                    table => table.deserialize_any(visitor),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for EnvNode {
+    type Error = ValueError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ValueError> {
+        match self {
+            EnvNode::Value { value, .. } => visitor.visit_string(value),
+            EnvNode::Table(table) => visitor.visit_map(MapDeserializer::new(table.into_iter())),
+        }
+    }
+
+    deserialize_parsed! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ValueError> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, ValueError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ValueError> {
+        match self {
+            EnvNode::Value { name, value } => {
+                let items = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .map(|item| EnvNode::Value {
+                        name: name.clone(),
+                        value: item.to_string(),
+                    });
+                visitor.visit_seq(SeqDeserializer::new(items))
+            }
+            table => table.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        enum_name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, ValueError> {
+        match self {
+            EnvNode::Value { value, .. } => value
+                .into_deserializer()
+                .deserialize_enum(enum_name, variants, visitor),
+            table => table.deserialize_any(visitor),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        str string bytes byte_buf unit unit_struct tuple tuple_struct map struct identifier
+        ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, ValueError> for EnvNode {
+    type Deserializer = Self;
+
+    #[inline(always)]
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
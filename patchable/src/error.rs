@@ -0,0 +1,41 @@
+//! # Patch Errors
+//!
+//! The error returned by derived [`TryPatch`](crate::TryPatch) implementations.
+
+use core::convert::Infallible;
+use core::fmt;
+
+/// An error raised while applying a patch with a derived
+/// [`TryPatch`](crate::TryPatch) implementation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PatchError {
+    /// Applying the delta of a `#[patchable(delta = "checked")]` field overflowed.
+    Overflow {
+        /// The name (or index, for tuple structs) of the field.
+        field: &'static str,
+    },
+    /// The patched value failed the `#[patchable(invariant = ...)]` check of its struct.
+    Invariant {
+        /// The message returned by the check.
+        message: &'static str,
+    },
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Overflow { field } => write!(f, "applying the delta to `{field}` overflowed"),
+            Self::Invariant { message } => write!(f, "the patched value is invalid: {message}"),
+        }
+    }
+}
+
+impl core::error::Error for PatchError {}
+
+/// Lets infallible nested patches be applied inside a derived `try_patch`.
+impl From<Infallible> for PatchError {
+    fn from(value: Infallible) -> Self {
+        match value {}
+    }
+}
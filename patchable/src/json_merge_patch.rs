@@ -0,0 +1,205 @@
+//! # JSON Merge Patch
+//!
+//! [`apply_merge_patch`] applies a [JSON Merge Patch](https://www.rfc-editor.org/rfc/rfc7396)
+//! document, such as the body of an HTTP `PATCH` request, to a type deriving [`JsonMergePatch`].
+//!
+//! The derive matches the keys of the document against the fields of the struct, so only the
+//! fields present in the document are deserialized and applied:
+//!
+//! - the value of a plain field replaces it, and `null` clears an `Option` field,
+//! - an object given for a `#[patchable]` field is merged into the nested struct,
+//! - the value of another field is its patch value (e.g., the difference of a
+//!   `#[patchable(delta)]` field), applied like [`Patch::patch`],
+//! - keys of skipped fields and unknown keys are rejected.
+//!
+//! The whole document is checked before any field is written, so a rejected document leaves the
+//! value unchanged. Errors locate the offending value with a JSON Pointer.
+//!
+//! This module is available with the `json-merge-patch` feature.
+
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+
+use serde_json::Value;
+
+use crate::Patch;
+
+/// A type that can apply JSON Merge Patch documents.
+///
+/// ## Usage
+///
+/// ```rust
+/// use patchable::{JsonMergePatch, Patch, Patchable, apply_merge_patch};
+/// use serde_json::json;
+///
+/// #[derive(Debug, PartialEq, Patchable, Patch, JsonMergePatch)]
+/// struct Address {
+///     city: String,
+///     zip: Option<String>,
+/// }
+///
+/// #[derive(Debug, PartialEq, Patchable, Patch, JsonMergePatch)]
+/// struct User<A> {
+///     name: String,
+///     #[patchable]
+///     address: A,
+/// }
+///
+/// let mut user = User {
+///     name: "Ada".to_owned(),
+///     address: Address { city: "London".to_owned(), zip: Some("NW1".to_owned()) },
+/// };
+/// apply_merge_patch(&mut user, &json!({ "address": { "city": "Paris", "zip": null } })).unwrap();
+/// assert_eq!(user.address, Address { city: "Paris".to_owned(), zip: None });
+///
+/// let error = apply_merge_patch(&mut user, &json!({ "name": "Bob", "age": 36 })).unwrap_err();
+/// assert_eq!(error.path(), "/age");
+/// assert_eq!(user.name, "Ada");
+/// ```
+pub trait JsonMergePatch: Patch {
+    /// Checks the JSON object `patch` against the fields of `self`, and writes them if `apply`
+    /// is set. Called by [`apply_merge_patch`].
+    #[doc(hidden)]
+    fn merge_json(&mut self, patch: &Value, apply: bool) -> Result<(), MergePatchError>;
+}
+
+/// Applies the JSON Merge Patch document `patch` to `target`.
+///
+/// # Errors
+///
+/// Returns an error, leaving `target` unchanged, if the document does not match the fields of
+/// `target`.
+pub fn apply_merge_patch<T: JsonMergePatch + ?Sized>(
+    target: &mut T,
+    patch: &Value,
+) -> Result<(), MergePatchError> {
+    target.merge_json(patch, false)?;
+    target.merge_json(patch, true)
+}
+
+/// An error raised by [`apply_merge_patch`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum MergePatchError {
+    /// The document, or the value of a `#[patchable]` field, is not a JSON object.
+    NotAnObject {
+        /// The JSON Pointer to the value.
+        path: String,
+    },
+    /// The key does not name a patched field.
+    UnknownField {
+        /// The JSON Pointer to the key.
+        path: String,
+    },
+    /// The value cannot be deserialized into the patch value of its field.
+    InvalidValue {
+        /// The JSON Pointer to the value.
+        path: String,
+        /// The deserialization error.
+        source: serde_json::Error,
+    },
+}
+
+impl MergePatchError {
+    /// Returns the JSON Pointer to the offending value, empty for the document itself.
+    pub fn path(&self) -> &str {
+        match self {
+            Self::NotAnObject { path }
+            | Self::UnknownField { path }
+            | Self::InvalidValue { path, .. } => path,
+        }
+    }
+
+    /// Prefixes the path with the given key of the enclosing object.
+    fn within(mut self, key: &str) -> Self {
+        let (Self::NotAnObject { path }
+        | Self::UnknownField { path }
+        | Self::InvalidValue { path, .. }) = &mut self;
+        *path = format!("/{}{path}", escape(key));
+        self
+    }
+}
+
+impl fmt::Display for MergePatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotAnObject { path } => write!(f, "expected a JSON object at `{path}`"),
+            Self::UnknownField { path } => write!(f, "unknown field at `{path}`"),
+            Self::InvalidValue { path, source } => write!(f, "invalid value at `{path}`: {source}"),
+        }
+    }
+}
+
+impl core::error::Error for MergePatchError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::InvalidValue { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Escapes `~` and `/` in a JSON Pointer segment.
+fn escape(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+/// Helpers used by the derived `merge_json`.
+#[doc(hidden)]
+pub mod __private {
+    use alloc::string::String;
+
+    pub use serde::de::DeserializeOwned;
+    pub use serde_json::{Map, Value};
+
+    use super::{JsonMergePatch, MergePatchError};
+
+    /// Returns the members of `patch`, which must be an object.
+    #[inline(always)]
+    pub fn as_object(patch: &Value) -> Result<&Map<String, Value>, MergePatchError> {
+        patch.as_object().ok_or(MergePatchError::NotAnObject {
+            path: String::new(),
+        })
+    }
+
+    /// Deserializes the whole document, for transparent structs.
+    #[inline(always)]
+    pub fn document_value<T: DeserializeOwned>(patch: &Value) -> Result<T, MergePatchError> {
+        T::deserialize(patch).map_err(|source| MergePatchError::InvalidValue {
+            path: String::new(),
+            source,
+        })
+    }
+
+    /// Deserializes the value of the field `key`.
+    #[inline(always)]
+    pub fn field_value<T: DeserializeOwned>(
+        value: &Value,
+        key: &str,
+    ) -> Result<T, MergePatchError> {
+        document_value(value).map_err(|error| error.within(key))
+    }
+
+    /// Merges the value of the nested field `key` into `target`.
+    #[inline(always)]
+    pub fn merge_field<T: JsonMergePatch + ?Sized>(
+        target: &mut T,
+        value: &Value,
+        key: &str,
+        apply: bool,
+    ) -> Result<(), MergePatchError> {
+        target
+            .merge_json(value, apply)
+            .map_err(|error| error.within(key))
+    }
+
+    /// Rejects the unknown key `key`.
+    #[inline(always)]
+    pub fn unknown_field(key: &str) -> MergePatchError {
+        MergePatchError::UnknownField {
+            path: String::new(),
+        }
+        .within(key)
+    }
+}
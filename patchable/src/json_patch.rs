@@ -0,0 +1,568 @@
+//! # JSON Patch
+//!
+//! [`apply_json_patch`] applies a [JSON Patch](https://www.rfc-editor.org/rfc/rfc6902) document,
+//! a list of `add`, `remove`, `replace`, `move`, `copy` and `test` operations, to a type deriving
+//! [`JsonPatch`]. [`JsonPatched`] exposes the same through [`TryPatch`].
+//!
+//! The JSON Pointer paths of the operations are resolved against the struct itself:
+//!
+//! - a segment names a non-skipped field (or its index, for tuple structs),
+//! - the rest of the path is resolved in the nested struct for `#[patchable]` fields,
+//! - and in the serialized value of other fields otherwise, so elements of collections such as
+//!   `Vec` or maps can be added, removed or replaced (`-` appends to an array).
+//!
+//! Fields hold their current value, as with [`Snapshot`](crate::Snapshot), rather than their
+//! patch value: `replace` sets a `#[patchable(delta)]` field to the given value. Removing a field
+//! clears it when it is an `Option`, and is rejected otherwise.
+//!
+//! Operations are applied in order to a copy of the value, which replaces it only once every
+//! operation succeeded, so a failed `test` operation acts as a precondition of the whole document.
+//! As in RFC 6902, `test` compares numbers by value, so `1` and `1.0` are equal.
+//!
+//! This module is available with the `json-patch` feature.
+
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{Patch, Patchable, TryPatch};
+
+/// A type whose fields can be addressed by the JSON Pointer paths of a JSON Patch document.
+///
+/// ## Usage
+///
+/// ```rust
+/// use patchable::{JsonPatch, Patch, Patchable, PatchOperation, apply_json_patch};
+/// use serde_json::json;
+///
+/// #[derive(Clone, Debug, PartialEq, Patchable, Patch, JsonPatch)]
+/// struct Profile {
+///     name: String,
+///     tags: Vec<String>,
+/// }
+///
+/// let mut profile = Profile { name: "ada".to_owned(), tags: vec!["admin".to_owned()] };
+/// let operations: Vec<PatchOperation> = serde_json::from_value(json!([
+///     { "op": "test", "path": "/name", "value": "ada" },
+///     { "op": "add", "path": "/tags/-", "value": "ops" },
+///     { "op": "replace", "path": "/name", "value": "Ada" },
+/// ]))
+/// .unwrap();
+///
+/// apply_json_patch(&mut profile, &operations).unwrap();
+/// assert_eq!(profile.name, "Ada");
+/// assert_eq!(profile.tags, ["admin", "ops"]);
+///
+/// // The `test` no longer holds, so nothing is applied.
+/// let error = apply_json_patch(&mut profile, &operations).unwrap_err();
+/// assert_eq!((error.operation(), error.path()), (0, "/name"));
+/// assert_eq!(profile.tags.len(), 2);
+/// ```
+pub trait JsonPatch: Patch {
+    /// Returns the serialized value at `path`.
+    #[doc(hidden)]
+    fn pointer_get(&self, path: &[String]) -> Result<Value, JsonPatchErrorKind>;
+
+    /// Writes `value` at `path`, inserting it into an array or object if `insert` is set
+    /// (`add`) and replacing an existing value otherwise (`replace`).
+    #[doc(hidden)]
+    fn pointer_set(
+        &mut self,
+        path: &[String],
+        value: Value,
+        insert: bool,
+    ) -> Result<(), JsonPatchErrorKind>;
+
+    /// Removes the value at `path` and returns it.
+    #[doc(hidden)]
+    fn pointer_remove(&mut self, path: &[String]) -> Result<Value, JsonPatchErrorKind>;
+}
+
+/// An operation of a JSON Patch document, deserialized from its JSON form.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOperation {
+    /// Adds `value` at `path`, replacing an existing field or inserting into a collection.
+    Add {
+        /// The JSON Pointer to the target.
+        path: String,
+        /// The value to add.
+        value: Value,
+    },
+    /// Removes the value at `path`.
+    Remove {
+        /// The JSON Pointer to the target.
+        path: String,
+    },
+    /// Replaces the existing value at `path` with `value`.
+    Replace {
+        /// The JSON Pointer to the target.
+        path: String,
+        /// The new value.
+        value: Value,
+    },
+    /// Removes the value at `from` and adds it at `path`.
+    Move {
+        /// The JSON Pointer to the value to move.
+        from: String,
+        /// The JSON Pointer to the target.
+        path: String,
+    },
+    /// Adds a copy of the value at `from` at `path`.
+    Copy {
+        /// The JSON Pointer to the value to copy.
+        from: String,
+        /// The JSON Pointer to the target.
+        path: String,
+    },
+    /// Checks that the value at `path` equals `value`.
+    Test {
+        /// The JSON Pointer to the target.
+        path: String,
+        /// The expected value.
+        value: Value,
+    },
+}
+
+impl PatchOperation {
+    /// Applies the operation, returning the failing path with the error.
+    fn apply<T: JsonPatch + ?Sized>(
+        &self,
+        target: &mut T,
+    ) -> Result<(), (&str, JsonPatchErrorKind)> {
+        match self {
+            Self::Add { path, value } => set(target, path, value.clone(), true),
+            Self::Remove { path } => remove(target, path).map(drop),
+            Self::Replace { path, value } => set(target, path, value.clone(), false),
+            Self::Move { from, path } => {
+                if path
+                    .strip_prefix(from.as_str())
+                    .is_some_and(|rest| rest.starts_with('/'))
+                {
+                    return Err((
+                        path,
+                        JsonPatchErrorKind::Unsupported("a value cannot be moved into itself"),
+                    ));
+                }
+                let value = remove(target, from)?;
+                set(target, path, value, true)
+            }
+            Self::Copy { from, path } => {
+                let value = get(target, from)?;
+                set(target, path, value, true)
+            }
+            Self::Test { path, value } if json_eq(&get(target, path)?, value) => Ok(()),
+            Self::Test { path, .. } => Err((path, JsonPatchErrorKind::TestFailed)),
+        }
+    }
+}
+
+/// Compares two values as the `test` operation does: numbers are equal when their values are,
+/// so `1` equals `1.0`, including inside arrays and objects.
+fn json_eq(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Number(left), Value::Number(right)) if left.is_f64() || right.is_f64() => {
+            left.as_f64() == right.as_f64()
+        }
+        (Value::Array(left), Value::Array(right)) => {
+            left.len() == right.len() && left.iter().zip(right).all(|(l, r)| json_eq(l, r))
+        }
+        (Value::Object(left), Value::Object(right)) => {
+            left.len() == right.len()
+                && left
+                    .iter()
+                    .all(|(key, l)| right.get(key).is_some_and(|r| json_eq(l, r)))
+        }
+        _ => left == right,
+    }
+}
+
+fn get<'p, T: JsonPatch + ?Sized>(
+    target: &T,
+    path: &'p str,
+) -> Result<Value, (&'p str, JsonPatchErrorKind)> {
+    parse_pointer(path)
+        .and_then(|segments| target.pointer_get(&segments))
+        .map_err(|kind| (path, kind))
+}
+
+fn set<'p, T: JsonPatch + ?Sized>(
+    target: &mut T,
+    path: &'p str,
+    value: Value,
+    insert: bool,
+) -> Result<(), (&'p str, JsonPatchErrorKind)> {
+    parse_pointer(path)
+        .and_then(|segments| target.pointer_set(&segments, value, insert))
+        .map_err(|kind| (path, kind))
+}
+
+fn remove<'p, T: JsonPatch + ?Sized>(
+    target: &mut T,
+    path: &'p str,
+) -> Result<Value, (&'p str, JsonPatchErrorKind)> {
+    parse_pointer(path)
+        .and_then(|segments| target.pointer_remove(&segments))
+        .map_err(|kind| (path, kind))
+}
+
+/// Applies the JSON Patch document `operations` to `target`, in order.
+///
+/// The operations are applied to a clone of `target`, which replaces it once all of them
+/// succeeded.
+///
+/// # Errors
+///
+/// Returns the error of the first failing operation, leaving `target` unchanged.
+pub fn apply_json_patch<T: JsonPatch + Clone>(
+    target: &mut T,
+    operations: &[PatchOperation],
+) -> Result<(), JsonPatchError> {
+    let mut draft = target.clone();
+    for (index, operation) in operations.iter().enumerate() {
+        operation
+            .apply(&mut draft)
+            .map_err(|(path, kind)| JsonPatchError {
+                operation: index,
+                path: path.to_owned(),
+                kind,
+            })?;
+    }
+    *target = draft;
+    Ok(())
+}
+
+/// A value patched with JSON Patch documents through [`TryPatch`].
+///
+/// ## Usage
+///
+/// ```rust
+/// use patchable::{JsonPatch, JsonPatched, Patch, Patchable, TryPatch};
+/// use serde_json::json;
+///
+/// #[derive(Clone, Patchable, Patch, JsonPatch)]
+/// struct Counter {
+///     value: u32,
+/// }
+///
+/// let mut counter = JsonPatched(Counter { value: 1 });
+/// let patch = serde_json::from_value(json!([{ "op": "replace", "path": "/value", "value": 2 }]));
+/// counter.try_patch(patch.unwrap()).unwrap();
+/// assert_eq!(counter.0.value, 2);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct JsonPatched<T>(pub T);
+
+impl<T> Patchable for JsonPatched<T> {
+    type Patch = Vec<PatchOperation>;
+}
+
+impl<T: JsonPatch + Clone> TryPatch for JsonPatched<T> {
+    type Error = JsonPatchError;
+
+    #[inline(always)]
+    fn try_patch(&mut self, patch: Self::Patch) -> Result<(), Self::Error> {
+        apply_json_patch(&mut self.0, &patch)
+    }
+}
+
+/// An error raised by [`apply_json_patch`].
+#[derive(Debug)]
+pub struct JsonPatchError {
+    operation: usize,
+    path: String,
+    kind: JsonPatchErrorKind,
+}
+
+impl JsonPatchError {
+    /// Returns the index of the failing operation in the document.
+    pub const fn operation(&self) -> usize {
+        self.operation
+    }
+
+    /// Returns the JSON Pointer at which the operation failed (its `from` or `path`).
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the reason of the failure.
+    pub const fn kind(&self) -> &JsonPatchErrorKind {
+        &self.kind
+    }
+}
+
+impl fmt::Display for JsonPatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "operation {} failed at `{}`: {}",
+            self.operation, self.path, self.kind
+        )
+    }
+}
+
+impl core::error::Error for JsonPatchError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match &self.kind {
+            JsonPatchErrorKind::InvalidValue(source) => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// The reason a JSON Patch operation failed.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum JsonPatchErrorKind {
+    /// The path is not a valid JSON Pointer.
+    InvalidPointer,
+    /// No value exists at the path (or, for `add`, at its parent).
+    NotFound,
+    /// The value does not fit the field at the path.
+    InvalidValue(serde_json::Error),
+    /// The value of a `test` operation differs from the current one.
+    TestFailed,
+    /// The operation would change the shape of a struct, such as removing a required field.
+    Unsupported(&'static str),
+}
+
+impl fmt::Display for JsonPatchErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidPointer => f.write_str("invalid JSON Pointer"),
+            Self::NotFound => f.write_str("no value at this path"),
+            Self::InvalidValue(source) => write!(f, "invalid value: {source}"),
+            Self::TestFailed => f.write_str("the value differs from the expected one"),
+            Self::Unsupported(reason) => f.write_str(reason),
+        }
+    }
+}
+
+/// Splits a JSON Pointer into its unescaped segments.
+fn parse_pointer(pointer: &str) -> Result<Vec<String>, JsonPatchErrorKind> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    let Some(segments) = pointer.strip_prefix('/') else {
+        return Err(JsonPatchErrorKind::InvalidPointer);
+    };
+    segments
+        .split('/')
+        .map(|segment| {
+            let mut unescaped = String::with_capacity(segment.len());
+            let mut chars = segment.chars();
+            while let Some(c) = chars.next() {
+                let c = match c {
+                    '~' => match chars.next() {
+                        Some('0') => '~',
+                        Some('1') => '/',
+                        _ => return Err(JsonPatchErrorKind::InvalidPointer),
+                    },
+                    c => c,
+                };
+                unescaped.push(c);
+            }
+            Ok(unescaped)
+        })
+        .collect()
+}
+
+/// Helpers used by the derived `JsonPatch` implementations.
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::string::String;
+    pub use serde::Serialize;
+    pub use serde::de::DeserializeOwned;
+    pub use serde_json::{Map, Value};
+
+    use super::JsonPatchErrorKind;
+
+    /// Returns the serialized value at `path` in `field`.
+    pub fn leaf_get<T: Serialize>(field: &T, path: &[String]) -> Result<Value, JsonPatchErrorKind> {
+        let value = to_value(field)?;
+        get(&value, path).cloned()
+    }
+
+    /// Writes `value` at `path` in `field`, through its serialized value for a non-empty path.
+    pub fn leaf_set<T: Serialize + DeserializeOwned>(
+        field: &mut T,
+        path: &[String],
+        value: Value,
+        insert: bool,
+    ) -> Result<(), JsonPatchErrorKind> {
+        let value = if path.is_empty() {
+            value
+        } else {
+            let mut current = to_value(field)?;
+            set(&mut current, path, value, insert)?;
+            current
+        };
+        *field = from_value(value)?;
+        Ok(())
+    }
+
+    /// Removes the value at `path` in `field`; an empty path clears an `Option` field.
+    pub fn leaf_remove<T: Serialize + DeserializeOwned>(
+        field: &mut T,
+        path: &[String],
+    ) -> Result<Value, JsonPatchErrorKind> {
+        let mut current = to_value(field)?;
+        if path.is_empty() {
+            *field = serde_json::from_value(Value::Null).map_err(|_| {
+                JsonPatchErrorKind::Unsupported("a required field cannot be removed")
+            })?;
+            return Ok(current);
+        }
+        let removed = remove(&mut current, path)?;
+        *field = from_value(current)?;
+        Ok(removed)
+    }
+
+    /// Rejects the removal of a whole struct.
+    pub const fn remove_struct() -> JsonPatchErrorKind {
+        JsonPatchErrorKind::Unsupported("a struct cannot be removed")
+    }
+
+    /// Rejects a segment that names no field.
+    pub const fn not_found() -> JsonPatchErrorKind {
+        JsonPatchErrorKind::NotFound
+    }
+
+    /// Returns the members of a value replacing a whole struct.
+    pub fn into_object(value: Value) -> Result<Map<String, Value>, JsonPatchErrorKind> {
+        match value {
+            Value::Object(object) => Ok(object),
+            other => Err(invalid_value(format_args!(
+                "invalid type: {}, expected an object",
+                kind_of(&other)
+            ))),
+        }
+    }
+
+    /// Takes the value of the field `key` from the members of a value replacing a struct.
+    pub fn take_field(
+        object: &mut Map<String, Value>,
+        key: &str,
+    ) -> Result<Value, JsonPatchErrorKind> {
+        object
+            .remove(key)
+            .ok_or_else(|| invalid_value(format_args!("missing field `{key}`")))
+    }
+
+    /// Rejects the members left over once every field was taken.
+    pub fn deny_unknown_fields(object: &Map<String, Value>) -> Result<(), JsonPatchErrorKind> {
+        match object.keys().next() {
+            Some(key) => Err(invalid_value(format_args!("unknown field `{key}`"))),
+            None => Ok(()),
+        }
+    }
+
+    fn invalid_value(message: core::fmt::Arguments<'_>) -> JsonPatchErrorKind {
+        JsonPatchErrorKind::InvalidValue(<serde_json::Error as serde::de::Error>::custom(message))
+    }
+
+    fn kind_of(value: &Value) -> &'static str {
+        match value {
+            Value::Null => "null",
+            Value::Bool(_) => "boolean",
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
+        }
+    }
+
+    fn to_value<T: Serialize>(field: &T) -> Result<Value, JsonPatchErrorKind> {
+        serde_json::to_value(field).map_err(JsonPatchErrorKind::InvalidValue)
+    }
+
+    fn from_value<T: DeserializeOwned>(value: Value) -> Result<T, JsonPatchErrorKind> {
+        serde_json::from_value(value).map_err(JsonPatchErrorKind::InvalidValue)
+    }
+
+    /// Parses an array index, which has no sign or leading zero.
+    fn index(segment: &str) -> Result<usize, JsonPatchErrorKind> {
+        let is_canonical = segment == "0"
+            || (!segment.starts_with('0') && segment.bytes().all(|byte| byte.is_ascii_digit()));
+        match segment.parse() {
+            Ok(index) if is_canonical => Ok(index),
+            _ => Err(JsonPatchErrorKind::NotFound),
+        }
+    }
+
+    fn get<'v>(value: &'v Value, path: &[String]) -> Result<&'v Value, JsonPatchErrorKind> {
+        path.iter().try_fold(value, |value, segment| {
+            match value {
+                Value::Object(object) => object.get(segment),
+                Value::Array(array) => array.get(index(segment)?),
+                _ => None,
+            }
+            .ok_or(JsonPatchErrorKind::NotFound)
+        })
+    }
+
+    fn get_mut<'v>(
+        value: &'v mut Value,
+        path: &[String],
+    ) -> Result<&'v mut Value, JsonPatchErrorKind> {
+        path.iter().try_fold(value, |value, segment| {
+            match value {
+                Value::Object(object) => object.get_mut(segment),
+                Value::Array(array) => array.get_mut(index(segment)?),
+                _ => None,
+            }
+            .ok_or(JsonPatchErrorKind::NotFound)
+        })
+    }
+
+    /// Writes `new` at the non-empty `path` in `value`, as `add` or `replace`.
+    fn set(
+        value: &mut Value,
+        path: &[String],
+        new: Value,
+        insert: bool,
+    ) -> Result<(), JsonPatchErrorKind> {
+        let (last, parent_path) = path.split_last().ok_or(JsonPatchErrorKind::NotFound)?;
+        match get_mut(value, parent_path)? {
+            Value::Object(object) if insert => {
+                object.insert(last.clone(), new);
+            }
+            Value::Object(object) => {
+                *object.get_mut(last).ok_or(JsonPatchErrorKind::NotFound)? = new;
+            }
+            Value::Array(array) if insert && last == "-" => array.push(new),
+            Value::Array(array) if insert => {
+                let index = index(last)?;
+                if index > array.len() {
+                    return Err(JsonPatchErrorKind::NotFound);
+                }
+                array.insert(index, new);
+            }
+            Value::Array(array) => {
+                *array
+                    .get_mut(index(last)?)
+                    .ok_or(JsonPatchErrorKind::NotFound)? = new;
+            }
+            _ => return Err(JsonPatchErrorKind::NotFound),
+        }
+        Ok(())
+    }
+
+    /// Removes the value at the non-empty `path` in `value`.
+    fn remove(value: &mut Value, path: &[String]) -> Result<Value, JsonPatchErrorKind> {
+        let (last, parent_path) = path.split_last().ok_or(JsonPatchErrorKind::NotFound)?;
+        match get_mut(value, parent_path)? {
+            Value::Object(object) => object.remove(last),
+            Value::Array(array) => {
+                let index = index(last)?;
+                (index < array.len()).then(|| array.remove(index))
+            }
+            _ => None,
+        }
+        .ok_or(JsonPatchErrorKind::NotFound)
+    }
+}
@@ -0,0 +1,221 @@
+//! # Layered Configuration
+//!
+//! [`Layered`] builds a value from layers of partial patches (see [`partial`](crate::partial)),
+//! applied in order on top of `Default::default()` or a given base value:
+//!
+//! - [`Layered::source`] deserializes a layer from any serde [`Deserializer`], such as the
+//!   parsed contents of a JSON or TOML file,
+//! - [`Layered::env`] reads a layer from environment variables named by field path (see
+//!   [`env`](crate::env)), so that `APP_LIMITS__MAX_CONNECTIONS=10` sets
+//!   `limits.max_connections` with the prefix `APP`,
+//! - [`Layered::layer`] adds an explicit patch, such as overrides from the command line.
+//!
+//! The [`Provenance`] returned along with the value records which layer last set each field.
+//!
+//! This module is available with the `layered` feature.
+
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use serde::de::{Deserialize, DeserializeOwned, Deserializer};
+
+use crate::{PartialPatch, Patch, Patchable, env};
+
+/// The name of the layer read by [`Layered::env`].
+pub const ENV_LAYER: &str = "env";
+
+/// A value built from layers of partial patches.
+///
+/// ## Usage
+///
+/// ```rust
+/// use patchable::layered::Layered;
+/// use patchable::{Patch, Patchable};
+///
+/// #[derive(Debug, Default, Patchable, Patch)]
+/// #[patchable(partial)]
+/// struct Limits {
+///     max_connections: u32,
+///     timeout: u64,
+/// }
+///
+/// #[derive(Debug, Default, Patchable, Patch)]
+/// #[patchable(partial)]
+/// struct Config<L> {
+///     name: String,
+///     #[patchable]
+///     limits: L,
+/// }
+///
+/// let file = r#"{ "name": "api", "limits": { "max_connections": 16, "timeout": 30 } }"#;
+/// let env = [("APP_LIMITS__MAX_CONNECTIONS", "128"), ("HOME", "/root")];
+///
+/// let (config, provenance) = Layered::<Config<Limits>>::new()
+///     .source("config.json", &mut serde_json::Deserializer::from_str(file))?
+///     .env("APP", env)?
+///     .build();
+///
+/// assert_eq!(config.limits.max_connections, 128);
+/// assert_eq!(provenance.layer_of("limits.max_connections"), Some("env"));
+/// assert_eq!(provenance.layer_of("limits.timeout"), Some("config.json"));
+/// # Ok::<(), patchable::layered::LayerError>(())
+/// ```
+pub struct Layered<T: Patchable> {
+    layers: Vec<(String, T::Patch)>,
+}
+
+impl<T: Patchable> Layered<T> {
+    /// Returns a builder without layers.
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self { layers: Vec::new() }
+    }
+
+    /// Adds the layer `name`, applying `patch`.
+    #[must_use]
+    pub fn layer(mut self, name: impl Into<String>, patch: T::Patch) -> Self {
+        self.layers.push((name.into(), patch));
+        self
+    }
+
+    /// Adds the layer `name`, applying the patch deserialized from `deserializer`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the patch cannot be deserialized.
+    pub fn source<'de, D>(
+        self,
+        name: impl Into<String>,
+        deserializer: D,
+    ) -> Result<Self, LayerError>
+    where
+        D: Deserializer<'de>,
+        T::Patch: Deserialize<'de>,
+    {
+        let name = name.into();
+        match T::Patch::deserialize(deserializer) {
+            Ok(patch) => Ok(self.layer(name, patch)),
+            Err(error) => Err(LayerError {
+                layer: name,
+                message: error.to_string(),
+            }),
+        }
+    }
+
+    /// Adds the [`ENV_LAYER`] layer, applying the patch read by [`env::from_vars`] from the
+    /// variables `vars` whose name starts with `prefix` and `_`.
+    ///
+    /// Pass `std::env::vars()` to read the environment of the process.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming every variable whose name is malformed, clashes with another one,
+    /// or whose value cannot be deserialized.
+    pub fn env(
+        self,
+        prefix: &str,
+        vars: impl IntoIterator<Item = (impl AsRef<str>, impl Into<String>)>,
+    ) -> Result<Self, LayerError>
+    where
+        T::Patch: PartialPatch + DeserializeOwned,
+    {
+        match env::from_vars::<T>(prefix, vars) {
+            Ok(patch) => Ok(self.layer(ENV_LAYER, patch)),
+            Err(error) => Err(LayerError {
+                layer: ENV_LAYER.to_string(),
+                message: error.to_string(),
+            }),
+        }
+    }
+}
+
+impl<T> Layered<T>
+where
+    T: Patch,
+    T::Patch: PartialPatch,
+{
+    /// Applies the layers in order to `T::default()`.
+    pub fn build(self) -> (T, Provenance)
+    where
+        T: Default,
+    {
+        self.build_on(T::default())
+    }
+
+    /// Applies the layers in order to `base`.
+    pub fn build_on(self, mut base: T) -> (T, Provenance) {
+        let mut provenance = Provenance::default();
+        for (name, patch) in self.layers {
+            patch.for_each_set_field(|path| {
+                provenance.fields.insert(path.to_string(), name.clone());
+            });
+            base.patch(patch);
+        }
+        (base, provenance)
+    }
+}
+
+impl<T: Patchable> Default for Layered<T> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Patchable> fmt::Debug for Layered<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.layers.iter().map(|(name, _)| name))
+            .finish()
+    }
+}
+
+/// The layer that last set each field of a value built by [`Layered`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Provenance {
+    fields: BTreeMap<String, String>,
+}
+
+impl Provenance {
+    /// Returns the name of the last layer that set the field at the dotted `path`, or `None` if
+    /// the field keeps its default or base value.
+    pub fn layer_of(&self, path: &str) -> Option<&str> {
+        self.fields.get(path).map(String::as_str)
+    }
+
+    /// Iterates over the paths of the set fields, in order, with the name of their layer.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.fields
+            .iter()
+            .map(|(path, layer)| (path.as_str(), layer.as_str()))
+    }
+}
+
+/// An error raised while reading a layer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayerError {
+    layer: String,
+    message: String,
+}
+
+impl LayerError {
+    /// Returns the name of the layer.
+    pub fn layer(&self) -> &str {
+        &self.layer
+    }
+
+    /// Returns the description of the error.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for LayerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid layer `{}`: {}", self.layer, self.message)
+    }
+}
+
+impl core::error::Error for LayerError {}
@@ -2,9 +2,21 @@
 //!
 //! A crate for handling partial updates to data structures.
 //!
-//! This crate provides the [`Patchable`], [`Patch`], and [`TryPatch`] traits, along with
-//! derive macros for `Patchable` and `Patch`, and an attribute macro `patchable_model`
-//! re-exported from `patchable_macro` for easy derivation.
+//! This crate provides the [`Patchable`], [`Patch`], [`TryPatch`] and [`ValidatePatch`] traits,
+//! along with derive macros for `Patchable`, `Patch`, `PatchWithContext`, `ReportPatch`,
+//! `MaskedPatch`, `PatchEvents`, `Snapshot` and `FromPatch`, and an attribute macro
+//! `patchable_model` re-exported from `patchable_macro` for easy derivation. With the
+//! `json-merge-patch` feature, [`json_merge_patch`] applies JSON Merge Patch documents to types
+//! deriving `JsonMergePatch`, and with the `json-patch` feature, [`json_patch`] applies JSON Patch
+//! operations to types deriving `JsonPatch`. With the `serde` feature, [`path`] reads and writes
+//! the fields of types deriving `PatchPath` by a dotted path. Structs marked with
+//! `#[patchable(partial)]` get [`partial`] patches with optional fields, which the `env` feature
+//! reads from environment variables with [`env::from_env`], the `clap` feature reads from
+//! command-line flags with [`cli::PatchArgs`], and the `layered` feature stacks into a value with
+//! [`layered::Layered`]. With the `std` feature, [`reload::ConfigReloader`] applies the changes of
+//! a configuration file to a shared value. [`Versioned`] rejects patches written against an
+//! outdated version of a value, and with the `alloc` feature, [`merge3`] merges concurrent
+//! patches of types deriving `Merge3`.
 //!
 //! ## Motivation
 //!
@@ -17,7 +29,66 @@
 
 extern crate self as patchable;
 
-pub use patchable_macro::{Patch, Patchable, patchable_model};
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "clap")]
+pub mod cli;
+pub mod delta;
+#[cfg(feature = "env")]
+pub mod env;
+mod error;
+#[cfg(feature = "json-merge-patch")]
+pub mod json_merge_patch;
+#[cfg(feature = "json-patch")]
+pub mod json_patch;
+#[cfg(feature = "layered")]
+pub mod layered;
+pub mod mask;
+pub mod merge;
+#[cfg(feature = "alloc")]
+pub mod merge3;
+pub mod partial;
+#[cfg(feature = "serde")]
+pub mod path;
+#[cfg(feature = "std")]
+pub mod reload;
+pub mod report;
+pub mod versioned;
+
+#[cfg(feature = "clap")]
+pub use cli::PatchArgs;
+pub use delta::Delta;
+pub use error::PatchError;
+#[cfg(feature = "json-merge-patch")]
+pub use json_merge_patch::{JsonMergePatch, MergePatchError, apply_merge_patch};
+#[cfg(feature = "json-patch")]
+pub use json_patch::{JsonPatch, JsonPatchError, JsonPatched, PatchOperation, apply_json_patch};
+pub use mask::{FieldMask, MaskedPatch};
+pub use merge::MergeStrategy;
+#[cfg(feature = "alloc")]
+pub use merge3::{Conflicts, Merge3, Resolution};
+pub use partial::PartialPatch;
+#[cfg(feature = "json-merge-patch")]
+pub use patchable_macro::JsonMergePatch;
+#[cfg(feature = "json-patch")]
+pub use patchable_macro::JsonPatch;
+#[cfg(feature = "alloc")]
+pub use patchable_macro::Merge3;
+#[cfg(feature = "clap")]
+pub use patchable_macro::PatchArgs;
+#[cfg(feature = "serde")]
+pub use patchable_macro::PatchPath;
+pub use patchable_macro::{
+    FromPatch, MaskedPatch, Patch, PatchEvents, PatchWithContext, Patchable, ReportPatch, Snapshot,
+    patchable_model,
+};
+#[cfg(feature = "serde")]
+pub use path::{PatchPath, PathError, PathValue};
+pub use report::{ChangeSet, PatchReport, ReportPatch};
+pub use versioned::{Versioned, VersionedError, VersionedPatch};
 
 /// A type that declares a companion patch type.
 ///
@@ -107,6 +178,196 @@ pub trait Patch: Patchable {
     fn patch(&mut self, patch: Self::Patch);
 }
 
+/// A type that can be patched with access to a context, to rebuild the fields the patch skips.
+///
+/// `#[derive(PatchWithContext)]` applies the patch like [`Patch::patch`], then calls the function
+/// given with `#[patchable(skip, rehydrate = path)]` for each such field, with the field and the
+/// context (for example, to re-bind a handle from a connection pool or a registry).
+///
+/// ## Usage
+///
+/// ```rust
+/// use patchable::{Patch, PatchWithContext, Patchable};
+///
+/// struct Registry {
+///     names: Vec<&'static str>,
+/// }
+///
+/// fn rebind(name: &mut &'static str, registry: &Registry) {
+///     *name = registry.names[0];
+/// }
+///
+/// #[derive(Patchable, Patch, PatchWithContext)]
+/// #[patchable(context = Registry)]
+/// struct Worker {
+///     id: usize,
+///     #[patchable(skip, rehydrate = rebind)]
+///     name: &'static str,
+/// }
+///
+/// # #[cfg(feature = "serde")]
+/// # {
+/// let registry = Registry { names: vec!["pool-1"] };
+/// let mut worker = Worker { id: 0, name: "" };
+/// worker.patch_with(serde_json::from_str(r#"{"id": 3}"#).unwrap(), &registry);
+/// assert_eq!((worker.id, worker.name), (3, "pool-1"));
+/// # }
+/// ```
+pub trait PatchWithContext<Ctx: ?Sized>: Patchable {
+    /// Applies the given patch, using `ctx` to rebuild the skipped fields.
+    fn patch_with(&mut self, patch: Self::Patch, ctx: &Ctx);
+}
+
+/// A type whose patches can be split into, and applied as, one event per field.
+///
+/// `#[derive(PatchEvents)]` generates a `<Struct>Event` enum with a variant per non-skipped
+/// field, named in `UpperCamelCase`, carrying the value of that field in the patch (the nested
+/// patch for `#[patchable]` fields). With the `serde` feature, events implement `Serialize` and
+/// `Deserialize`, so they can be persisted one by one in an event store.
+///
+/// Applying every event of [`into_events`](PatchEvents::into_events) is equivalent to applying
+/// the patch.
+///
+/// ## Usage
+///
+/// ```rust
+/// use patchable::{Patch, PatchEvents, Patchable, Snapshot};
+///
+/// #[derive(Debug, PartialEq, Patchable, Patch, Snapshot, PatchEvents)]
+/// struct Order {
+///     status: &'static str,
+///     #[patchable(merge = "add")]
+///     items: u32,
+/// }
+///
+/// let mut order = Order { status: "open", items: 1 };
+/// order.apply_event(OrderEvent::Items(2));
+/// order.apply_event(OrderEvent::Status("paid"));
+/// assert_eq!(order, Order { status: "paid", items: 3 });
+///
+/// let mut replayed = Order { status: "open", items: 0 };
+/// Order::into_events(order.snapshot()).for_each(|event| replayed.apply_event(event));
+/// assert_eq!(replayed, order);
+/// ```
+pub trait PatchEvents: Patch {
+    /// The event type, generated as `<Struct>Event` by the derive.
+    type Event;
+
+    /// The iterator returned by [`into_events`](PatchEvents::into_events).
+    type Events: Iterator<Item = Self::Event>;
+
+    /// Applies the change carried by `event` to its field.
+    fn apply_event(&mut self, event: Self::Event);
+
+    /// Splits `patch` into one event per field, in declaration order.
+    fn into_events(patch: Self::Patch) -> Self::Events;
+}
+
+/// A type that can capture its current state as a patch without being consumed.
+///
+/// Applying the snapshot to another instance with [`Patch::patch`] copies the state over, which
+/// makes snapshots suitable for memento-style checkpoints.
+///
+/// Fields marked with `#[patchable(merge = ...)]` or `#[patchable(delta)]` are applied as usual
+/// though: their snapshot is merged into the target field, or is the difference from the default
+/// value and is added to it. On a target where such fields hold their default value, such as
+/// `Default::default()`, a snapshot reproduces:
+///
+/// - `delta` fields, and fields merged with `"add"`, `"or"`, `"extend"` or `"append"` when their
+///   default is zero or empty, as for numbers and collections,
+/// - fields merged with `"max"` only when their value is at least the default, with `"min"` only
+///   when it is at most the default, and with `"and"` only when it is the default of an integer or
+///   `bool`,
+/// - fields merged with a custom [`MergeStrategy`] as far as merging into the default returns the
+///   incoming value.
+///
+/// On other targets, merged and delta fields are generally not reproduced.
+///
+/// ## Usage
+///
+/// ```rust
+/// use patchable::{Patch, Patchable, Snapshot};
+///
+/// #[derive(Debug, PartialEq, Patchable, Patch, Snapshot)]
+/// struct Editor {
+///     text: String,
+///     cursor: usize,
+///     #[patchable(skip)]
+///     dirty: bool,
+/// }
+///
+/// let mut editor = Editor { text: "draft".to_owned(), cursor: 5, dirty: false };
+/// let checkpoint = editor.snapshot();
+///
+/// editor.text.push_str(" and more");
+/// editor.dirty = true;
+///
+/// editor.patch(checkpoint);
+/// assert_eq!(editor, Editor { text: "draft".to_owned(), cursor: 5, dirty: true });
+/// ```
+pub trait Snapshot: Patchable {
+    /// Returns a patch holding the current state of `self`.
+    fn snapshot(&self) -> Self::Patch;
+}
+
+/// A type that can be rebuilt from its patch and the fields the patch skips.
+///
+/// `#[derive(FromPatch)]` generates a `<Struct>Skipped` struct holding the fields marked with
+/// `#[patchable(skip)]`, and uses it as [`Skipped`](FromPatch::Skipped). Skipped fields marked with
+/// `#[patchable(skip, default = expr)]` are initialized with `expr` instead; when all of them are,
+/// `From<Patch>` is implemented for the struct as well.
+///
+/// ## Usage
+///
+/// ```rust
+/// use patchable::{FromPatch, Patch, Patchable, Snapshot};
+///
+/// #[derive(Debug, PartialEq, Patchable, Patch, Snapshot, FromPatch)]
+/// struct Session {
+///     user: String,
+///     #[patchable(skip)]
+///     token: u64,
+///     #[patchable(skip, default = Vec::new())]
+///     history: Vec<String>,
+/// }
+///
+/// let session = Session { user: "ada".to_owned(), token: 7, history: vec!["a".to_owned()] };
+/// let restored = Session::from_patch(session.snapshot(), SessionSkipped { token: 9 });
+/// assert_eq!(restored, Session { user: "ada".to_owned(), token: 9, history: vec![] });
+/// ```
+pub trait FromPatch: Patchable {
+    /// The fields that the patch does not carry.
+    type Skipped;
+
+    /// Builds a value from `patch` and the `skipped` fields.
+    fn from_patch(patch: Self::Patch, skipped: Self::Skipped) -> Self;
+}
+
+/// A patch that can be squashed with a later patch of the same type.
+///
+/// `first.compose(later)` returns a single patch whose application is equivalent to applying
+/// `first` and then `later` with [`Patch::patch`].
+///
+/// `#[derive(Patchable)]` implements this trait for the generated patch struct:
+///
+/// - plain fields keep the value of `later` (last write wins),
+/// - `#[patchable]` fields are composed recursively,
+/// - `#[patchable(merge = ...)]` fields are merged with the same strategy, which therefore has to
+///   be associative,
+/// - `#[patchable(delta)]` fields add up their differences with [`Delta::compose_delta`].
+///
+/// For `#[patchable(delta = "checked")]` fields, the composed patch is only checked for overflow
+/// once, on the sum of the differences: on a `1_i8`, `+127` followed by `-1` overflows when
+/// applied one after the other, but composes into `+126`, which applies without error.
+///
+/// No implementation is generated when a field uses `#[patchable(with = "...")]`, since the
+/// composition of a custom patch is unknown.
+pub trait ComposePatch {
+    /// Combines `self` with a `later` patch into a single patch.
+    #[must_use]
+    fn compose(self, later: Self) -> Self;
+}
+
 /// A fallible variant of [`Patch`].
 ///
 /// This trait lets you apply a patch with validation and return a custom error
@@ -194,3 +455,82 @@ impl<T: Patch> TryPatch for T {
         Ok(())
     }
 }
+
+/// Validation layered on top of an infallible [`Patch`] implementation.
+///
+/// The blanket [`TryPatch`] implementation for [`Patch`] types rules out a hand-written
+/// `TryPatch` for a type that derives `Patch`. Implementing this trait instead adds a fallible
+/// [`checked_patch`](ValidatePatch::checked_patch) entry point, which validates the patch against
+/// the current value before applying it, while the derived `patch` stays available.
+///
+/// Validation only runs when `checked_patch` is called: code that is generic over [`Patch`] or
+/// [`TryPatch`] applies patches without it, so validate a patch before handing it to such code.
+///
+/// ## Usage
+///
+/// ```rust
+/// use core::fmt;
+/// use patchable::{Patch, Patchable, ValidatePatch};
+///
+/// #[derive(Debug, Patchable, Patch)]
+/// struct Config {
+///     concurrency: u32,
+/// }
+///
+/// #[derive(Debug)]
+/// struct ZeroConcurrency;
+///
+/// impl fmt::Display for ZeroConcurrency {
+///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         f.write_str("concurrency must be > 0")
+///     }
+/// }
+///
+/// impl core::error::Error for ZeroConcurrency {}
+///
+/// impl ValidatePatch for Config {
+///     type Error = ZeroConcurrency;
+///
+///     fn validate_patch(&self, patch: &Self::Patch) -> Result<(), Self::Error> {
+///         if patch.concurrency == 0 {
+///             return Err(ZeroConcurrency);
+///         }
+///         Ok(())
+///     }
+/// }
+///
+/// # #[cfg(feature = "serde")]
+/// # {
+/// let mut config = Config { concurrency: 1 };
+/// config.checked_patch(serde_json::from_str(r#"{"concurrency": 4}"#).unwrap()).unwrap();
+/// assert_eq!(config.concurrency, 4);
+///
+/// let invalid = serde_json::from_str(r#"{"concurrency": 0}"#).unwrap();
+/// assert!(config.checked_patch(invalid).is_err());
+/// assert_eq!(config.concurrency, 4);
+/// # }
+/// ```
+pub trait ValidatePatch: Patch {
+    /// The error type returned when a patch is rejected.
+    type Error: core::error::Error + Send + Sync + 'static;
+
+    /// Checks whether `patch` may be applied to `self`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the patch must not be applied.
+    fn validate_patch(&self, patch: &Self::Patch) -> Result<(), Self::Error>;
+
+    /// Validates `patch` with [`validate_patch`](ValidatePatch::validate_patch) and applies it
+    /// only if it is valid.
+    ///
+    /// # Errors
+    ///
+    /// Returns the validation error, leaving `self` unchanged.
+    #[inline(always)]
+    fn checked_patch(&mut self, patch: Self::Patch) -> Result<(), Self::Error> {
+        self.validate_patch(&patch)?;
+        self.patch(patch);
+        Ok(())
+    }
+}
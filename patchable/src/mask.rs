@@ -0,0 +1,73 @@
+//! # Field Masks
+//!
+//! `#[derive(MaskedPatch)]` generates, for a struct, a `<Struct>Field` enum with one variant per
+//! non-skipped field and a `<Struct>FieldMask` bitset of those fields. Their
+//! [`patch_masked`](MaskedPatch::patch_masked) method applies only the selected fields of a
+//! patch, which is enough for field-level access control or selective synchronization.
+//!
+//! Variants are named after the fields in `UpperCamelCase` (`Field0`, `Field1`, ... for tuple
+//! structs), and a mask holds up to 64 fields.
+
+use core::fmt::Debug;
+use core::hash::Hash;
+use core::ops::{BitAnd, BitOr, Not, Sub};
+
+use crate::Patch;
+
+/// A type that can apply a subset of the fields of its patch.
+///
+/// ## Usage
+///
+/// ```rust
+/// use patchable::{MaskedPatch, Patch, Patchable, Snapshot};
+///
+/// #[derive(Debug, PartialEq, Patchable, Patch, Snapshot, MaskedPatch)]
+/// struct Profile {
+///     display_name: String,
+///     email: String,
+///     role: u8,
+/// }
+///
+/// let mut profile = Profile { display_name: "ada".into(), email: "a@b.c".into(), role: 0 };
+/// let incoming = Profile { display_name: "Ada".into(), email: "ada@b.c".into(), role: 9 };
+///
+/// // Users may edit their own profile, but not their role.
+/// let editable = ProfileFieldMask::ALL - ProfileField::Role.into();
+/// profile.patch_masked(incoming.snapshot(), editable);
+/// assert_eq!(profile, Profile { display_name: "Ada".into(), email: "ada@b.c".into(), role: 0 });
+/// ```
+pub trait MaskedPatch: Patch {
+    /// The set of fields to apply, generated as `<Struct>FieldMask` by the derive.
+    type Mask: FieldMask;
+
+    /// Applies the fields of `patch` selected by `mask`, leaving the other fields unchanged.
+    fn patch_masked(&mut self, patch: Self::Patch, mask: Self::Mask);
+}
+
+/// A set of fields of a struct, stored as a bitset.
+///
+/// The derived masks also provide these operations as inherent `const` items and methods.
+pub trait FieldMask:
+    Copy
+    + Eq
+    + Hash
+    + Default
+    + Debug
+    + From<Self::Field>
+    + BitOr<Output = Self>
+    + BitAnd<Output = Self>
+    + Sub<Output = Self>
+    + Not<Output = Self>
+{
+    /// The field enum, generated as `<Struct>Field` by the derive.
+    type Field: Copy + Eq + Hash + Debug;
+
+    /// The mask selecting every field.
+    const ALL: Self;
+
+    /// The mask selecting no field.
+    const NONE: Self;
+
+    /// Returns whether `field` is selected.
+    fn contains(&self, field: Self::Field) -> bool;
+}
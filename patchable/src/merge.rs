@@ -0,0 +1,174 @@
+//! # Merge Strategies
+//!
+//! Strategies that combine the incoming value of a field with its current value, instead of
+//! overwriting it.
+//!
+//! Fields opt in with `#[patchable(merge = "...")]`, naming one of the built-in strategies of this
+//! module, or with `#[patchable(merge = path::to::Strategy)]` for a custom [`MergeStrategy`].
+//!
+//! | Name       | Strategy   | Requirement on the field type                                 |
+//! |------------|------------|---------------------------------------------------------------|
+//! | `"add"`    | [`Add`]    | [`AddAssign`]                                                 |
+//! | `"max"`    | [`Max`]    | [`PartialOrd`]                                                |
+//! | `"min"`    | [`Min`]    | [`PartialOrd`]                                                |
+//! | `"or"`     | [`Or`]     | [`BitOrAssign`]                                               |
+//! | `"and"`    | [`And`]    | [`BitAndAssign`]                                              |
+//! | `"extend"` | [`Extend`] | [`IntoIterator`] + [`core::iter::Extend`]                     |
+//! | `"append"` | [`Append`] | `Vec`, `VecDeque`, `LinkedList` or `String` (`alloc` feature) |
+
+use core::ops::{AddAssign, BitAndAssign, BitOrAssign};
+
+/// A way to combine an incoming value into the current value of a field.
+///
+/// [`ComposePatch`](crate::ComposePatch) merges the values of two patches with the same strategy,
+/// so a strategy should be associative for composed patches to match sequential ones. All
+/// built-in strategies are.
+///
+/// ## Usage
+///
+/// ```rust
+/// use patchable::{MergeStrategy, Patch, Patchable};
+///
+/// /// Keeps the longest path seen so far.
+/// struct Longest;
+///
+/// impl MergeStrategy<String> for Longest {
+///     fn merge(current: &mut String, incoming: String) {
+///         if incoming.len() > current.len() {
+///             *current = incoming;
+///         }
+///     }
+/// }
+///
+/// #[derive(Patchable, Patch)]
+/// struct Stats {
+///     #[patchable(merge = "add")]
+///     requests: u64,
+///     #[patchable(merge = Longest)]
+///     longest_path: String,
+/// }
+///
+/// let mut longest_path = "/a/b".to_owned();
+/// Longest::merge(&mut longest_path, "/c".to_owned());
+/// assert_eq!(longest_path, "/a/b");
+/// ```
+pub trait MergeStrategy<T> {
+    /// Combines `incoming` into `current`.
+    fn merge(current: &mut T, incoming: T);
+}
+
+/// Adds the incoming value to the current one.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Add;
+
+impl<T: AddAssign> MergeStrategy<T> for Add {
+    #[inline(always)]
+    fn merge(current: &mut T, incoming: T) {
+        *current += incoming;
+    }
+}
+
+/// Keeps the greater of the current and the incoming value.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Max;
+
+impl<T: PartialOrd> MergeStrategy<T> for Max {
+    #[inline(always)]
+    fn merge(current: &mut T, incoming: T) {
+        if incoming > *current {
+            *current = incoming;
+        }
+    }
+}
+
+/// Keeps the lesser of the current and the incoming value.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Min;
+
+impl<T: PartialOrd> MergeStrategy<T> for Min {
+    #[inline(always)]
+    fn merge(current: &mut T, incoming: T) {
+        if incoming < *current {
+            *current = incoming;
+        }
+    }
+}
+
+/// Combines the current and the incoming value with a bitwise (or logical) OR.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Or;
+
+impl<T: BitOrAssign> MergeStrategy<T> for Or {
+    #[inline(always)]
+    fn merge(current: &mut T, incoming: T) {
+        *current |= incoming;
+    }
+}
+
+/// Combines the current and the incoming value with a bitwise (or logical) AND.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct And;
+
+impl<T: BitAndAssign> MergeStrategy<T> for And {
+    #[inline(always)]
+    fn merge(current: &mut T, incoming: T) {
+        *current &= incoming;
+    }
+}
+
+/// Extends the current collection with the items of the incoming one.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Extend;
+
+impl<T> MergeStrategy<T> for Extend
+where
+    T: IntoIterator + core::iter::Extend<<T as IntoIterator>::Item>,
+{
+    #[inline(always)]
+    fn merge(current: &mut T, incoming: T) {
+        current.extend(incoming);
+    }
+}
+
+/// Appends the incoming value to the end of the current one.
+///
+/// Unlike [`Extend`], this moves the elements in bulk and also supports `String`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Append;
+
+#[cfg(feature = "alloc")]
+mod append_impls {
+    use alloc::collections::{LinkedList, VecDeque};
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    use super::{Append, MergeStrategy};
+
+    impl<T> MergeStrategy<Vec<T>> for Append {
+        #[inline(always)]
+        fn merge(current: &mut Vec<T>, mut incoming: Vec<T>) {
+            current.append(&mut incoming);
+        }
+    }
+
+    impl<T> MergeStrategy<VecDeque<T>> for Append {
+        #[inline(always)]
+        fn merge(current: &mut VecDeque<T>, mut incoming: VecDeque<T>) {
+            current.append(&mut incoming);
+        }
+    }
+
+    impl<T> MergeStrategy<LinkedList<T>> for Append {
+        #[inline(always)]
+        fn merge(current: &mut LinkedList<T>, mut incoming: LinkedList<T>) {
+            current.append(&mut incoming);
+        }
+    }
+
+    impl MergeStrategy<String> for Append {
+        #[inline(always)]
+        fn merge(current: &mut String, incoming: String) {
+            current.push_str(&incoming);
+        }
+    }
+}
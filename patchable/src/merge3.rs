@@ -0,0 +1,261 @@
+//! # Three-Way Merge
+//!
+//! [`Merge3::merge3`] merges two patches written concurrently against the same base value, such as
+//! by two replicas, into a single patch applying both sets of changes.
+//!
+//! `#[derive(Merge3)]` merges the patches field by field:
+//!
+//! - a plain field takes the value of the side that changed it from the base, or the common value
+//!   when both sides set the same one; when both sides set different new values, it conflicts,
+//! - a `#[patchable]` field merges the nested patches recursively,
+//! - `merge` and `delta` fields combine both changes, as [`ComposePatch`](crate::ComposePatch)
+//!   does.
+//!
+//! In a partial patch (see [`partial`](crate::partial)), an unset field counts as unchanged.
+//! Conflicts are reported by the dotted path of their field, and [`Merge3::merge3_with`] lets a
+//! callback decide them.
+//!
+//! This module is available with the `alloc` feature.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::Patchable;
+use crate::partial::FieldPath;
+
+/// A type whose concurrent patches can be merged against a common base.
+///
+/// ## Usage
+///
+/// ```rust
+/// use patchable::{Merge3, Patch, Patchable, Resolution};
+///
+/// #[derive(Clone, Merge3, Patchable, Patch)]
+/// struct Limits {
+///     max_connections: u32,
+///     timeout: u64,
+/// }
+///
+/// #[derive(Clone, Merge3, Patchable, Patch)]
+/// struct Config<L> {
+///     name: String,
+///     #[patchable]
+///     limits: L,
+///     #[patchable(delta)]
+///     restarts: u32,
+/// }
+///
+/// type ConfigPatch<L> = <Config<L> as Patchable>::Patch;
+/// type LimitsPatch = <Limits as Patchable>::Patch;
+///
+/// let base = Config {
+///     name: "api".to_owned(),
+///     limits: Limits { max_connections: 16, timeout: 30 },
+///     restarts: 0,
+/// };
+/// let ours = ConfigPatch::<Limits> {
+///     name: "gateway".to_owned(),
+///     limits: LimitsPatch { max_connections: 128, timeout: 30 },
+///     restarts: 1,
+/// };
+/// let theirs = ConfigPatch::<Limits> {
+///     name: "api".to_owned(),
+///     limits: LimitsPatch { max_connections: 16, timeout: 60 },
+///     restarts: 2,
+/// };
+///
+/// let mut merged = base.clone();
+/// merged.patch(base.merge3(&ours, &theirs)?);
+/// assert_eq!(merged.name, "gateway");
+/// assert_eq!((merged.limits.max_connections, merged.limits.timeout), (128, 60));
+/// assert_eq!(merged.restarts, 3);
+///
+/// // Both sides rename the config: the conflict is reported, unless a callback decides it.
+/// let theirs = ConfigPatch::<Limits> { name: "web".to_owned(), ..theirs };
+/// let Err(conflicts) = base.merge3(&ours, &theirs) else { unreachable!() };
+/// assert_eq!(conflicts.paths(), ["name"]);
+///
+/// let mut merged = base.clone();
+/// merged.patch(base.merge3_with(&ours, &theirs, |_| Some(Resolution::Theirs))?);
+/// assert_eq!(merged.name, "web");
+/// # Ok::<(), patchable::merge3::Conflicts>(())
+/// ```
+pub trait Merge3: Patchable {
+    /// Merges `ours` and `theirs` below `prefix`, recording the conflicts in `merger`.
+    #[doc(hidden)]
+    fn merge3_below(
+        &self,
+        ours: &Self::Patch,
+        theirs: &Self::Patch,
+        merger: &mut __private::Merger<'_>,
+        prefix: Option<&FieldPath<'_>>,
+    ) -> Self::Patch;
+
+    /// Merges two patches written against `self`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the paths of the fields both patches changed to different values.
+    fn merge3(&self, ours: &Self::Patch, theirs: &Self::Patch) -> Result<Self::Patch, Conflicts> {
+        self.merge3_with(ours, theirs, |_| None)
+    }
+
+    /// Merges two patches written against `self`, calling `resolve` with the path of each
+    /// conflicting field to decide which value it takes.
+    ///
+    /// # Errors
+    ///
+    /// Returns the paths of the conflicting fields for which `resolve` returned `None`.
+    fn merge3_with(
+        &self,
+        ours: &Self::Patch,
+        theirs: &Self::Patch,
+        mut resolve: impl FnMut(&FieldPath<'_>) -> Option<Resolution>,
+    ) -> Result<Self::Patch, Conflicts> {
+        let mut merger = __private::Merger {
+            resolve: &mut resolve,
+            conflicts: Vec::new(),
+        };
+        let merged = self.merge3_below(ours, theirs, &mut merger, None);
+        if merger.conflicts.is_empty() {
+            Ok(merged)
+        } else {
+            Err(Conflicts {
+                paths: merger.conflicts,
+            })
+        }
+    }
+}
+
+/// The value a conflicting field takes, as decided by the callback of [`Merge3::merge3_with`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Resolution {
+    /// The value of `ours`.
+    Ours,
+    /// The value of `theirs`.
+    Theirs,
+    /// The value of the base, dropping both changes.
+    Base,
+}
+
+/// The fields changed to different values by both merged patches.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Conflicts {
+    paths: Vec<String>,
+}
+
+impl Conflicts {
+    /// Returns the dotted paths of the conflicting fields, in field order.
+    pub fn paths(&self) -> &[String] {
+        &self.paths
+    }
+}
+
+impl fmt::Display for Conflicts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("conflicting changes to ")?;
+        for (index, path) in self.paths.iter().enumerate() {
+            if index > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "`{path}`")?;
+        }
+        Ok(())
+    }
+}
+
+impl core::error::Error for Conflicts {}
+
+#[doc(hidden)]
+pub mod __private {
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    use super::{Merge3, Resolution, ToString};
+    use crate::partial::FieldPath;
+
+    /// The state of a merge: the callback deciding conflicts, and the undecided ones.
+    pub struct Merger<'r> {
+        pub(super) resolve: &'r mut dyn FnMut(&FieldPath<'_>) -> Option<Resolution>,
+        pub(super) conflicts: Vec<String>,
+    }
+
+    impl Merger<'_> {
+        /// Decides the value of a conflicting field, or records the conflict and keeps `ours`.
+        fn resolve<T>(&mut self, path: &FieldPath<'_>, ours: T, theirs: T, base: T) -> T {
+            match (self.resolve)(path) {
+                Some(Resolution::Ours) => ours,
+                Some(Resolution::Theirs) => theirs,
+                Some(Resolution::Base) => base,
+                None => {
+                    self.conflicts.push(path.to_string());
+                    ours
+                }
+            }
+        }
+    }
+
+    /// Merges the values of a plain field.
+    pub fn keep<T>(
+        merger: &mut Merger<'_>,
+        path: &FieldPath<'_>,
+        base: &T,
+        ours: &T,
+        theirs: &T,
+    ) -> T
+    where
+        T: PartialEq + Clone,
+    {
+        if ours == theirs || theirs == base {
+            ours.clone()
+        } else if ours == base {
+            theirs.clone()
+        } else {
+            merger.resolve(path, ours, theirs, base).clone()
+        }
+    }
+
+    /// Merges the values of a plain field of a partial patch, where an unset value is unchanged.
+    pub fn keep_partial<T>(
+        merger: &mut Merger<'_>,
+        path: &FieldPath<'_>,
+        base: &T,
+        ours: &Option<T>,
+        theirs: &Option<T>,
+    ) -> Option<T>
+    where
+        T: PartialEq + Clone,
+    {
+        let changed = |side: &Option<T>| side.as_ref().is_some_and(|value| value != base);
+        match (changed(ours), changed(theirs)) {
+            (true, true) if ours != theirs => {
+                let base = Some(base.clone());
+                merger.resolve(path, ours.clone(), theirs.clone(), base)
+            }
+            (false, true) => theirs.clone(),
+            _ => ours.clone().or_else(|| theirs.clone()),
+        }
+    }
+
+    /// Merges the patches of a nested field of a partial patch, where an unset patch is empty.
+    pub fn nested_partial<T>(
+        merger: &mut Merger<'_>,
+        path: &FieldPath<'_>,
+        base: &T,
+        ours: &Option<T::Patch>,
+        theirs: &Option<T::Patch>,
+    ) -> Option<T::Patch>
+    where
+        T: Merge3,
+        T::Patch: Default,
+    {
+        if ours.is_none() && theirs.is_none() {
+            return None;
+        }
+        let empty = T::Patch::default();
+        let ours = ours.as_ref().unwrap_or(&empty);
+        let theirs = theirs.as_ref().unwrap_or(&empty);
+        Some(base.merge3_below(ours, theirs, merger, Some(path)))
+    }
+}
@@ -0,0 +1,150 @@
+//! # Partial Patches
+//!
+//! With `#[patchable(partial)]`, every field of the generated patch struct is wrapped in an
+//! `Option`, and only the fields that are `Some` are applied. Such a patch describes the fields
+//! one source sets, so partial patches from several sources can be layered: they compose field
+//! by field, the later patch winning for the fields both set.
+//!
+//! The generated patch struct implements `Default`, with no field set, and [`PartialPatch`],
+//! which tells the paths of the fields that are set.
+
+use core::fmt;
+
+/// A patch whose fields are optional, generated for structs marked with
+/// `#[patchable(partial)]`.
+///
+/// ## Usage
+///
+/// ```rust
+/// use patchable::{Patch, Patchable, PartialPatch};
+///
+/// #[derive(Patchable, Patch)]
+/// #[patchable(partial)]
+/// struct Limits {
+///     max_connections: u32,
+///     timeout: u64,
+/// }
+///
+/// #[derive(Patchable, Patch)]
+/// #[patchable(partial)]
+/// struct Config<L> {
+///     name: String,
+///     #[patchable]
+///     limits: L,
+/// }
+///
+/// type LimitsPatch = <Limits as Patchable>::Patch;
+/// type ConfigPatch<L> = <Config<L> as Patchable>::Patch;
+///
+/// let patch: ConfigPatch<Limits> = ConfigPatch {
+///     limits: Some(LimitsPatch {
+///         max_connections: Some(128),
+///         ..Default::default()
+///     }),
+///     ..Default::default()
+/// };
+///
+/// let mut set = Vec::new();
+/// patch.for_each_set_field(|path| set.push(path.to_string()));
+/// assert_eq!(set, ["limits.max_connections"]);
+///
+/// let mut config = Config {
+///     name: "api".to_owned(),
+///     limits: Limits { max_connections: 16, timeout: 30 },
+/// };
+/// config.patch(patch);
+/// assert_eq!((config.limits.max_connections, config.limits.timeout), (128, 30));
+/// ```
+pub trait PartialPatch {
+    /// Calls `visit` with the path of each field set by this patch, below `parent`.
+    #[doc(hidden)]
+    fn visit_set_fields(
+        &self,
+        parent: Option<&FieldPath<'_>>,
+        visit: &mut dyn FnMut(&FieldPath<'_>),
+    );
+
+    /// Calls `visit` with the path of each field set by this patch.
+    ///
+    /// Set `#[patchable]` fields are visited through the fields they set in turn.
+    fn for_each_set_field(&self, mut visit: impl FnMut(&FieldPath<'_>)) {
+        self.visit_set_fields(None, &mut visit);
+    }
+
+    /// Returns whether this patch sets no field.
+    fn is_empty(&self) -> bool {
+        let mut empty = true;
+        self.visit_set_fields(None, &mut |_| empty = false);
+        empty
+    }
+}
+
+/// The path of a field in a patch, displayed with dots (`limits.max_connections`).
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct FieldPath<'a> {
+    parent: Option<&'a FieldPath<'a>>,
+    name: &'static str,
+}
+
+impl<'a> FieldPath<'a> {
+    /// Returns the path of the field `name` in the struct found at `parent`.
+    #[inline(always)]
+    pub const fn new(name: &'static str, parent: Option<&'a FieldPath<'a>>) -> Self {
+        Self { parent, name }
+    }
+
+    /// Returns the name of the field, or its index for tuple structs.
+    #[inline(always)]
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the path of the struct holding the field, if it is nested.
+    #[inline(always)]
+    pub const fn parent(&self) -> Option<&'a FieldPath<'a>> {
+        self.parent
+    }
+}
+
+impl fmt::Display for FieldPath<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(parent) = self.parent {
+            write!(f, "{parent}.")?;
+        }
+        f.write_str(self.name)
+    }
+}
+
+impl fmt::Debug for FieldPath<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FieldPath({self})")
+    }
+}
+
+/// Helpers used by the code generated for partial patches.
+#[doc(hidden)]
+pub mod __private {
+    /// Deserializes a present field of a partial patch, so that `null` is kept as a value.
+    #[cfg(feature = "serde")]
+    #[inline(always)]
+    pub fn deserialize_some<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        T: serde::Deserialize<'de>,
+    {
+        T::deserialize(deserializer).map(Some)
+    }
+
+    /// Composes two optional field patches, with `compose` when both are set.
+    #[inline(always)]
+    pub fn compose<P>(
+        first: Option<P>,
+        later: Option<P>,
+        compose: impl FnOnce(P, P) -> P,
+    ) -> Option<P> {
+        match (first, later) {
+            (Some(first), Some(later)) => Some(compose(first, later)),
+            (first, later) => later.or(first),
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! # Field Paths
+//!
+//! `#[derive(PatchPath)]` gives runtime access to the fields of a struct by a dotted path such as
+//! `"limits.max_connections"`, for admin tooling or configuration overrides. Each segment names a
+//! non-skipped field (or its index, for tuple structs) and continues into `#[patchable]` fields;
+//! skipped fields are not addressable.
+//!
+//! [`get_path`](PatchPath::get_path) returns a [`PathValue`] that serializes the addressed field,
+//! and [`set_path`](PatchPath::set_path) deserializes a new value for it from any
+//! [`Deserializer`]. A path naming a struct (`#[patchable]` field, or `""` for the value itself)
+//! serializes it as a map of its fields, and sets it by deserializing its patch and applying it
+//! with [`Patch::patch`].
+//!
+//! This module is available with the `serde` feature.
+
+use core::convert::Infallible;
+use core::fmt;
+
+use serde::{Deserializer, Serialize, Serializer};
+
+use crate::Patch;
+
+/// A type whose fields can be read and written by a dotted path.
+///
+/// ## Usage
+///
+/// ```rust
+/// use patchable::{Patch, PatchPath, Patchable};
+///
+/// #[derive(Patchable, Patch, PatchPath)]
+/// struct Limits {
+///     max_connections: u32,
+/// }
+///
+/// #[derive(Patchable, Patch, PatchPath)]
+/// struct Config<L> {
+///     name: String,
+///     #[patchable]
+///     limits: L,
+///     #[patchable(skip)]
+///     secret: String,
+/// }
+///
+/// let mut config = Config {
+///     name: "api".to_owned(),
+///     limits: Limits { max_connections: 16 },
+///     secret: "hunter2".to_owned(),
+/// };
+///
+/// let value = &mut serde_json::Deserializer::from_str("128");
+/// config.set_path("limits.max_connections", value).unwrap();
+/// assert_eq!(config.limits.max_connections, 128);
+///
+/// let limits = config.get_path("limits").unwrap();
+/// assert_eq!(serde_json::to_string(&limits).unwrap(), r#"{"max_connections":128}"#);
+/// assert!(config.get_path("secret").is_err());
+/// ```
+pub trait PatchPath: Patch {
+    /// Returns whether `path` addresses a field, `None` addressing the value itself.
+    #[doc(hidden)]
+    fn has_path(path: Option<&str>) -> bool;
+
+    /// Serializes the field at `path`, which [`has_path`](PatchPath::has_path) accepts.
+    #[doc(hidden)]
+    fn serialize_path<S: Serializer>(
+        &self,
+        path: Option<&str>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>;
+
+    /// Deserializes the field at `path` from `deserializer`.
+    #[doc(hidden)]
+    fn deserialize_path<'de, D: Deserializer<'de>>(
+        &mut self,
+        path: Option<&str>,
+        deserializer: D,
+    ) -> Result<(), PathError<D::Error>>;
+
+    /// Returns the field at the dotted `path`, as a value to serialize.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PathError::NotFound`] if `path` addresses no field.
+    fn get_path<'a>(&'a self, path: &'a str) -> Result<PathValue<'a, Self>, PathError> {
+        let path = (!path.is_empty()).then_some(path);
+        if Self::has_path(path) {
+            Ok(PathValue { target: self, path })
+        } else {
+            Err(PathError::NotFound)
+        }
+    }
+
+    /// Sets the field at the dotted `path` to the value deserialized from `value`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `path` addresses no field or the value cannot be deserialized, in
+    /// which case `self` is unchanged.
+    fn set_path<'de, D: Deserializer<'de>>(
+        &mut self,
+        path: &str,
+        value: D,
+    ) -> Result<(), PathError<D::Error>> {
+        self.deserialize_path((!path.is_empty()).then_some(path), value)
+    }
+}
+
+/// The field of a `T` at a path, returned by [`PatchPath::get_path`].
+///
+/// Serializing it serializes the field.
+pub struct PathValue<'a, T: ?Sized> {
+    target: &'a T,
+    path: Option<&'a str>,
+}
+
+impl<'a, T: ?Sized> PathValue<'a, T> {
+    /// Returns the value `target` itself.
+    #[doc(hidden)]
+    #[inline(always)]
+    pub const fn whole(target: &'a T) -> Self {
+        Self { target, path: None }
+    }
+}
+
+impl<T: PatchPath + ?Sized> Serialize for PathValue<'_, T> {
+    #[inline(always)]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.target.serialize_path(self.path, serializer)
+    }
+}
+
+impl<T: ?Sized> Clone for PathValue<'_, T> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for PathValue<'_, T> {}
+
+impl<T: ?Sized> fmt::Debug for PathValue<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PathValue")
+            .field("path", &self.path.unwrap_or_default())
+            .finish_non_exhaustive()
+    }
+}
+
+/// An error raised while accessing a field by path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PathError<E = Infallible> {
+    /// The path names no field, names a skipped field, or continues past a plain field.
+    NotFound,
+    /// The new value could not be deserialized.
+    Value(E),
+}
+
+impl<E: fmt::Display> fmt::Display for PathError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound => f.write_str("no field at this path"),
+            Self::Value(error) => write!(f, "invalid value: {error}"),
+        }
+    }
+}
+
+impl<E: core::error::Error + 'static> core::error::Error for PathError<E> {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::NotFound => None,
+            Self::Value(error) => Some(error),
+        }
+    }
+}
+
+/// Helpers used by the derived `PatchPath` implementations.
+#[doc(hidden)]
+pub mod __private {
+    pub use serde::de::{Deserialize, DeserializeOwned, Deserializer};
+    pub use serde::ser::{Error, Serialize, SerializeMap, Serializer};
+
+    use super::PathError;
+    use crate::Patch;
+
+    /// Deserializes a patch for the whole `target` and applies it.
+    #[inline(always)]
+    pub fn deserialize_patch<'de, T, D>(
+        target: &mut T,
+        deserializer: D,
+    ) -> Result<(), PathError<D::Error>>
+    where
+        T: Patch + ?Sized,
+        T::Patch: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        let patch = T::Patch::deserialize(deserializer).map_err(PathError::Value)?;
+        target.patch(patch);
+        Ok(())
+    }
+
+    /// Splits the first segment off a dotted path.
+    #[inline(always)]
+    pub fn split(path: &str) -> (&str, Option<&str>) {
+        match path.split_once('.') {
+            Some((key, rest)) => (key, Some(rest)),
+            None => (path, None),
+        }
+    }
+}
@@ -0,0 +1,433 @@
+//! # Hot Reloading
+//!
+//! [`ConfigReloader`] watches a configuration file by polling its modification time and the hash
+//! of its contents. When the contents change, it deserializes the patch of the configuration type
+//! from them and applies it to a value shared behind an [`RwLock`]: with [`TryPatch::try_patch`]
+//! for reloaders built by [`ConfigReloader::new`], or with [`ValidatePatch::checked_patch`] for
+//! those built by [`ConfigReloader::checked`].
+//!
+//! The patch is applied to a copy of the current value, which replaces it only on success: when
+//! the file cannot be read or deserialized, or the patch is rejected, the old value stays in place
+//! and the error is kept in [`ConfigReloader::last_error`]. Subscribers are notified of each
+//! successful reload with a [`Reload`], which holds the previous and the current value and, for
+//! reloaders turned into reporting ones by [`ConfigReloader::reporting`], the [`PatchReport`] of
+//! the applied patch.
+//!
+//! [`ConfigReloader::poll`] checks the file once, and [`ConfigReloader::watch`] polls it from a
+//! background thread.
+//!
+//! This module is available with the `std` feature.
+
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use std::fs;
+use std::hash::DefaultHasher;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{PoisonError, RwLock};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime};
+
+use crate::{PatchReport, Patchable, ReportPatch, TryPatch, ValidatePatch};
+
+/// The error returned by the function deserializing patches.
+pub type DeserializeError = Box<dyn core::error::Error + Send + Sync>;
+
+type DeserializeFn<T> =
+    Box<dyn FnMut(&[u8]) -> Result<<T as Patchable>::Patch, DeserializeError> + Send>;
+type ValidateFn<T, E> = fn(&T, &<T as Patchable>::Patch) -> Result<(), E>;
+type ApplyFn<T, E, R> = fn(&mut T, <T as Patchable>::Patch) -> Result<R, E>;
+type Subscriber<T, R> = Box<dyn FnMut(&Reload<'_, T, R>) + Send>;
+
+/// Reloads a value shared behind an [`RwLock`] when its configuration file changes.
+///
+/// `E` is the error of rejected patches, and `R` what the reloader reports to its subscribers
+/// along with the values: nothing, or the [`PatchReport`] of the applied patch.
+///
+/// ## Usage
+///
+/// ```rust
+/// use std::sync::{Arc, RwLock};
+///
+/// use patchable::reload::ConfigReloader;
+/// use patchable::{Patch, Patchable, ReportPatch};
+///
+/// #[derive(Clone, Debug, Patchable, Patch, ReportPatch)]
+/// struct Config {
+///     name: String,
+///     max_connections: u32,
+/// }
+///
+/// # #[cfg(feature = "serde")]
+/// # {
+/// let dir = tempfile::tempdir()?;
+/// let path = dir.path().join("config.json");
+/// std::fs::write(&path, r#"{ "name": "api", "max_connections": 128 }"#)?;
+///
+/// let config = Arc::new(RwLock::new(Config { name: "api".to_owned(), max_connections: 16 }));
+/// let mut reloader = ConfigReloader::new(&path, Arc::clone(&config), |contents| {
+///     serde_json::from_slice(contents)
+/// })
+/// .reporting();
+/// reloader.subscribe(|reload| {
+///     let report = reload.report();
+///     assert!(!report.name && report.max_connections);
+/// });
+///
+/// assert!(reloader.poll().is_ok_and(|reloaded| reloaded));
+/// assert_eq!(config.read().unwrap().max_connections, 128);
+/// assert!(reloader.poll().is_ok_and(|reloaded| !reloaded));
+/// # }
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct ConfigReloader<T: TryPatch, E = <T as TryPatch>::Error, R = ()> {
+    path: PathBuf,
+    value: Arc<RwLock<T>>,
+    deserialize: DeserializeFn<T>,
+    validate: ValidateFn<T, E>,
+    apply: ApplyFn<T, E, R>,
+    subscribers: Vec<Subscriber<T, R>>,
+    stamp: Option<FileStamp>,
+    last_error: Option<ReloadError<E>>,
+}
+
+impl<T: TryPatch + Clone> ConfigReloader<T> {
+    /// Returns a reloader applying the contents of the file at `path` to `value`, once
+    /// deserialized into a patch by `deserialize`, with [`TryPatch::try_patch`].
+    ///
+    /// The file is first read by the next call to [`poll`](Self::poll).
+    pub fn new<D>(
+        path: impl AsRef<Path>,
+        value: Arc<RwLock<T>>,
+        deserialize: impl FnMut(&[u8]) -> Result<T::Patch, D> + Send + 'static,
+    ) -> Self
+    where
+        D: Into<DeserializeError>,
+    {
+        Self::with_apply(path, value, deserialize, |_, _| Ok(()), T::try_patch)
+    }
+}
+
+impl<T: ValidatePatch + Clone> ConfigReloader<T, <T as ValidatePatch>::Error> {
+    /// Returns a reloader like [`new`](ConfigReloader::new), which applies the patches with
+    /// [`ValidatePatch::checked_patch`], so that a patch rejected by
+    /// [`validate_patch`](ValidatePatch::validate_patch) is reported as
+    /// [`ReloadError::Patch`].
+    pub fn checked<D>(
+        path: impl AsRef<Path>,
+        value: Arc<RwLock<T>>,
+        deserialize: impl FnMut(&[u8]) -> Result<T::Patch, D> + Send + 'static,
+    ) -> Self
+    where
+        D: Into<DeserializeError>,
+    {
+        Self::with_apply(
+            path,
+            value,
+            deserialize,
+            T::validate_patch,
+            |value, patch| {
+                value.patch(patch);
+                Ok(())
+            },
+        )
+    }
+}
+
+impl<T: ReportPatch + Clone + 'static, E> ConfigReloader<T, E> {
+    /// Turns the reloader into one applying the patches with [`ReportPatch::patch_report`], after
+    /// the validation of [`checked`](ConfigReloader::checked) reloaders, so that subscribers get
+    /// the report of the applied patch from [`Reload::report`].
+    ///
+    /// The subscribers added so far are kept.
+    pub fn reporting(self) -> ConfigReloader<T, E, PatchReport<T>> {
+        let subscribers = self
+            .subscribers
+            .into_iter()
+            .map(|mut subscriber| -> Subscriber<T, PatchReport<T>> {
+                Box::new(move |reload| {
+                    subscriber(&Reload {
+                        previous: reload.previous,
+                        current: reload.current,
+                        report: &(),
+                    });
+                })
+            })
+            .collect();
+        ConfigReloader {
+            path: self.path,
+            value: self.value,
+            deserialize: self.deserialize,
+            validate: self.validate,
+            apply: |value, patch| Ok(value.patch_report(patch)),
+            subscribers,
+            stamp: self.stamp,
+            last_error: self.last_error,
+        }
+    }
+}
+
+impl<T: TryPatch + Clone, E, R> ConfigReloader<T, E, R> {
+    fn with_apply<D>(
+        path: impl AsRef<Path>,
+        value: Arc<RwLock<T>>,
+        mut deserialize: impl FnMut(&[u8]) -> Result<T::Patch, D> + Send + 'static,
+        validate: ValidateFn<T, E>,
+        apply: ApplyFn<T, E, R>,
+    ) -> Self
+    where
+        D: Into<DeserializeError>,
+    {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            value,
+            deserialize: Box::new(move |contents| deserialize(contents).map_err(Into::into)),
+            validate,
+            apply,
+            subscribers: Vec::new(),
+            stamp: None,
+            last_error: None,
+        }
+    }
+
+    /// Returns the path of the watched file.
+    #[inline(always)]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the shared value.
+    #[inline(always)]
+    pub fn value(&self) -> &Arc<RwLock<T>> {
+        &self.value
+    }
+
+    /// Returns the error of the last reload, until the file changes again and reloads
+    /// successfully.
+    #[inline(always)]
+    pub fn last_error(&self) -> Option<&ReloadError<E>> {
+        self.last_error.as_ref()
+    }
+
+    /// Calls `subscriber` after each successful reload.
+    ///
+    /// Subscribers are called while the shared value is still locked for writing, so that no
+    /// other writer changes it before they are notified: they get the value from the [`Reload`],
+    /// and must not lock it themselves.
+    pub fn subscribe(&mut self, subscriber: impl FnMut(&Reload<'_, T, R>) + Send + 'static) {
+        self.subscribers.push(Box::new(subscriber));
+    }
+
+    /// Checks the file once, and reloads the value if its contents changed since the last call.
+    ///
+    /// Returns whether the value was reloaded. The file is read only when its modification time
+    /// or length changed, and applied only when the hash of its contents changed as well.
+    ///
+    /// # Errors
+    ///
+    /// Returns the new [`last_error`](Self::last_error) if the file cannot be read or
+    /// deserialized, or if the patch is rejected, in which case the value is left unchanged. The
+    /// same contents are not applied again, so the following calls return `Ok(false)` until the
+    /// file changes.
+    pub fn poll(&mut self) -> Result<bool, &ReloadError<E>> {
+        match self.reload() {
+            Ok(reloaded) => {
+                if reloaded {
+                    self.last_error = None;
+                }
+                Ok(reloaded)
+            }
+            Err(error) => Err(self.last_error.insert(error)),
+        }
+    }
+
+    fn reload(&mut self) -> Result<bool, ReloadError<E>> {
+        let metadata = fs::metadata(&self.path).map_err(ReloadError::Io)?;
+        let modified = metadata.modified().ok();
+        let len = metadata.len();
+        if self
+            .stamp
+            .as_ref()
+            .is_some_and(|stamp| stamp.modified == modified && stamp.len == len)
+        {
+            return Ok(false);
+        }
+
+        let contents = fs::read(&self.path).map_err(ReloadError::Io)?;
+        let mut hasher = DefaultHasher::new();
+        contents.hash(&mut hasher);
+        let hash = hasher.finish();
+        let previous_hash = self.stamp.as_ref().map(|stamp| stamp.hash);
+        self.stamp = Some(FileStamp {
+            modified,
+            len,
+            hash,
+        });
+        if previous_hash == Some(hash) {
+            return Ok(false);
+        }
+
+        let patch = (self.deserialize)(&contents).map_err(ReloadError::Deserialize)?;
+        let mut value = self.value.write().unwrap_or_else(PoisonError::into_inner);
+        (self.validate)(&value, &patch).map_err(ReloadError::Patch)?;
+        let mut candidate = value.clone();
+        let report = (self.apply)(&mut candidate, patch).map_err(ReloadError::Patch)?;
+        let previous = core::mem::replace(&mut *value, candidate);
+
+        let reload = Reload {
+            previous: &previous,
+            current: &value,
+            report: &report,
+        };
+        for subscriber in &mut self.subscribers {
+            subscriber(&reload);
+        }
+        Ok(true)
+    }
+
+    /// Polls the file every `interval` from a background thread, until the returned
+    /// [`Watcher`] is stopped or dropped.
+    pub fn watch(self, interval: Duration) -> Watcher<T, E, R>
+    where
+        T: Send + Sync + 'static,
+        E: Send + 'static,
+        R: 'static,
+    {
+        let (stop, stopped) = mpsc::channel::<()>();
+        let thread = thread::spawn(move || {
+            let mut reloader = self;
+            loop {
+                let _ = reloader.poll();
+                match stopped.recv_timeout(interval) {
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Ok(()) | Err(RecvTimeoutError::Disconnected) => return reloader,
+                }
+            }
+        });
+        Watcher {
+            stop: Some(stop),
+            thread: Some(thread),
+        }
+    }
+}
+
+impl<T: TryPatch, E: fmt::Debug, R> fmt::Debug for ConfigReloader<T, E, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConfigReloader")
+            .field("path", &self.path)
+            .field("subscribers", &self.subscribers.len())
+            .field("last_error", &self.last_error)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The modification time, length and contents hash of the file at its last reload.
+struct FileStamp {
+    modified: Option<SystemTime>,
+    len: u64,
+    hash: u64,
+}
+
+/// A successful reload, passed to the subscribers of a [`ConfigReloader`].
+pub struct Reload<'a, T, R = ()> {
+    previous: &'a T,
+    current: &'a T,
+    report: &'a R,
+}
+
+impl<T, R> Reload<'_, T, R> {
+    /// Returns the value before the reload.
+    #[inline(always)]
+    pub fn previous(&self) -> &T {
+        self.previous
+    }
+
+    /// Returns the value after the reload.
+    #[inline(always)]
+    pub fn current(&self) -> &T {
+        self.current
+    }
+}
+
+impl<T: ReportPatch> Reload<'_, T, PatchReport<T>> {
+    /// Returns the report of the applied patch, with the fields it changed.
+    #[inline(always)]
+    pub fn report(&self) -> PatchReport<T> {
+        *self.report
+    }
+}
+
+/// A [`ConfigReloader`] polling its file from a background thread, returned by
+/// [`ConfigReloader::watch`].
+///
+/// Dropping the watcher stops the thread.
+pub struct Watcher<T: TryPatch, E = <T as TryPatch>::Error, R = ()> {
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<ConfigReloader<T, E, R>>>,
+}
+
+impl<T: TryPatch, E, R> Watcher<T, E, R> {
+    /// Stops the thread and returns the reloader, with the error of its last reload.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a subscriber panicked on the thread.
+    pub fn stop(mut self) -> ConfigReloader<T, E, R> {
+        self.join().expect("the thread of the reloader panicked")
+    }
+
+    fn join(&mut self) -> Option<ConfigReloader<T, E, R>> {
+        drop(self.stop.take());
+        self.thread.take()?.join().ok()
+    }
+}
+
+impl<T: TryPatch, E, R> Drop for Watcher<T, E, R> {
+    fn drop(&mut self) {
+        self.join();
+    }
+}
+
+impl<T: TryPatch, E, R> fmt::Debug for Watcher<T, E, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Watcher")
+            .field("running", &self.thread.is_some())
+            .finish()
+    }
+}
+
+/// An error raised while reloading a value.
+#[derive(Debug)]
+pub enum ReloadError<E> {
+    /// The file could not be read.
+    Io(io::Error),
+    /// The contents of the file could not be deserialized into a patch.
+    Deserialize(DeserializeError),
+    /// The patch was rejected by [`TryPatch::try_patch`] or
+    /// [`ValidatePatch::validate_patch`].
+    Patch(E),
+}
+
+impl<E: fmt::Display> fmt::Display for ReloadError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "failed to read the file: {error}"),
+            Self::Deserialize(error) => write!(f, "failed to deserialize the patch: {error}"),
+            Self::Patch(error) => write!(f, "failed to apply the patch: {error}"),
+        }
+    }
+}
+
+impl<E: core::error::Error + 'static> core::error::Error for ReloadError<E> {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Deserialize(error) => Some(&**error),
+            Self::Patch(error) => Some(error),
+        }
+    }
+}
@@ -0,0 +1,192 @@
+//! # Change Reports
+//!
+//! [`ReportPatch::patch_report`] applies a patch like [`Patch::patch`] and reports which fields
+//! it actually changed, for cache invalidation or change events.
+//!
+//! `#[derive(ReportPatch)]` generates a `<Struct>Changes` struct with one field per non-skipped
+//! field: a `bool` for plain fields, and the [`PatchReport`] of the nested struct for fields marked
+//! with `#[patchable]`. Reports are plain values, so they need no allocation.
+//!
+//! A field counts as changed when its new value differs from the old one. Values are compared with
+//! [`PartialEq`] when their type implements it (fields whose type uses a type parameter then
+//! require it); a field of another type counts as changed whenever the patch touches it.
+
+use core::fmt;
+use core::ops::Deref;
+
+use crate::Patch;
+
+/// A type that reports the fields changed by a patch.
+///
+/// ## Usage
+///
+/// ```rust
+/// use patchable::{Patch, Patchable, ReportPatch, Snapshot};
+///
+/// #[derive(Clone, Patchable, Patch, Snapshot, ReportPatch)]
+/// struct Position {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// #[derive(Clone, Patchable, Patch, Snapshot, ReportPatch)]
+/// struct Sprite<P> {
+///     name: &'static str,
+///     #[patchable]
+///     position: P,
+/// }
+///
+/// let mut sprite = Sprite { name: "hero", position: Position { x: 0, y: 0 } };
+/// let mut moved = sprite.clone();
+/// moved.position.y = 5;
+///
+/// let report = sprite.patch_report(moved.snapshot());
+/// assert!(report.is_changed());
+/// assert!(!report.name);
+/// assert!(!report.position.x && report.position.y);
+/// ```
+pub trait ReportPatch: Patch {
+    /// The set of changed fields, generated as `<Struct>Changes` by the derive.
+    type Changes: ChangeSet;
+
+    /// Applies the given patch and reports the fields it changed.
+    fn patch_report(&mut self, patch: Self::Patch) -> PatchReport<Self>;
+}
+
+/// A set of changed fields.
+pub trait ChangeSet: Copy + Default + Eq + fmt::Debug {
+    /// Returns whether any field changed.
+    fn is_changed(&self) -> bool;
+}
+
+/// The fields of a `T` changed by a patch, returned by [`ReportPatch::patch_report`].
+///
+/// The report dereferences to the `<Struct>Changes` set of `T`.
+pub struct PatchReport<T: ReportPatch + ?Sized> {
+    changes: T::Changes,
+}
+
+impl<T: ReportPatch + ?Sized> PatchReport<T> {
+    /// Wraps the given set of changed fields.
+    #[inline(always)]
+    pub const fn new(changes: T::Changes) -> Self {
+        Self { changes }
+    }
+
+    /// Returns the set of changed fields.
+    #[inline(always)]
+    pub const fn changes(&self) -> &T::Changes {
+        &self.changes
+    }
+
+    /// Returns the set of changed fields, consuming the report.
+    #[inline(always)]
+    pub fn into_changes(self) -> T::Changes {
+        self.changes
+    }
+
+    /// Returns whether the patch changed any field.
+    #[inline(always)]
+    pub fn is_changed(&self) -> bool {
+        self.changes.is_changed()
+    }
+}
+
+impl<T: ReportPatch + ?Sized> Deref for PatchReport<T> {
+    type Target = T::Changes;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &self.changes
+    }
+}
+
+impl<T: ReportPatch + ?Sized> Clone for PatchReport<T> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ReportPatch + ?Sized> Copy for PatchReport<T> {}
+
+impl<T: ReportPatch + ?Sized> Default for PatchReport<T> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new(T::Changes::default())
+    }
+}
+
+impl<T: ReportPatch + ?Sized> PartialEq for PatchReport<T> {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        self.changes == other.changes
+    }
+}
+
+impl<T: ReportPatch + ?Sized> Eq for PatchReport<T> {}
+
+impl<T: ReportPatch + ?Sized> fmt::Debug for PatchReport<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.changes.fmt(f)
+    }
+}
+
+impl<T: ReportPatch + ?Sized> ChangeSet for PatchReport<T> {
+    #[inline(always)]
+    fn is_changed(&self) -> bool {
+        self.changes.is_changed()
+    }
+}
+
+/// Comparisons used by the derived `patch_report`, which fall back to "changed" for types that
+/// do not implement `PartialEq`.
+///
+/// The fallback relies on method resolution: `(&Probe(&value)).differs(..)` picks [`Compare`]
+/// when `T: PartialEq`, and the auto-referenced [`AssumeChanged`] otherwise. Likewise,
+/// `previous` only keeps a clone of values that can be compared afterwards.
+#[doc(hidden)]
+pub mod __private {
+    /// A value whose comparability is resolved at the call site.
+    pub struct Probe<'a, T>(pub &'a T);
+
+    pub trait Compare<T> {
+        fn differs(&self, other: &T) -> bool;
+    }
+
+    impl<T: PartialEq> Compare<T> for Probe<'_, T> {
+        #[inline(always)]
+        fn differs(&self, other: &T) -> bool {
+            self.0 != other
+        }
+    }
+
+    pub trait AssumeChanged<T> {
+        #[inline(always)]
+        fn differs(&self, _other: &T) -> bool {
+            true
+        }
+    }
+
+    impl<T> AssumeChanged<T> for &Probe<'_, T> {}
+
+    pub trait KeepPrevious<T> {
+        fn previous(&self) -> Option<T>;
+    }
+
+    impl<T: PartialEq + Clone> KeepPrevious<T> for Probe<'_, T> {
+        #[inline(always)]
+        fn previous(&self) -> Option<T> {
+            Some(self.0.clone())
+        }
+    }
+
+    pub trait DropPrevious<T> {
+        #[inline(always)]
+        fn previous(&self) -> Option<T> {
+            None
+        }
+    }
+
+    impl<T> DropPrevious<T> for &Probe<'_, T> {}
+}
@@ -0,0 +1,185 @@
+//! # Versioned State
+//!
+//! [`Versioned`] wraps a value with a version that increases with every applied patch, for
+//! optimistic concurrency between several writers. Its patch, [`VersionedPatch`], carries the
+//! version its writer last saw, and [`TryPatch::try_patch`] rejects it with
+//! [`VersionedError::Conflict`] when the value has been patched since.
+//!
+//! With the `serde` feature, both types serialize the version along with the value or the patch,
+//! so that versions travel with the state.
+
+use core::fmt;
+use core::ops::Deref;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{Patchable, TryPatch};
+
+/// A value with a version increased by each applied patch.
+///
+/// ## Usage
+///
+/// ```rust
+/// use patchable::{Patch, Patchable, TryPatch, Versioned, VersionedError, VersionedPatch};
+///
+/// #[derive(Debug, Patchable, Patch)]
+/// struct Counter {
+///     value: u32,
+/// }
+///
+/// type CounterPatch = <Counter as Patchable>::Patch;
+///
+/// let mut counter = Versioned::new(Counter { value: 0 });
+/// let seen = counter.version();
+///
+/// counter.try_patch(VersionedPatch::new(seen, CounterPatch { value: 1 })).unwrap();
+/// assert_eq!((counter.version(), counter.value), (1, 1));
+///
+/// // A second writer still holding the old version is rejected.
+/// let stale = counter.try_patch(VersionedPatch::new(seen, CounterPatch { value: 2 }));
+/// assert_eq!(stale, Err(VersionedError::Conflict { current: 1, expected: 0 }));
+/// assert_eq!(counter.value, 1);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Versioned<T> {
+    version: u64,
+    value: T,
+}
+
+impl<T> Versioned<T> {
+    /// Wraps `value` at version `0`.
+    #[inline(always)]
+    pub const fn new(value: T) -> Self {
+        Self::with_version(value, 0)
+    }
+
+    /// Wraps `value` at the given version, such as one restored from storage.
+    #[inline(always)]
+    pub const fn with_version(value: T, version: u64) -> Self {
+        Self { version, value }
+    }
+
+    /// Returns the current version.
+    #[inline(always)]
+    pub const fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Returns the wrapped value.
+    #[inline(always)]
+    pub const fn get(&self) -> &T {
+        &self.value
+    }
+
+    /// Returns the wrapped value, dropping its version.
+    #[inline(always)]
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    /// Returns a patch applying `patch` on top of the current version.
+    #[inline(always)]
+    pub fn next_patch<P>(&self, patch: P) -> VersionedPatch<P> {
+        VersionedPatch::new(self.version, patch)
+    }
+}
+
+impl<T> Deref for Versioned<T> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: Patchable> Patchable for Versioned<T> {
+    type Patch = VersionedPatch<T::Patch>;
+}
+
+/// Applies the patch if its expected version is the current one, and then increases the version.
+///
+/// The version is left unchanged when the patch is rejected, either for a version conflict, because
+/// the version is already `u64::MAX`, or by the `try_patch` of the wrapped value.
+impl<T: TryPatch> TryPatch for Versioned<T> {
+    type Error = VersionedError<T::Error>;
+
+    fn try_patch(&mut self, patch: Self::Patch) -> Result<(), Self::Error> {
+        if patch.expected_version != self.version {
+            return Err(VersionedError::Conflict {
+                current: self.version,
+                expected: patch.expected_version,
+            });
+        }
+        let next_version = self
+            .version
+            .checked_add(1)
+            .ok_or(VersionedError::VersionOverflow)?;
+        self.value
+            .try_patch(patch.patch)
+            .map_err(VersionedError::Patch)?;
+        self.version = next_version;
+        Ok(())
+    }
+}
+
+/// The patch of a [`Versioned`] value, applied only at the version its writer last saw.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct VersionedPatch<P> {
+    /// The version the patch was written against.
+    pub expected_version: u64,
+    /// The patch of the wrapped value.
+    pub patch: P,
+}
+
+impl<P> VersionedPatch<P> {
+    /// Returns a patch applying `patch` at `expected_version`.
+    #[inline(always)]
+    pub const fn new(expected_version: u64, patch: P) -> Self {
+        Self {
+            expected_version,
+            patch,
+        }
+    }
+}
+
+/// The error of [`Versioned::try_patch`](TryPatch::try_patch).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VersionedError<E> {
+    /// The value was patched since the version the patch was written against.
+    Conflict {
+        /// The current version of the value.
+        current: u64,
+        /// The version expected by the patch.
+        expected: u64,
+    },
+    /// The version is `u64::MAX`, and cannot be increased by another patch.
+    VersionOverflow,
+    /// The wrapped value rejected the patch.
+    Patch(E),
+}
+
+impl<E: fmt::Display> fmt::Display for VersionedError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Conflict { current, expected } => write!(
+                f,
+                "the patch expects version {expected}, but the current version is {current}"
+            ),
+            Self::VersionOverflow => f.write_str("the version cannot be increased past u64::MAX"),
+            Self::Patch(_) => f.write_str("the wrapped value rejected the patch"),
+        }
+    }
+}
+
+impl<E: core::error::Error + 'static> core::error::Error for VersionedError<E> {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Conflict { .. } | Self::VersionOverflow => None,
+            Self::Patch(error) => Some(error),
+        }
+    }
+}
@@ -0,0 +1,315 @@
+use std::net::IpAddr;
+
+use clap::error::ErrorKind;
+use clap::{Args, CommandFactory, FromArgMatches, Parser};
+use patchable::cli::__private::{Flag, clashes};
+use patchable::{PartialPatch, Patch, PatchArgs, Patchable};
+
+#[derive(Clone, Debug, Default, PartialEq, Patchable, Patch, PatchArgs)]
+#[patchable(partial)]
+struct Limits {
+    max_connections: u32,
+    timeout: Option<u64>,
+    enabled: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch, PatchArgs)]
+#[patchable(partial)]
+struct Config<L, T> {
+    name: String,
+    #[patchable]
+    limits: L,
+    tags: Vec<T>,
+    address: IpAddr,
+    #[patchable(delta)]
+    weight: i64,
+    #[patchable(skip)]
+    secret: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch, PatchArgs)]
+#[patchable(partial)]
+struct Server<L> {
+    #[patchable]
+    limits: L,
+    limits_file: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch, PatchArgs)]
+#[patchable(partial)]
+struct Clashing<L> {
+    #[patchable]
+    limits: L,
+    limits_timeout: Option<u64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Patchable, Patch, PatchArgs)]
+#[patchable(partial)]
+struct Counters {
+    #[patchable(merge = "max")]
+    peak: u32,
+    #[patchable(delta)]
+    total: i64,
+    labels: Option<Vec<String>>,
+    verbose: Option<bool>,
+}
+
+#[derive(Parser)]
+struct Cli {
+    #[arg(long)]
+    config: Option<String>,
+    #[command(flatten)]
+    overrides: ConfigArgs<Limits, String>,
+}
+
+fn config() -> Config<Limits, String> {
+    Config {
+        name: "api".to_owned(),
+        limits: Limits {
+            max_connections: 16,
+            timeout: None,
+            enabled: false,
+        },
+        tags: Vec::new(),
+        address: IpAddr::from([127, 0, 0, 1]),
+        weight: 5,
+        secret: "hunter2".to_owned(),
+    }
+}
+
+fn parse(args: &[&str]) -> Result<Cli, clap::Error> {
+    Cli::try_parse_from(["app"].iter().chain(args))
+}
+
+#[test]
+fn test_flags_set_nested_fields() {
+    let cli = parse(&[
+        "--config",
+        "app.toml",
+        "--limits-max-connections",
+        "128",
+        "--limits-timeout",
+        "30",
+        "--limits-enabled",
+        "--tags",
+        "edge",
+        "--tags",
+        "blue",
+        "--address",
+        "::1",
+        "--weight",
+        "-2",
+    ])
+    .unwrap();
+    assert_eq!(cli.config.as_deref(), Some("app.toml"));
+
+    let mut value = config();
+    value.patch(cli.overrides.into_patch());
+    assert_eq!(
+        value,
+        Config {
+            name: "api".to_owned(),
+            limits: Limits {
+                max_connections: 128,
+                timeout: Some(30),
+                enabled: true,
+            },
+            tags: vec!["edge".to_owned(), "blue".to_owned()],
+            address: "::1".parse().unwrap(),
+            weight: 3,
+            secret: "hunter2".to_owned(),
+        }
+    );
+}
+
+#[test]
+fn test_missing_flags_leave_fields_unchanged() {
+    let patch = parse(&[]).unwrap().overrides.into_patch();
+    assert!(patch.is_empty());
+
+    let mut value = config();
+    value.patch(patch);
+    assert_eq!(value, config());
+
+    let patch = parse(&["--name", "gateway"])
+        .unwrap()
+        .overrides
+        .into_patch();
+    let mut set = Vec::new();
+    patch.for_each_set_field(|path| set.push(path.to_string()));
+    assert_eq!(set, ["name"]);
+}
+
+#[test]
+fn test_skipped_fields_have_no_flag() {
+    let command = Cli::command();
+    let longs: Vec<_> = command
+        .get_arguments()
+        .filter_map(|arg| arg.get_long())
+        .collect();
+    assert_eq!(
+        longs,
+        [
+            "config",
+            "name",
+            "limits-max-connections",
+            "limits-timeout",
+            "limits-enabled",
+            "tags",
+            "address",
+            "weight",
+        ]
+    );
+
+    let error = parse(&["--secret", "leaked"]).err().unwrap();
+    assert_eq!(error.kind(), ErrorKind::UnknownArgument);
+}
+
+#[test]
+fn test_invalid_values_are_rejected() {
+    for args in [
+        &["--limits-max-connections", "many"][..],
+        &["--address", "localhost"],
+        &["--limits-enabled=maybe"],
+    ] {
+        let error = parse(args).err().unwrap();
+        assert!(
+            matches!(
+                error.kind(),
+                ErrorKind::ValueValidation | ErrorKind::InvalidValue
+            ),
+            "{args:?}"
+        );
+    }
+}
+
+#[test]
+fn test_update_keeps_earlier_flags() {
+    let command = ConfigArgs::<Limits, String>::augment_args(clap::Command::new("app"));
+    let first = command
+        .clone()
+        .try_get_matches_from(["app", "--name", "first", "--limits-timeout", "1"])
+        .unwrap();
+    let later = command
+        .try_get_matches_from(["app", "--limits-timeout", "2"])
+        .unwrap();
+
+    let mut args = ConfigArgs::<Limits, String>::from_arg_matches(&first).unwrap();
+    args.update_from_arg_matches(&later).unwrap();
+
+    let mut value = config();
+    value.patch(args.into_patch());
+    assert_eq!(value.name, "first");
+    assert_eq!(value.limits.timeout, Some(2));
+}
+
+#[test]
+fn test_flags_sharing_a_prefix_with_a_nested_field() {
+    let command = ServerArgs::<Limits>::augment_args(clap::Command::new("app"));
+    let matches = command
+        .try_get_matches_from([
+            "app",
+            "--limits-file",
+            "limits.json",
+            "--limits-timeout",
+            "3",
+        ])
+        .unwrap();
+    let patch = ServerArgs::<Limits>::from_arg_matches(&matches)
+        .unwrap()
+        .into_patch();
+    assert_eq!(patch.limits_file.as_deref(), Some("limits.json"));
+    assert_eq!(
+        patch.limits.and_then(|limits| limits.timeout),
+        Some(Some(3))
+    );
+}
+
+#[test]
+fn test_clashing_flags_are_detected() {
+    // `augment_patch_args` asserts this at compile time, once `ClashingArgs<Limits>` is used.
+    let flags = <Clashing<Limits> as PatchArgs>::PATCH_FLAGS;
+    assert!(clashes(&flags[0], &flags[1]));
+
+    let flags = <Server<Limits> as PatchArgs>::PATCH_FLAGS;
+    assert!(!clashes(&flags[0], &flags[1]));
+
+    // Both `--a-b-c` flags, through two nested fields.
+    let first = Flag {
+        name: "a",
+        nested: Some(&[Flag {
+            name: "b-c",
+            nested: None,
+        }]),
+    };
+    let second = Flag {
+        name: "a-b",
+        nested: Some(&[Flag {
+            name: "c",
+            nested: None,
+        }]),
+    };
+    assert!(clashes(&first, &second));
+}
+
+fn counters(args: &[&str]) -> <Counters as Patchable>::Patch {
+    let command = CountersArgs::augment_args(clap::Command::new("app"));
+    let matches = command
+        .try_get_matches_from(["app"].iter().chain(args))
+        .unwrap();
+    CountersArgs::from_arg_matches(&matches)
+        .unwrap()
+        .into_patch()
+}
+
+#[test]
+fn test_bool_flags_are_switches() {
+    let patch = parse(&["--limits-enabled"]).unwrap().overrides.into_patch();
+    assert_eq!(patch.limits.and_then(|limits| limits.enabled), Some(true));
+
+    let patch = parse(&["--limits-enabled=false"])
+        .unwrap()
+        .overrides
+        .into_patch();
+    assert_eq!(patch.limits.and_then(|limits| limits.enabled), Some(false));
+
+    // The value of a switch follows `=`, so a separate argument is not taken as its value.
+    let error = parse(&["--limits-enabled", "false"]).err().unwrap();
+    assert_eq!(error.kind(), ErrorKind::UnknownArgument);
+
+    assert_eq!(counters(&["--verbose"]).verbose, Some(Some(true)));
+    assert_eq!(counters(&[]).verbose, None);
+}
+
+#[test]
+fn test_optional_lists_take_a_value_per_flag() {
+    let patch = counters(&["--labels", "a", "--labels", "b"]);
+    assert_eq!(
+        patch.labels,
+        Some(Some(vec!["a".to_owned(), "b".to_owned()]))
+    );
+    assert_eq!(counters(&["--peak", "3"]).labels, None);
+}
+
+#[test]
+fn test_help_describes_the_effect_of_the_value() {
+    let command = CountersArgs::augment_args(clap::Command::new("app"));
+    let help = |id: &str| {
+        let arg = command
+            .get_arguments()
+            .find(|arg| arg.get_id() == id)
+            .unwrap();
+        arg.get_help().unwrap().to_string()
+    };
+    assert_eq!(help("peak"), "Merges the value into `peak`");
+    assert_eq!(help("total"), "Adds the value to `total`");
+    assert_eq!(help("labels"), "Sets `labels`");
+
+    let mut value = Counters {
+        peak: 5,
+        total: 10,
+        ..Default::default()
+    };
+    value.patch(counters(&["--peak", "3", "--total", "-4"]));
+    assert_eq!((value.peak, value.total), (5, 6));
+}
@@ -0,0 +1,186 @@
+use patchable::{ComposePatch, Patch, Patchable};
+use proptest::prelude::*;
+use serde_json::{Value, json};
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch)]
+struct Inner {
+    label: String,
+    #[patchable(merge = "max")]
+    peak: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch)]
+struct Outer<T> {
+    #[patchable]
+    inner: T,
+    value: i64,
+    #[patchable(merge = "add")]
+    total: u64,
+    #[patchable(merge = "append")]
+    log: Vec<u8>,
+    #[patchable(merge = "or")]
+    flags: u8,
+    #[patchable(delta)]
+    counter: i32,
+    #[patchable(skip)]
+    cache: u8,
+}
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch)]
+struct Pair(u8, #[patchable(delta)] u16);
+
+type OuterPatch = <Outer<Inner> as Patchable>::Patch;
+
+fn outer_patch(patch: &Value) -> OuterPatch {
+    serde_json::from_value(patch.clone()).unwrap()
+}
+
+fn inner_strategy() -> impl Strategy<Value = Inner> {
+    ("[a-z]{0,4}", any::<u32>()).prop_map(|(label, peak)| Inner { label, peak })
+}
+
+fn outer_strategy() -> impl Strategy<Value = Outer<Inner>> {
+    (
+        inner_strategy(),
+        any::<i64>(),
+        0..u64::MAX / 4,
+        proptest::collection::vec(any::<u8>(), 0..4),
+        any::<u8>(),
+        any::<i32>(),
+        any::<u8>(),
+    )
+        .prop_map(|(inner, value, total, log, flags, counter, cache)| Outer {
+            inner,
+            value,
+            total,
+            log,
+            flags,
+            counter,
+            cache,
+        })
+}
+
+fn patch_strategy() -> impl Strategy<Value = Value> {
+    (
+        "[a-z]{0,4}",
+        any::<u32>(),
+        any::<i64>(),
+        0..u64::MAX / 4,
+        proptest::collection::vec(any::<u8>(), 0..4),
+        any::<u8>(),
+        any::<i32>(),
+    )
+        .prop_map(|(label, peak, value, total, log, flags, counter)| {
+            json!({
+                "inner": { "label": label, "peak": peak },
+                "value": value,
+                "total": total,
+                "log": log,
+                "flags": flags,
+                "counter": counter,
+            })
+        })
+}
+
+proptest! {
+    #[test]
+    fn compose_matches_sequential_patches(
+        start in outer_strategy(),
+        first in patch_strategy(),
+        later in patch_strategy(),
+    ) {
+        let mut sequential = start.clone();
+        sequential.patch(outer_patch(&first));
+        sequential.patch(outer_patch(&later));
+
+        let mut composed = start;
+        composed.patch(outer_patch(&first).compose(outer_patch(&later)));
+
+        prop_assert_eq!(composed, sequential);
+    }
+
+    #[test]
+    fn compose_is_associative(
+        start in outer_strategy(),
+        a in patch_strategy(),
+        b in patch_strategy(),
+        c in patch_strategy(),
+    ) {
+        let mut left = start.clone();
+        left.patch(outer_patch(&a).compose(outer_patch(&b)).compose(outer_patch(&c)));
+
+        let mut right = start;
+        right.patch(outer_patch(&a).compose(outer_patch(&b).compose(outer_patch(&c))));
+
+        prop_assert_eq!(left, right);
+    }
+
+    #[test]
+    fn compose_tuple_struct_matches_sequential_patches(
+        start in any::<(u8, u16)>(),
+        first in any::<(u8, u16)>(),
+        later in any::<(u8, u16)>(),
+    ) {
+        let patch = |(keep, delta): (u8, u16)| -> <Pair as Patchable>::Patch {
+            serde_json::from_value(json!([keep, delta])).unwrap()
+        };
+
+        let mut sequential = Pair(start.0, start.1);
+        sequential.patch(patch(first));
+        sequential.patch(patch(later));
+
+        let mut composed = Pair(start.0, start.1);
+        composed.patch(patch(first).compose(patch(later)));
+
+        prop_assert_eq!(composed, sequential);
+    }
+}
+
+#[test]
+fn test_compose_keeps_last_write_and_merges() {
+    let first = outer_patch(&json!({
+        "inner": { "label": "a", "peak": 9 },
+        "value": 1,
+        "total": 2,
+        "log": [1],
+        "flags": 1,
+        "counter": -4,
+    }));
+    let later = outer_patch(&json!({
+        "inner": { "label": "b", "peak": 3 },
+        "value": 5,
+        "total": 3,
+        "log": [2, 3],
+        "flags": 2,
+        "counter": 10,
+    }));
+    let mut outer = Outer {
+        inner: Inner {
+            label: String::new(),
+            peak: 0,
+        },
+        value: 0,
+        total: 0,
+        log: vec![],
+        flags: 0,
+        counter: 0,
+        cache: 7,
+    };
+
+    outer.patch(first.compose(later));
+    assert_eq!(
+        outer,
+        Outer {
+            inner: Inner {
+                label: "b".to_owned(),
+                peak: 9,
+            },
+            value: 5,
+            total: 5,
+            log: vec![1, 2, 3],
+            flags: 3,
+            counter: 6,
+            cache: 7,
+        }
+    );
+}
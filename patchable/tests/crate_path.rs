@@ -0,0 +1,85 @@
+use patchable::{Patch, Patchable};
+
+mod facade {
+    pub use ::patchable;
+}
+
+/// Local stand-ins for the `patchable` traits, used to check that the derives honor the
+/// configured crate path instead of `::patchable`.
+mod shim {
+    pub trait Patchable {
+        type Patch;
+    }
+
+    pub trait Patch: Patchable {
+        fn patch(&mut self, patch: Self::Patch);
+    }
+
+    pub trait ComposePatch {
+        fn compose(self, later: Self) -> Self;
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch)]
+#[patchable(crate = "crate::facade::patchable")]
+struct FacadeInner {
+    value: i32,
+}
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch)]
+#[patchable(crate = "crate::facade::patchable")]
+struct FacadeOuter<T> {
+    #[patchable]
+    inner: T,
+    #[patchable(skip)]
+    marker: u8,
+}
+
+#[patchable::patchable_model(crate = "crate::facade::patchable")]
+#[derive(Clone, Debug, PartialEq)]
+struct ModelWithFacade {
+    value: i32,
+    #[patchable(skip)]
+    cache: u32,
+}
+
+#[derive(Patchable, Patch)]
+#[patchable(crate = "crate::shim")]
+struct ShimmedStruct {
+    value: i32,
+}
+
+#[test]
+fn test_custom_crate_path_resolves_through_facade() {
+    let mut target = FacadeOuter {
+        inner: FacadeInner { value: 0 },
+        marker: 1,
+    };
+    let patch: <FacadeOuter<FacadeInner> as Patchable>::Patch =
+        serde_json::from_str(r#"{"inner": {"value": 42}}"#).unwrap();
+
+    <FacadeOuter<FacadeInner> as facade::patchable::Patch>::patch(&mut target, patch);
+    assert_eq!(target.inner, FacadeInner { value: 42 });
+    assert_eq!(target.marker, 1);
+}
+
+#[test]
+fn test_patchable_model_forwards_custom_crate_path() {
+    let mut model = ModelWithFacade { value: 1, cache: 2 };
+    let state = serde_json::to_string(&ModelWithFacade { value: 7, cache: 9 }).unwrap();
+
+    model.patch(serde_json::from_str(&state).unwrap());
+    assert_eq!(model, ModelWithFacade { value: 7, cache: 2 });
+}
+
+#[test]
+fn test_custom_crate_path_is_used_for_trait_impls() {
+    let mut shimmed = ShimmedStruct { value: 0 };
+    let patch: <ShimmedStruct as shim::Patchable>::Patch =
+        serde_json::from_str(r#"{"value": 5}"#).unwrap();
+    let later: <ShimmedStruct as shim::Patchable>::Patch =
+        serde_json::from_str(r#"{"value": 6}"#).unwrap();
+
+    shim::Patch::patch(&mut shimmed, shim::ComposePatch::compose(patch, later));
+    assert_eq!(shimmed.value, 6);
+}
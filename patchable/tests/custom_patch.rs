@@ -0,0 +1,104 @@
+use patchable::{Patch, Patchable};
+
+/// Hand-written patch for [`Settings`], with its own derives.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct SettingsPatch {
+    retries: u32,
+    label: String,
+}
+
+#[derive(Debug, PartialEq, Patchable, Patch)]
+#[patchable(patch = SettingsPatch)]
+struct Settings {
+    retries: u32,
+    label: String,
+    #[patchable(skip)]
+    cache: Vec<u8>,
+}
+
+struct CounterPatch {
+    value: i32,
+}
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch)]
+#[patchable(patch = CounterPatch)]
+struct Counter {
+    value: i32,
+}
+
+struct WrapperPatch<T: Patchable> {
+    inner: T::Patch,
+    version: u32,
+}
+
+#[derive(Debug, PartialEq, Patchable, Patch)]
+#[patchable(patch = WrapperPatch<T>)]
+struct Wrapper<T> {
+    #[patchable]
+    inner: T,
+    version: u32,
+}
+
+struct PairPatch(i32, i64);
+
+#[derive(Debug, PartialEq, Patchable, Patch)]
+#[patchable(patch = PairPatch)]
+struct Pair(i32, #[patchable(skip)] u8, i64);
+
+#[test]
+fn test_custom_patch_type_is_used_as_patchable_patch() {
+    fn assert_patch_type<T: Patchable<Patch = P>, P>() {}
+
+    assert_patch_type::<Settings, SettingsPatch>();
+    assert_patch_type::<Pair, PairPatch>();
+}
+
+#[test]
+fn test_custom_patch_type_applies_fields() {
+    let mut settings = Settings {
+        retries: 1,
+        label: "old".to_string(),
+        cache: vec![1, 2, 3],
+    };
+
+    settings.patch(SettingsPatch {
+        retries: 4,
+        label: "new".to_string(),
+    });
+    assert_eq!(
+        settings,
+        Settings {
+            retries: 4,
+            label: "new".to_string(),
+            cache: vec![1, 2, 3],
+        }
+    );
+}
+
+#[test]
+fn test_custom_patch_type_with_nested_field() {
+    let mut wrapper = Wrapper {
+        inner: Counter { value: 1 },
+        version: 1,
+    };
+
+    wrapper.patch(WrapperPatch {
+        inner: CounterPatch { value: 5 },
+        version: 2,
+    });
+    assert_eq!(
+        wrapper,
+        Wrapper {
+            inner: Counter { value: 5 },
+            version: 2,
+        }
+    );
+}
+
+#[test]
+fn test_custom_patch_type_for_tuple_struct() {
+    let mut pair = Pair(1, 9, 2);
+
+    pair.patch(PairPatch(10, 20));
+    assert_eq!(pair, Pair(10, 9, 20));
+}
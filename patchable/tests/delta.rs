@@ -0,0 +1,321 @@
+use patchable::{ComposePatch, Delta, Patch, PatchError, Patchable, TryPatch};
+use proptest::prelude::*;
+use serde::Deserialize;
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch)]
+struct Counters {
+    #[patchable(delta)]
+    requests: u64,
+    #[patchable(delta)]
+    balance: i32,
+    #[patchable(delta)]
+    load: f64,
+    label: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch)]
+struct CheckedCounters {
+    #[patchable(delta = "checked")]
+    hits: u8,
+    #[patchable(delta = "checked")]
+    level: i8,
+    #[patchable(delta)]
+    generation: u8,
+}
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch)]
+struct Outer<T> {
+    #[patchable]
+    inner: T,
+    #[patchable(delta = "checked")]
+    total: u16,
+}
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch)]
+struct Plain {
+    value: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+struct CountersPatch {
+    requests: i128,
+    balance: i32,
+    load: f64,
+    label: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch)]
+#[patchable(patch = CountersPatch)]
+struct HandWritten {
+    #[patchable(delta)]
+    requests: u64,
+    #[patchable(delta)]
+    balance: i32,
+    #[patchable(delta)]
+    load: f64,
+    label: String,
+}
+
+#[test]
+fn test_delta_fields_add_differences() {
+    let mut counters = Counters {
+        requests: 10,
+        balance: -5,
+        load: 0.5,
+        label: "a".to_owned(),
+    };
+    let patch: <Counters as Patchable>::Patch =
+        serde_json::from_str(r#"{"requests": 3, "balance": -10, "load": 0.25, "label": "b"}"#)
+            .unwrap();
+
+    counters.patch(patch);
+    assert_eq!(
+        counters,
+        Counters {
+            requests: 13,
+            balance: -15,
+            load: 0.75,
+            label: "b".to_owned(),
+        }
+    );
+}
+
+#[test]
+fn test_delta_patches_commute() {
+    let first = r#"{"requests": 1, "balance": 7, "load": 1.0, "label": "x"}"#;
+    let second = r#"{"requests": 4, "balance": -2, "load": 2.0, "label": "x"}"#;
+    let start = Counters {
+        requests: 0,
+        balance: 0,
+        load: 0.0,
+        label: String::new(),
+    };
+
+    let mut forward = start.clone();
+    forward.patch(serde_json::from_str(first).unwrap());
+    forward.patch(serde_json::from_str(second).unwrap());
+    let mut backward = start;
+    backward.patch(serde_json::from_str(second).unwrap());
+    backward.patch(serde_json::from_str(first).unwrap());
+
+    assert_eq!(forward, backward);
+    assert_eq!(forward.requests, 5);
+    assert_eq!(forward.balance, 5);
+}
+
+#[test]
+fn test_delta_wraps_unchecked_overflow() {
+    let mut checked = CheckedCounters {
+        hits: 0,
+        level: 0,
+        generation: u8::MAX,
+    };
+    let patch: <CheckedCounters as Patchable>::Patch =
+        serde_json::from_str(r#"{"hits": 1, "level": -1, "generation": 2}"#).unwrap();
+
+    checked.try_patch(patch).unwrap();
+    assert_eq!(
+        checked,
+        CheckedCounters {
+            hits: 1,
+            level: -1,
+            generation: 1,
+        }
+    );
+}
+
+#[test]
+fn test_checked_delta_reports_overflow_without_changes() {
+    let mut checked = CheckedCounters {
+        hits: 1,
+        level: i8::MIN,
+        generation: 0,
+    };
+    let original = checked.clone();
+    let patch: <CheckedCounters as Patchable>::Patch =
+        serde_json::from_str(r#"{"hits": 1, "level": -1, "generation": 1}"#).unwrap();
+
+    let error = checked.try_patch(patch).unwrap_err();
+    assert_eq!(error, PatchError::Overflow { field: "level" });
+    assert_eq!(
+        error.to_string(),
+        "applying the delta to `level` overflowed"
+    );
+    assert_eq!(checked, original);
+}
+
+#[test]
+fn test_composed_checked_delta_only_checks_the_sum() {
+    let start = CheckedCounters {
+        hits: 0,
+        level: 1,
+        generation: 0,
+    };
+    let first = r#"{"hits": 0, "level": 127, "generation": 0}"#;
+    let later = r#"{"hits": 0, "level": -1, "generation": 0}"#;
+    let patch =
+        |json| -> <CheckedCounters as Patchable>::Patch { serde_json::from_str(json).unwrap() };
+
+    let mut sequential = start.clone();
+    assert_eq!(
+        sequential.try_patch(patch(first)),
+        Err(PatchError::Overflow { field: "level" })
+    );
+
+    let mut composed = start;
+    composed
+        .try_patch(patch(first).compose(patch(later)))
+        .unwrap();
+    assert_eq!(composed.level, i8::MAX);
+}
+
+#[test]
+fn test_checked_delta_with_nested_patch() {
+    let mut outer = Outer {
+        inner: Plain { value: 1 },
+        total: u16::MAX - 1,
+    };
+    let patch: <Outer<Plain> as Patchable>::Patch =
+        serde_json::from_str(r#"{"inner": {"value": 2}, "total": 1}"#).unwrap();
+    outer.try_patch(patch).unwrap();
+    assert_eq!(outer.inner, Plain { value: 2 });
+    assert_eq!(outer.total, u16::MAX);
+
+    let patch: <Outer<Plain> as Patchable>::Patch =
+        serde_json::from_str(r#"{"inner": {"value": 3}, "total": 1}"#).unwrap();
+    assert_eq!(
+        outer.try_patch(patch),
+        Err(PatchError::Overflow { field: "total" })
+    );
+    assert_eq!(outer.inner, Plain { value: 2 });
+}
+
+#[test]
+fn test_delta_with_hand_written_patch() {
+    let mut value = HandWritten {
+        requests: 1,
+        balance: 1,
+        load: 1.0,
+        label: String::new(),
+    };
+    value.patch(CountersPatch {
+        requests: 1,
+        balance: -2,
+        load: 0.5,
+        label: "z".to_owned(),
+    });
+    assert_eq!(value.requests, 2);
+    assert_eq!(value.balance, -1);
+    assert_eq!(value.load, 1.5);
+    assert_eq!(value.label, "z");
+}
+
+#[test]
+fn test_delta_trait_round_trips() {
+    assert_eq!(u8::delta(&250, &4), -246);
+    let mut value = 250_u8;
+    value.apply_delta(u8::delta(&250, &4));
+    assert_eq!(value, 4);
+    assert_eq!(250_u8.checked_apply_delta(10), None);
+
+    assert_eq!(i64::delta(&-3, &4), 7);
+    assert_eq!(f32::delta(&1.5, &4.0), 2.5);
+    assert_eq!(f64::MAX.checked_apply_delta(f64::MAX), None);
+}
+
+#[test]
+fn test_unsigned_delta_can_decrease() {
+    let mut checked = CheckedCounters {
+        hits: 200,
+        level: 0,
+        generation: 0,
+    };
+    let patch: <CheckedCounters as Patchable>::Patch =
+        serde_json::from_str(r#"{"hits": -150, "level": 0, "generation": -1}"#).unwrap();
+
+    checked.try_patch(patch).unwrap();
+    assert_eq!((checked.hits, checked.generation), (50, u8::MAX));
+
+    let patch = serde_json::from_str(r#"{"hits": -51, "level": 0, "generation": 0}"#).unwrap();
+    assert_eq!(
+        checked.try_patch(patch),
+        Err(PatchError::Overflow { field: "hits" })
+    );
+}
+
+proptest! {
+    #[test]
+    fn unsigned_checked_delta_round_trips(a in any::<u64>(), b in any::<u64>()) {
+        prop_assert_eq!(a.checked_apply_delta(u64::delta(&a, &b)), Some(b));
+        prop_assert_eq!(b.checked_apply_delta(u64::delta(&b, &a)), Some(a));
+    }
+
+    #[test]
+    fn unsigned_checked_delta_round_trips_decreases(a in any::<u8>(), b in any::<u8>()) {
+        let (from, to) = (a.max(b), a.min(b));
+        prop_assert_eq!(from.checked_apply_delta(u8::delta(&from, &to)), Some(to));
+        prop_assert_eq!(
+            usize::from(from).checked_apply_delta(usize::delta(&from.into(), &to.into())),
+            Some(usize::from(to))
+        );
+    }
+}
+
+#[cfg(feature = "impl_from")]
+#[test]
+fn test_delta_from_builds_increment_from_default() {
+    let source = Counters {
+        requests: 4,
+        balance: -2,
+        load: 1.5,
+        label: "s".to_owned(),
+    };
+    let mut target = Counters {
+        requests: 1,
+        balance: 1,
+        load: 1.0,
+        label: String::new(),
+    };
+
+    target.patch(source.into());
+    assert_eq!(
+        target,
+        Counters {
+            requests: 5,
+            balance: -1,
+            load: 2.5,
+            label: "s".to_owned(),
+        }
+    );
+}
+
+mod foreign {
+    pub struct Meter {
+        pub reading: u16,
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Patchable, Patch)]
+#[patchable(remote = "foreign::Meter")]
+struct MeterDef {
+    #[patchable(delta = "checked")]
+    reading: u16,
+}
+
+#[test]
+fn test_checked_delta_on_remote_type() {
+    let mut meter = foreign::Meter { reading: 10 };
+    MeterDef::patch(
+        &mut meter,
+        serde_json::from_str(r#"{"reading": 5}"#).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(meter.reading, 15);
+
+    let patch = serde_json::from_str(r#"{"reading": 65535}"#).unwrap();
+    assert_eq!(
+        MeterDef::patch(&mut meter, patch),
+        Err(PatchError::Overflow { field: "reading" })
+    );
+}
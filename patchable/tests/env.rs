@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+
+use patchable::env::{EnvError, from_vars};
+use patchable::{PartialPatch, Patch, Patchable};
+use serde::Deserialize;
+
+#[derive(Clone, Debug, Default, PartialEq, Patchable, Patch)]
+#[patchable(partial)]
+struct Limits {
+    max_connections: u32,
+    timeout: Option<u64>,
+    enabled: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+enum Mode {
+    #[default]
+    Fast,
+    Safe,
+}
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch)]
+#[patchable(partial)]
+struct Config<L> {
+    name: String,
+    #[patchable]
+    limits: L,
+    tags: Vec<String>,
+    mode: Mode,
+    address: IpAddr,
+    ratio: f32,
+    #[patchable(skip)]
+    secret: String,
+}
+
+/// A range whose bounds are read from separate variables, and rejected once both are known.
+#[derive(Debug, Deserialize)]
+#[serde(try_from = "RawRange")]
+struct RangePatch {
+    start: Option<u32>,
+    end: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct RawRange {
+    start: Option<u32>,
+    end: Option<u32>,
+}
+
+impl TryFrom<RawRange> for RangePatch {
+    type Error = &'static str;
+
+    fn try_from(raw: RawRange) -> Result<Self, Self::Error> {
+        match (raw.start, raw.end) {
+            (Some(start), Some(end)) if start > end => Err("the range ends before it starts"),
+            (start, end) => Ok(Self { start, end }),
+        }
+    }
+}
+
+mod range {
+    pub type Patch = super::RangePatch;
+
+    pub fn patch(field: &mut (u32, u32), patch: Patch) {
+        field.0 = patch.start.unwrap_or(field.0);
+        field.1 = patch.end.unwrap_or(field.1);
+    }
+
+    #[allow(dead_code)]
+    pub fn from(field: (u32, u32)) -> Patch {
+        super::RangePatch {
+            start: Some(field.0),
+            end: Some(field.1),
+        }
+    }
+}
+
+#[derive(Debug, Patchable, Patch)]
+#[patchable(partial)]
+struct Window {
+    #[patchable(with = "range")]
+    range: (u32, u32),
+}
+
+fn config() -> Config<Limits> {
+    Config {
+        name: "api".to_owned(),
+        limits: Limits::default(),
+        tags: Vec::new(),
+        mode: Mode::Fast,
+        address: IpAddr::from([127, 0, 0, 1]),
+        ratio: 0.5,
+        secret: "hunter2".to_owned(),
+    }
+}
+
+fn read(vars: &[(&str, &str)]) -> Result<Config<Limits>, EnvError> {
+    let patch = from_vars::<Config<Limits>>("APP", vars.iter().copied())?;
+    let mut value = config();
+    value.patch(patch);
+    Ok(value)
+}
+
+fn error_names(error: &EnvError) -> Vec<&str> {
+    error.errors().iter().map(|error| error.name()).collect()
+}
+
+#[test]
+fn test_variables_map_to_nested_fields() {
+    let value = read(&[
+        ("APP_NAME", "gateway"),
+        ("APP_LIMITS__MAX_CONNECTIONS", "128"),
+        ("APP_LIMITS__TIMEOUT", "30"),
+        ("APP_LIMITS__ENABLED", "true"),
+        ("APP_TAGS", "edge, blue,"),
+        ("APP_MODE", "Safe"),
+        ("APP_ADDRESS", "::1"),
+        ("APP_RATIO", "0.25"),
+    ])
+    .unwrap();
+
+    assert_eq!(
+        value,
+        Config {
+            name: "gateway".to_owned(),
+            limits: Limits {
+                max_connections: 128,
+                timeout: Some(30),
+                enabled: true,
+            },
+            tags: vec!["edge".to_owned(), "blue".to_owned()],
+            mode: Mode::Safe,
+            address: "::1".parse().unwrap(),
+            ratio: 0.25,
+            secret: "hunter2".to_owned(),
+        }
+    );
+}
+
+#[test]
+fn test_unrelated_and_unknown_variables_are_ignored() {
+    let patch = from_vars::<Config<Limits>>(
+        "APP",
+        HashMap::from([
+            ("HOME", "/root"),
+            ("APPLICATION", "x"),
+            ("app_name", "lower"),
+            ("APP_SECRET", "leaked"),
+            ("APP_LIMITS__BURST", "3"),
+        ]),
+    )
+    .unwrap();
+    assert!(patch.is_empty());
+
+    let mut value = config();
+    value.patch(patch);
+    assert_eq!(value, config());
+}
+
+#[test]
+fn test_every_malformed_variable_is_reported() {
+    let error = read(&[
+        ("APP_RATIO", "half"),
+        ("APP_LIMITS__MAX_CONNECTIONS", "-1"),
+        ("APP_NAME", "fine"),
+        ("APP_MODE", "Slow"),
+        ("APP_LIMITS____TIMEOUT", "1"),
+        ("APP_ADDRESS", "localhost"),
+    ])
+    .unwrap_err();
+
+    assert_eq!(
+        error_names(&error),
+        [
+            "APP_ADDRESS",
+            "APP_LIMITS__MAX_CONNECTIONS",
+            "APP_LIMITS____TIMEOUT",
+            "APP_MODE",
+            "APP_RATIO",
+        ]
+    );
+    let message = error.to_string();
+    assert!(
+        message.starts_with("invalid variable `APP_ADDRESS`: "),
+        "{message}"
+    );
+    assert!(message.contains("invalid variable `APP_RATIO`: invalid value `half`"));
+}
+
+#[test]
+fn test_clashing_variables_are_reported() {
+    let error = read(&[("APP_LIMITS__TIMEOUT", "1"), ("APP_limits__timeout", "2")]).unwrap_err();
+    assert_eq!(error_names(&error), ["APP_limits__timeout"]);
+    assert_eq!(
+        error.errors()[0].message(),
+        "clashes with `APP_LIMITS__TIMEOUT`"
+    );
+}
+
+#[test]
+fn test_errors_of_combined_variables_name_a_variable() {
+    let vars = [("APP_RANGE__START", "5"), ("APP_RANGE__END", "2")];
+    let Err(error) = from_vars::<Window>("APP", vars) else {
+        panic!("the range should be rejected");
+    };
+    assert_eq!(error_names(&error), ["APP_RANGE__START"]);
+    assert_eq!(
+        error.to_string(),
+        "invalid variable `APP_RANGE__START`: the range ends before it starts"
+    );
+
+    let mut window = Window { range: (0, 10) };
+    window.patch(from_vars::<Window>("APP", [("APP_RANGE__START", "5")]).unwrap());
+    assert_eq!(window.range, (5, 10));
+}
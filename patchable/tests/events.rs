@@ -0,0 +1,99 @@
+use patchable::{Patch, PatchEvents, Patchable, Snapshot};
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Address {
+    city: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch, Snapshot)]
+#[patchable(patch = Address)]
+struct Location {
+    city: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch, Snapshot, PatchEvents)]
+struct Customer<L> {
+    name: String,
+    #[patchable]
+    location: L,
+    #[patchable(skip)]
+    visits: u32,
+    #[patchable(delta)]
+    balance: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch, Snapshot, PatchEvents)]
+struct Pair(u8, #[patchable(skip)] bool, char);
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch, Snapshot, PatchEvents)]
+struct Unit;
+
+fn customer(name: &str, city: &str, balance: i64) -> Customer<Location> {
+    Customer {
+        name: name.to_owned(),
+        location: Location {
+            city: city.to_owned(),
+        },
+        visits: 1,
+        balance,
+    }
+}
+
+#[test]
+fn test_apply_event_updates_single_field() {
+    let mut target = customer("ada", "london", 10);
+
+    target.apply_event(CustomerEvent::Name("grace".to_owned()));
+    target.apply_event(CustomerEvent::Balance(-4));
+    target.apply_event(CustomerEvent::Location(Address {
+        city: "paris".to_owned(),
+    }));
+    assert_eq!(target, customer("grace", "paris", 6));
+}
+
+#[test]
+fn test_replaying_events_matches_patch() {
+    let source = customer("grace", "paris", 7);
+    let mut patched = customer("ada", "london", 0);
+    let mut replayed = patched.clone();
+
+    patched.patch(source.snapshot());
+    for event in Customer::into_events(source.snapshot()) {
+        replayed.apply_event(event);
+    }
+    assert_eq!(replayed, patched);
+
+    let mut pair = Pair(1, true, 'a');
+    let events: Vec<_> = Pair::into_events(Pair(2, false, 'b').snapshot()).collect();
+    assert!(matches!(
+        events[..],
+        [PairEvent::Field0(2), PairEvent::Field2('b')]
+    ));
+    events.into_iter().for_each(|event| pair.apply_event(event));
+    assert_eq!(pair, Pair(2, true, 'b'));
+
+    assert_eq!(Unit::into_events(Unit.snapshot()).count(), 0);
+}
+
+#[test]
+fn test_events_roundtrip_through_serde() {
+    let events: Vec<_> = Customer::into_events(customer("ada", "rome", 3).snapshot()).collect();
+    let persisted: Vec<String> = events
+        .iter()
+        .map(|event| serde_json::to_string(event).unwrap())
+        .collect();
+    assert_eq!(
+        persisted,
+        [
+            r#"{"Name":"ada"}"#,
+            r#"{"Location":{"city":"rome"}}"#,
+            r#"{"Balance":3}"#
+        ]
+    );
+
+    let mut target = customer("", "", 0);
+    for event in &persisted {
+        target.apply_event(serde_json::from_str(event).unwrap());
+    }
+    assert_eq!(target, customer("ada", "rome", 3));
+}
@@ -0,0 +1,149 @@
+use patchable::{FromPatch, Patch, Patchable, Snapshot};
+
+#[derive(Clone, Debug, Default, PartialEq, Patchable, Patch, Snapshot, FromPatch)]
+struct Inner {
+    value: i32,
+    #[patchable(skip, default = 7)]
+    hits: u32,
+}
+
+#[derive(Debug, PartialEq, Patchable, Patch, Snapshot, FromPatch)]
+struct Accumulator<T, F> {
+    #[patchable]
+    inner: T,
+    #[patchable(merge = "add")]
+    total: u64,
+    #[patchable(delta)]
+    counter: i16,
+    #[patchable(skip)]
+    filter: F,
+    #[patchable(skip, default = Vec::new())]
+    cache: Vec<u8>,
+}
+
+#[derive(Debug, PartialEq, Patchable, Patch, Snapshot, FromPatch)]
+struct Limits {
+    #[patchable(merge = "min")]
+    low: u32,
+    #[patchable(merge = "and")]
+    mask: u8,
+    #[patchable(merge = "max")]
+    peak: i32,
+}
+
+#[derive(Debug, PartialEq, Patchable, Patch, Snapshot, FromPatch)]
+struct Tuple(#[patchable(skip)] u8, String, #[patchable(skip)] char);
+
+#[derive(Debug, PartialEq, Patchable, Patch, Snapshot, FromPatch)]
+struct Unit;
+
+#[derive(Debug, PartialEq, Patchable, Patch, Snapshot, FromPatch)]
+#[patchable(transparent)]
+struct Wrapper<T>(#[patchable] T, #[patchable(skip, default = 1)] u8);
+
+#[derive(Clone, Debug, PartialEq)]
+struct LabelPatch {
+    label: String,
+}
+
+#[derive(Debug, PartialEq, Patchable, Patch, FromPatch)]
+#[patchable(patch = LabelPatch)]
+struct Labeled {
+    label: String,
+    #[patchable(skip, default = 0)]
+    width: usize,
+}
+
+mod foreign {
+    pub struct Point {
+        pub x: i32,
+        pub y: i32,
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Patchable, Patch, Snapshot, FromPatch)]
+#[patchable(remote = "foreign::Point")]
+struct PointDef {
+    x: i32,
+    #[patchable(skip)]
+    y: i32,
+}
+
+#[test]
+fn test_from_patch_rebuilds_value() {
+    let source = Accumulator {
+        inner: Inner { value: 3, hits: 1 },
+        total: 10,
+        counter: -4,
+        filter: |x: &i32| *x > 2,
+        cache: vec![1, 2],
+    };
+    let filter: fn(&i32) -> bool = |x| *x < 0;
+
+    let restored = Accumulator::from_patch(source.snapshot(), AccumulatorSkipped { filter });
+    // Nested fields start from their `Default` value.
+    assert_eq!(restored.inner, Inner { value: 3, hits: 0 });
+    assert_eq!(restored.total, 10);
+    assert_eq!(restored.counter, -4);
+    assert!((restored.filter)(&-1));
+    assert!(restored.cache.is_empty());
+}
+
+#[test]
+fn test_from_patch_takes_merge_fields_from_the_patch() {
+    let source = Limits {
+        low: 5,
+        mask: 0b1010,
+        peak: -3,
+    };
+    let restored: Limits = source.snapshot().into();
+    assert_eq!(
+        restored,
+        Limits {
+            low: 5,
+            mask: 0b1010,
+            peak: -3,
+        }
+    );
+}
+
+#[test]
+fn test_from_patch_for_tuple_and_unit_structs() {
+    let source = Tuple(1, "text".to_owned(), 'x');
+    let restored = Tuple::from_patch(source.snapshot(), TupleSkipped(2, 'y'));
+    assert_eq!(restored, Tuple(2, "text".to_owned(), 'y'));
+
+    assert_eq!(Unit::from_patch(Unit.snapshot(), UnitSkipped), Unit);
+}
+
+#[test]
+fn test_from_conversion_with_defaultable_skipped_fields() {
+    let restored: Inner = Inner { value: 5, hits: 0 }.snapshot().into();
+    assert_eq!(restored, Inner { value: 5, hits: 7 });
+
+    let restored = Labeled::from(LabelPatch {
+        label: "a".to_owned(),
+    });
+    assert_eq!(
+        restored,
+        Labeled {
+            label: "a".to_owned(),
+            width: 0,
+        }
+    );
+}
+
+#[test]
+fn test_from_patch_for_transparent_struct() {
+    let patch = Wrapper(Inner { value: 4, hits: 0 }, 9).snapshot();
+    let restored = Wrapper::<Inner>::from_patch(patch, WrapperSkipped);
+    assert_eq!(restored, Wrapper(Inner { value: 4, hits: 0 }, 1));
+}
+
+#[test]
+fn test_from_patch_for_remote_type() {
+    let source = foreign::Point { x: 3, y: 4 };
+    let restored = PointDef::from_patch(PointDef::snapshot(&source), PointDefSkipped { y: -1 });
+    assert_eq!((restored.x, restored.y), (3, -1));
+}
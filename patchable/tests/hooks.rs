@@ -0,0 +1,137 @@
+use patchable::{FromPatch, Patch, PatchError, Patchable, Snapshot, TryPatch};
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch, Snapshot, FromPatch)]
+#[patchable(after_patch = Self::recompute)]
+struct Ledger {
+    entries: Vec<i64>,
+    #[patchable(skip, default = 0)]
+    total: i64,
+}
+
+impl Ledger {
+    fn recompute(&mut self) {
+        self.total = self.entries.iter().sum();
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch, Snapshot)]
+#[patchable(after_patch = Self::sort, invariant = Self::check)]
+struct Range {
+    bounds: (i32, i32),
+    #[patchable(skip)]
+    sorted: Vec<i32>,
+}
+
+impl Range {
+    fn sort(&mut self) {
+        self.sorted = vec![self.bounds.0, self.bounds.1];
+        self.sorted.sort_unstable();
+    }
+
+    fn check(&self) -> Result<(), &'static str> {
+        if self.bounds.0 <= self.bounds.1 {
+            Ok(())
+        } else {
+            Err("start must not exceed end")
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch, Snapshot)]
+#[patchable(invariant = Self::check, rollback)]
+struct Window<T> {
+    #[patchable]
+    range: T,
+    width: u32,
+    #[patchable(delta = "checked")]
+    offset: u8,
+}
+
+impl<T> Window<T> {
+    fn check(&self) -> Result<(), &'static str> {
+        if self.width > 0 {
+            Ok(())
+        } else {
+            Err("width must be positive")
+        }
+    }
+}
+
+fn range(start: i32, end: i32) -> Range {
+    Range {
+        bounds: (start, end),
+        sorted: vec![],
+    }
+}
+
+#[test]
+fn test_after_patch_recomputes_caches() {
+    let mut ledger = Ledger {
+        entries: vec![],
+        total: 0,
+    };
+    let source = Ledger {
+        entries: vec![3, 4, -2],
+        total: 0,
+    };
+
+    ledger.patch(source.snapshot());
+    assert_eq!(ledger.total, 5);
+
+    let restored: Ledger = source.snapshot().into();
+    assert_eq!(restored.total, 5);
+}
+
+#[test]
+fn test_invariant_failure_is_an_error() {
+    let mut target = range(0, 1);
+    target.try_patch(range(2, 5).snapshot()).unwrap();
+    assert_eq!(target.sorted, vec![2, 5]);
+
+    let error = target.try_patch(range(9, 1).snapshot()).unwrap_err();
+    assert_eq!(
+        error,
+        PatchError::Invariant {
+            message: "start must not exceed end"
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "the patched value is invalid: start must not exceed end"
+    );
+    // Without rollback, the fields stay patched.
+    assert_eq!(target.bounds, (9, 1));
+    assert_eq!(target.sorted, vec![1, 9]);
+}
+
+#[test]
+fn test_rollback_restores_previous_state() {
+    let mut window = Window {
+        range: range(0, 1),
+        width: 4,
+        offset: 0,
+    };
+    let original = window.clone();
+
+    let mut invalid = window.clone();
+    invalid.width = 0;
+    invalid.range = range(3, 4);
+    assert!(window.try_patch(invalid.snapshot()).is_err());
+    assert_eq!(window, original);
+
+    // Errors of nested fields are rolled back too.
+    let mut nested_invalid = window.clone();
+    nested_invalid.range = range(5, 0);
+    nested_invalid.width = 8;
+    let error = window.try_patch(nested_invalid.snapshot()).unwrap_err();
+    assert!(matches!(error, PatchError::Invariant { .. }));
+    assert_eq!(window, original);
+
+    let mut valid = window.clone();
+    valid.range = range(1, 2);
+    valid.width = 8;
+    valid.offset = 255;
+    window.try_patch(valid.snapshot()).unwrap();
+    assert_eq!(window.range.sorted, vec![1, 2]);
+    assert_eq!((window.width, window.offset), (8, 255));
+}
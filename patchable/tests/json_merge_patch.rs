@@ -0,0 +1,193 @@
+use core::error::Error;
+
+use patchable::{JsonMergePatch, MergePatchError, Patch, Patchable, apply_merge_patch};
+use serde_json::json;
+
+#[derive(Clone, Debug, Default, PartialEq, Patchable, Patch, JsonMergePatch)]
+struct Address {
+    city: String,
+    zip: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch, JsonMergePatch)]
+#[patchable(after_patch = Customer::touch)]
+struct Customer<A, N> {
+    name: N,
+    #[patchable]
+    address: A,
+    #[patchable(skip)]
+    revision: u32,
+    #[patchable(delta)]
+    balance: i64,
+    #[patchable(merge = "add")]
+    r#type: u32,
+}
+
+impl<A, N> Customer<A, N> {
+    fn touch(&mut self) {
+        self.revision += 1;
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch, JsonMergePatch)]
+struct Pair(u8, #[patchable(skip)] bool, char);
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch, JsonMergePatch)]
+struct Unit;
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch, JsonMergePatch)]
+#[patchable(transparent)]
+struct Shipping<A>(#[patchable] A);
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch, JsonMergePatch)]
+#[patchable(transparent)]
+struct Label(String);
+
+fn customer() -> Customer<Address, String> {
+    Customer {
+        name: "Ada".to_owned(),
+        address: Address {
+            city: "London".to_owned(),
+            zip: Some("NW1".to_owned()),
+        },
+        revision: 0,
+        balance: 10,
+        r#type: 1,
+    }
+}
+
+#[test]
+fn test_merge_patch_applies_present_fields() {
+    let mut value = customer();
+    apply_merge_patch(&mut value, &json!({ "name": "Grace", "type": 2 })).unwrap();
+
+    assert_eq!(value.name, "Grace");
+    assert_eq!(value.r#type, 3);
+    assert_eq!(value.address, customer().address);
+    assert_eq!(value.balance, 10);
+}
+
+#[test]
+fn test_merge_patch_recurses_into_nested_fields() {
+    let mut value = customer();
+    apply_merge_patch(&mut value, &json!({ "address": { "city": "Paris" } })).unwrap();
+
+    assert_eq!(
+        value.address,
+        Address {
+            city: "Paris".to_owned(),
+            zip: Some("NW1".to_owned()),
+        }
+    );
+}
+
+#[test]
+fn test_merge_patch_null_clears_option_fields() {
+    let mut value = customer();
+    apply_merge_patch(&mut value, &json!({ "address": { "zip": null } })).unwrap();
+
+    assert_eq!(value.address.zip, None);
+}
+
+#[test]
+fn test_merge_patch_applies_delta_values() {
+    let mut value = customer();
+    apply_merge_patch(&mut value, &json!({ "balance": -4 })).unwrap();
+
+    assert_eq!(value.balance, 6);
+}
+
+#[test]
+fn test_merge_patch_rejects_unknown_and_skipped_keys() {
+    let mut value = customer();
+
+    let error = apply_merge_patch(&mut value, &json!({ "name": "Bob", "age": 36 })).unwrap_err();
+    assert!(matches!(&error, MergePatchError::UnknownField { path } if path == "/age"));
+
+    let error = apply_merge_patch(&mut value, &json!({ "revision": 3 })).unwrap_err();
+    assert_eq!(error.path(), "/revision");
+
+    let error =
+        apply_merge_patch(&mut value, &json!({ "address": { "country": "FR" } })).unwrap_err();
+    assert_eq!(error.path(), "/address/country");
+    assert_eq!(error.to_string(), "unknown field at `/address/country`");
+
+    let error = apply_merge_patch(&mut value, &json!({ "a/b~c": 1 })).unwrap_err();
+    assert_eq!(error.path(), "/a~1b~0c");
+}
+
+#[test]
+fn test_merge_patch_rejects_invalid_values() {
+    let mut value = customer();
+
+    let error = apply_merge_patch(&mut value, &json!({ "name": null })).unwrap_err();
+    assert!(matches!(error, MergePatchError::InvalidValue { .. }));
+    assert_eq!(error.path(), "/name");
+    assert!(error.source().is_some());
+
+    let error = apply_merge_patch(&mut value, &json!({ "address": "Paris" })).unwrap_err();
+    assert!(matches!(&error, MergePatchError::NotAnObject { path } if path == "/address"));
+
+    let error = apply_merge_patch(&mut value, &json!([1, 2])).unwrap_err();
+    assert_eq!(error.path(), "");
+    assert_eq!(error.to_string(), "expected a JSON object at ``");
+}
+
+#[test]
+fn test_merge_patch_leaves_value_unchanged_on_error() {
+    let mut value = customer();
+    let patch = json!({
+        "name": "Grace",
+        "address": { "city": "Paris", "zip": 75 },
+        "balance": 5,
+    });
+
+    let error = apply_merge_patch(&mut value, &patch).unwrap_err();
+    assert_eq!(error.path(), "/address/zip");
+    assert_eq!(value, customer());
+}
+
+#[test]
+fn test_merge_patch_calls_after_patch_once() {
+    let mut value = customer();
+    apply_merge_patch(&mut value, &json!({ "name": "Grace" })).unwrap();
+    assert_eq!(value.revision, 1);
+
+    apply_merge_patch(&mut value, &json!({ "name": 1 })).unwrap_err();
+    assert_eq!(value.revision, 1);
+}
+
+#[test]
+fn test_merge_patch_tuple_struct_uses_field_indexes() {
+    let mut pair = Pair(1, true, 'a');
+    apply_merge_patch(&mut pair, &json!({ "2": "z" })).unwrap();
+    assert_eq!(pair, Pair(1, true, 'z'));
+
+    let error = apply_merge_patch(&mut pair, &json!({ "1": false })).unwrap_err();
+    assert_eq!(error.path(), "/1");
+}
+
+#[test]
+fn test_merge_patch_unit_struct_accepts_empty_object() {
+    apply_merge_patch(&mut Unit, &json!({})).unwrap();
+    assert_eq!(
+        apply_merge_patch(&mut Unit, &json!({ "a": 1 }))
+            .unwrap_err()
+            .path(),
+        "/a"
+    );
+}
+
+#[test]
+fn test_merge_patch_transparent_struct_uses_field_document() {
+    let mut shipping = Shipping(Address::default());
+    apply_merge_patch(&mut shipping, &json!({ "city": "Oslo" })).unwrap();
+    assert_eq!(shipping.0.city, "Oslo");
+
+    let mut label = Label("old".to_owned());
+    apply_merge_patch(&mut label, &json!("new")).unwrap();
+    assert_eq!(label.0, "new");
+
+    let error = apply_merge_patch(&mut label, &json!(1)).unwrap_err();
+    assert_eq!(error.path(), "");
+}
@@ -0,0 +1,367 @@
+use std::collections::BTreeMap;
+
+use patchable::json_patch::JsonPatchErrorKind;
+use patchable::{
+    JsonPatch, JsonPatchError, JsonPatched, Patch, PatchOperation, Patchable, TryPatch,
+    apply_json_patch,
+};
+use serde_json::{Value, json};
+
+#[derive(Clone, Debug, Default, PartialEq, Patchable, Patch, JsonPatch)]
+struct Address {
+    city: String,
+    zip: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch, JsonPatch)]
+#[patchable(after_patch = Customer::touch)]
+struct Customer<A, T> {
+    name: String,
+    #[patchable]
+    address: A,
+    tags: Vec<T>,
+    limits: BTreeMap<String, u32>,
+    #[patchable(skip)]
+    revision: u32,
+    #[patchable(delta)]
+    balance: i64,
+}
+
+impl<A, T> Customer<A, T> {
+    fn touch(&mut self) {
+        self.revision += 1;
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch, JsonPatch)]
+struct Pair(u8, #[patchable(skip)] bool, char);
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch, JsonPatch)]
+struct Unit;
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch, JsonPatch)]
+#[patchable(transparent)]
+struct Tags(Vec<String>);
+
+fn customer() -> Customer<Address, String> {
+    Customer {
+        name: "Ada".to_owned(),
+        address: Address {
+            city: "London".to_owned(),
+            zip: Some("NW1".to_owned()),
+        },
+        tags: vec!["admin".to_owned()],
+        limits: BTreeMap::from([("daily".to_owned(), 10)]),
+        revision: 0,
+        balance: 10,
+    }
+}
+
+fn operations(document: Value) -> Vec<PatchOperation> {
+    serde_json::from_value(document).unwrap()
+}
+
+fn apply<T: JsonPatch + Clone>(target: &mut T, document: Value) -> Result<(), JsonPatchError> {
+    apply_json_patch(target, &operations(document))
+}
+
+#[test]
+fn test_json_patch_replaces_fields() {
+    let mut value = customer();
+    apply(
+        &mut value,
+        json!([
+            { "op": "replace", "path": "/name", "value": "Grace" },
+            { "op": "add", "path": "/address/city", "value": "Paris" },
+            { "op": "replace", "path": "/balance", "value": 3 },
+        ]),
+    )
+    .unwrap();
+
+    assert_eq!(value.name, "Grace");
+    assert_eq!(value.address.city, "Paris");
+    assert_eq!(value.balance, 3);
+}
+
+#[test]
+fn test_json_patch_edits_collections() {
+    let mut value = customer();
+    apply(
+        &mut value,
+        json!([
+            { "op": "add", "path": "/tags/-", "value": "ops" },
+            { "op": "add", "path": "/tags/0", "value": "first" },
+            { "op": "remove", "path": "/tags/1" },
+            { "op": "add", "path": "/limits/monthly", "value": 100 },
+            { "op": "replace", "path": "/limits/daily", "value": 20 },
+        ]),
+    )
+    .unwrap();
+
+    assert_eq!(value.tags, ["first", "ops"]);
+    assert_eq!(
+        value.limits,
+        BTreeMap::from([("daily".to_owned(), 20), ("monthly".to_owned(), 100)])
+    );
+}
+
+#[test]
+fn test_json_patch_moves_and_copies_values() {
+    let mut value = customer();
+    apply(
+        &mut value,
+        json!([
+            { "op": "copy", "from": "/address/city", "path": "/tags/-" },
+            { "op": "move", "from": "/tags/0", "path": "/name" },
+        ]),
+    )
+    .unwrap();
+
+    assert_eq!(value.name, "admin");
+    assert_eq!(value.tags, ["London"]);
+}
+
+#[test]
+fn test_json_patch_remove_clears_option_fields() {
+    let mut value = customer();
+    apply(
+        &mut value,
+        json!([{ "op": "remove", "path": "/address/zip" }]),
+    )
+    .unwrap();
+    assert_eq!(value.address.zip, None);
+
+    let error = apply(&mut value, json!([{ "op": "remove", "path": "/name" }])).unwrap_err();
+    assert!(matches!(error.kind(), JsonPatchErrorKind::Unsupported(_)));
+    assert_eq!(error.path(), "/name");
+
+    let error = apply(&mut value, json!([{ "op": "remove", "path": "/address" }])).unwrap_err();
+    assert!(matches!(error.kind(), JsonPatchErrorKind::Unsupported(_)));
+}
+
+#[test]
+fn test_json_patch_test_operations_are_preconditions() {
+    let mut value = customer();
+    let document = json!([
+        { "op": "test", "path": "/address", "value": { "city": "London", "zip": "NW1" } },
+        { "op": "replace", "path": "/name", "value": "Grace" },
+        { "op": "test", "path": "/name", "value": "Grace" },
+    ]);
+    apply(&mut value, document.clone()).unwrap();
+    assert_eq!(value.name, "Grace");
+
+    value.address.city = "Paris".to_owned();
+    let before = value.clone();
+    let error = apply(&mut value, document).unwrap_err();
+    assert!(matches!(error.kind(), JsonPatchErrorKind::TestFailed));
+    assert_eq!((error.operation(), error.path()), (0, "/address"));
+    assert_eq!(value, before);
+}
+
+#[test]
+fn test_json_patch_test_compares_numbers_by_value() {
+    let mut value = customer();
+    apply(
+        &mut value,
+        json!([
+            { "op": "test", "path": "/balance", "value": 10.0 },
+            { "op": "test", "path": "/limits", "value": { "daily": 1e1 } },
+            { "op": "replace", "path": "/name", "value": "Grace" },
+        ]),
+    )
+    .unwrap();
+    assert_eq!(value.name, "Grace");
+
+    let error = apply(
+        &mut value,
+        json!([{ "op": "test", "path": "/balance", "value": 10.5 }]),
+    );
+    assert!(matches!(
+        error.unwrap_err().kind(),
+        JsonPatchErrorKind::TestFailed
+    ));
+}
+
+#[test]
+fn test_json_patch_is_atomic() {
+    let mut value = customer();
+    let error = apply(
+        &mut value,
+        json!([
+            { "op": "replace", "path": "/name", "value": "Grace" },
+            { "op": "add", "path": "/tags/-", "value": "ops" },
+            { "op": "replace", "path": "/balance", "value": "many" },
+        ]),
+    )
+    .unwrap_err();
+
+    assert!(matches!(error.kind(), JsonPatchErrorKind::InvalidValue(_)));
+    assert_eq!((error.operation(), error.path()), (2, "/balance"));
+    assert_eq!(value, customer());
+}
+
+#[test]
+fn test_json_patch_reports_path_errors() {
+    let mut value = customer();
+
+    let error = apply(
+        &mut value,
+        json!([{ "op": "replace", "path": "/age", "value": 1 }]),
+    );
+    assert!(matches!(
+        error.unwrap_err().kind(),
+        JsonPatchErrorKind::NotFound
+    ));
+
+    let error = apply(
+        &mut value,
+        json!([{ "op": "test", "path": "/revision", "value": 0 }]),
+    );
+    assert!(matches!(
+        error.unwrap_err().kind(),
+        JsonPatchErrorKind::NotFound
+    ));
+
+    let error = apply(&mut value, json!([{ "op": "remove", "path": "/tags/01" }]));
+    assert!(matches!(
+        error.unwrap_err().kind(),
+        JsonPatchErrorKind::NotFound
+    ));
+
+    let error = apply(
+        &mut value,
+        json!([{ "op": "replace", "path": "name", "value": 1 }]),
+    );
+    assert!(matches!(
+        error.unwrap_err().kind(),
+        JsonPatchErrorKind::InvalidPointer
+    ));
+
+    let error = apply(
+        &mut value,
+        json!([{ "op": "move", "from": "/address", "path": "/address/city" }]),
+    )
+    .unwrap_err();
+    assert!(matches!(error.kind(), JsonPatchErrorKind::Unsupported(_)));
+
+    let error = apply(
+        &mut value,
+        json!([{ "op": "copy", "from": "/nowhere", "path": "/name" }]),
+    )
+    .unwrap_err();
+    assert_eq!(error.path(), "/nowhere");
+    assert_eq!(
+        error.to_string(),
+        "operation 0 failed at `/nowhere`: no value at this path"
+    );
+}
+
+#[test]
+fn test_json_patch_replaces_whole_structs() {
+    let mut value = customer();
+    apply(
+        &mut value,
+        json!([{ "op": "replace", "path": "/address", "value": { "city": "Oslo", "zip": null } }]),
+    )
+    .unwrap();
+    assert_eq!(
+        value.address,
+        Address {
+            city: "Oslo".to_owned(),
+            zip: None,
+        }
+    );
+
+    let error = apply(
+        &mut value,
+        json!([{ "op": "replace", "path": "/address", "value": { "city": "Rome" } }]),
+    )
+    .unwrap_err();
+    assert!(matches!(error.kind(), JsonPatchErrorKind::InvalidValue(_)));
+
+    let mut address = Address::default();
+    apply(
+        &mut address,
+        json!([{ "op": "replace", "path": "", "value": { "city": "Oslo", "zip": "0150" } }]),
+    )
+    .unwrap();
+    assert_eq!(address.zip.as_deref(), Some("0150"));
+}
+
+#[test]
+fn test_json_patch_calls_after_patch_per_operation() {
+    let mut value = customer();
+    apply(
+        &mut value,
+        json!([
+            { "op": "replace", "path": "/name", "value": "Grace" },
+            { "op": "test", "path": "/name", "value": "Grace" },
+            { "op": "remove", "path": "/tags/0" },
+        ]),
+    )
+    .unwrap();
+    assert_eq!(value.revision, 2);
+}
+
+#[test]
+fn test_json_patch_tuple_unit_and_transparent_structs() {
+    let mut pair = Pair(1, true, 'a');
+    apply(
+        &mut pair,
+        json!([{ "op": "replace", "path": "/2", "value": "z" }]),
+    )
+    .unwrap();
+    assert_eq!(pair, Pair(1, true, 'z'));
+    let error = apply(
+        &mut pair,
+        json!([{ "op": "replace", "path": "/1", "value": false }]),
+    );
+    assert!(matches!(
+        error.unwrap_err().kind(),
+        JsonPatchErrorKind::NotFound
+    ));
+
+    apply(
+        &mut Unit,
+        json!([{ "op": "test", "path": "", "value": {} }]),
+    )
+    .unwrap();
+
+    let mut tags = Tags(vec!["a".to_owned()]);
+    apply(
+        &mut tags,
+        json!([{ "op": "add", "path": "/-", "value": "b" }]),
+    )
+    .unwrap();
+    assert_eq!(tags.0, ["a", "b"]);
+}
+
+#[test]
+fn test_json_patched_implements_try_patch() {
+    let mut value = JsonPatched(customer());
+    value
+        .try_patch(operations(
+            json!([{ "op": "replace", "path": "/name", "value": "Grace" }]),
+        ))
+        .unwrap();
+    assert_eq!(value.0.name, "Grace");
+
+    let error = value
+        .try_patch(operations(
+            json!([{ "op": "test", "path": "/name", "value": "Ada" }]),
+        ))
+        .unwrap_err();
+    assert!(matches!(error.kind(), JsonPatchErrorKind::TestFailed));
+}
+
+#[test]
+fn test_patch_operation_serializes_to_rfc_form() {
+    let operation = PatchOperation::Move {
+        from: "/a".to_owned(),
+        path: "/b".to_owned(),
+    };
+    assert_eq!(
+        serde_json::to_value(&operation).unwrap(),
+        json!({ "op": "move", "from": "/a", "path": "/b" })
+    );
+}
@@ -0,0 +1,208 @@
+use std::collections::BTreeMap;
+
+use patchable::layered::{ENV_LAYER, Layered};
+use patchable::{Patch, Patchable};
+use serde::Deserialize;
+
+#[derive(Clone, Debug, Default, PartialEq, Patchable, Patch)]
+#[patchable(partial)]
+struct Limits {
+    max_connections: u32,
+    timeout: Option<u64>,
+    enabled: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+enum Mode {
+    #[default]
+    Fast,
+    Safe,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Patchable, Patch)]
+#[patchable(partial)]
+struct Config<L> {
+    name: String,
+    #[patchable]
+    limits: L,
+    tags: Vec<String>,
+    mode: Mode,
+    #[patchable(skip)]
+    revision: u32,
+}
+
+type LimitsPatch = <Limits as Patchable>::Patch;
+type ConfigPatch<L> = <Config<L> as Patchable>::Patch;
+
+const TOML: &str = r#"
+name = "api"
+tags = ["edge"]
+
+[limits]
+max_connections = 16
+timeout = 30
+"#;
+
+fn toml_layer() -> toml::Value {
+    toml::from_str(TOML).unwrap()
+}
+
+#[test]
+fn test_layers_apply_in_order() {
+    let env = [
+        ("APP_LIMITS__MAX_CONNECTIONS", "128"),
+        ("APP_MODE", "Safe"),
+        ("OTHER_NAME", "ignored"),
+    ];
+    let cli = ConfigPatch {
+        name: Some("gateway".to_owned()),
+        ..Default::default()
+    };
+
+    let (config, provenance) = Layered::<Config<Limits>>::new()
+        .source("config.toml", toml_layer())
+        .unwrap()
+        .env("APP", env)
+        .unwrap()
+        .layer("cli", cli)
+        .build();
+
+    assert_eq!(
+        config,
+        Config {
+            name: "gateway".to_owned(),
+            limits: Limits {
+                max_connections: 128,
+                timeout: Some(30),
+                enabled: false,
+            },
+            tags: vec!["edge".to_owned()],
+            mode: Mode::Safe,
+            revision: 0,
+        }
+    );
+    assert_eq!(provenance.layer_of("name"), Some("cli"));
+    assert_eq!(
+        provenance.layer_of("limits.max_connections"),
+        Some(ENV_LAYER)
+    );
+    assert_eq!(provenance.layer_of("limits.timeout"), Some("config.toml"));
+    assert_eq!(provenance.layer_of("limits.enabled"), None);
+    assert_eq!(provenance.layer_of("limits"), None);
+    assert_eq!(
+        provenance.iter().collect::<Vec<_>>(),
+        [
+            ("limits.max_connections", "env"),
+            ("limits.timeout", "config.toml"),
+            ("mode", "env"),
+            ("name", "cli"),
+            ("tags", "config.toml"),
+        ]
+    );
+}
+
+#[test]
+fn test_json_and_toml_sources_are_equivalent() {
+    let json = r#"{ "name": "api", "tags": ["edge"],
+        "limits": { "max_connections": 16, "timeout": 30 } }"#;
+    let (from_json, json_provenance) = Layered::<Config<Limits>>::new()
+        .source("config", &mut serde_json::Deserializer::from_str(json))
+        .unwrap()
+        .build();
+    let (from_toml, toml_provenance) = Layered::<Config<Limits>>::new()
+        .source("config", toml_layer())
+        .unwrap()
+        .build();
+
+    assert_eq!(from_json, from_toml);
+    assert_eq!(json_provenance, toml_provenance);
+}
+
+#[test]
+fn test_env_parses_values() {
+    let env = BTreeMap::from([
+        ("APP_NAME".to_owned(), "api".to_owned()),
+        ("APP_TAGS".to_owned(), "a, b,c".to_owned()),
+        ("APP_LIMITS__TIMEOUT".to_owned(), "5".to_owned()),
+        ("APP_LIMITS__ENABLED".to_owned(), "true".to_owned()),
+        ("APP_LIMITS__UNKNOWN".to_owned(), "1".to_owned()),
+        ("APPLICATION".to_owned(), "x".to_owned()),
+    ]);
+    let (config, _) = Layered::<Config<Limits>>::new()
+        .env("APP", env)
+        .unwrap()
+        .build();
+
+    assert_eq!(config.name, "api");
+    assert_eq!(config.tags, ["a", "b", "c"]);
+    assert_eq!(config.limits.timeout, Some(5));
+    assert!(config.limits.enabled);
+}
+
+#[test]
+fn test_env_errors_name_the_variable() {
+    let error = Layered::<Config<Limits>>::new()
+        .env("APP", [("APP_LIMITS__MAX_CONNECTIONS", "many")])
+        .unwrap_err();
+    assert_eq!(error.layer(), "env");
+    assert!(
+        error.message().contains("APP_LIMITS__MAX_CONNECTIONS"),
+        "{error}"
+    );
+    assert!(error.to_string().starts_with("invalid layer `env`: "));
+
+    let error = Layered::<Config<Limits>>::new()
+        .env("APP", [("APP_MODE", "Slow")])
+        .unwrap_err();
+    assert!(error.message().contains("APP_MODE"), "{error}");
+
+    let error = Layered::<Config<Limits>>::new()
+        .env("APP", [("APP_LIMITS", "1"), ("APP_LIMITS__TIMEOUT", "1")])
+        .unwrap_err();
+    assert!(error.message().contains("`APP_LIMITS`"), "{error}");
+
+    let error = Layered::<Config<Limits>>::new()
+        .env("APP", [("APP_LIMITS____TIMEOUT", "1")])
+        .unwrap_err();
+    assert!(error.message().contains("APP_LIMITS____TIMEOUT"), "{error}");
+}
+
+#[test]
+fn test_source_errors_name_the_layer() {
+    let error = Layered::<Config<Limits>>::new()
+        .source(
+            "broken.json",
+            &mut serde_json::Deserializer::from_str(r#"{ "limits": 1 }"#),
+        )
+        .unwrap_err();
+    assert_eq!(error.layer(), "broken.json");
+}
+
+#[test]
+fn test_build_on_keeps_base_values() {
+    let base = Config {
+        name: "base".to_owned(),
+        limits: Limits {
+            max_connections: 1,
+            timeout: None,
+            enabled: true,
+        },
+        tags: Vec::new(),
+        mode: Mode::Fast,
+        revision: 7,
+    };
+    let overrides = ConfigPatch {
+        limits: Some(LimitsPatch {
+            timeout: Some(Some(9)),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    let (config, provenance) = Layered::new().layer("overrides", overrides).build_on(base);
+    assert_eq!(config.name, "base");
+    assert_eq!(config.limits.timeout, Some(9));
+    assert!(config.limits.enabled);
+    assert_eq!(config.revision, 7);
+    assert_eq!(provenance.iter().count(), 1);
+}
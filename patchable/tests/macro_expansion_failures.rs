@@ -7,4 +7,29 @@ fn derive_macro_reports_expected_failures() {
     tests.compile_fail("tests/ui/derive_fail_patchable_unknown_parameter.rs");
     tests.compile_fail("tests/ui/derive_fail_patchable_skip_with_unknown_parameter.rs");
     tests.compile_fail("tests/ui/derive_fail_patchable_name_value_parameter.rs");
+    tests.compile_fail("tests/ui/derive_fail_patchable_unknown_container_parameter.rs");
+    tests.compile_fail("tests/ui/model_fail_unknown_parameter.rs");
+    tests.compile_fail("tests/ui/derive_fail_remote_missing_field.rs");
+    tests.compile_fail("tests/ui/derive_fail_remote_unknown_field.rs");
+    tests.compile_fail("tests/ui/derive_fail_custom_patch_missing_field.rs");
+    tests.compile_fail("tests/ui/derive_fail_custom_patch_extra_field.rs");
+    tests.compile_fail("tests/ui/derive_fail_custom_patch_field_type.rs");
+    tests.compile_fail("tests/ui/derive_fail_transparent_multiple_fields.rs");
+    tests.compile_fail("tests/ui/derive_fail_with_and_skip.rs");
+    tests.compile_fail("tests/ui/derive_fail_merge_unknown_strategy.rs");
+    tests.compile_fail("tests/ui/derive_fail_merge_and_skip.rs");
+    tests.compile_fail("tests/ui/derive_fail_delta_unknown_mode.rs");
+    tests.compile_fail("tests/ui/derive_fail_delta_and_merge.rs");
+    tests.compile_fail("tests/ui/derive_fail_default_without_skip.rs");
+    tests.compile_fail("tests/ui/derive_fail_rehydrate_without_context.rs");
+    tests.compile_fail("tests/ui/derive_fail_rollback_without_invariant.rs");
+    tests.compile_fail("tests/ui/derive_fail_report_patch_checked.rs");
+    tests.compile_fail("tests/ui/derive_fail_partial_transparent.rs");
+    tests.compile_fail("tests/ui/derive_fail_masked_patch_partial.rs");
+    #[cfg(feature = "clap")]
+    tests.compile_fail("tests/ui/derive_fail_patch_args_not_partial.rs");
+    #[cfg(feature = "clap")]
+    tests.compile_fail("tests/ui/derive_fail_patch_args_tuple_struct.rs");
+    #[cfg(feature = "alloc")]
+    tests.compile_fail("tests/ui/derive_fail_merge3_transparent.rs");
 }
@@ -0,0 +1,99 @@
+use patchable::{FieldMask, MaskedPatch, Patch, Patchable, Snapshot};
+
+#[derive(Clone, Debug, Default, PartialEq, Patchable, Patch, Snapshot, MaskedPatch)]
+struct Inner {
+    value: i32,
+}
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch, Snapshot, MaskedPatch)]
+struct Account<T> {
+    display_name: String,
+    #[patchable]
+    settings: T,
+    #[patchable(skip)]
+    session: u64,
+    #[patchable(merge = "add")]
+    r#type: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch, Snapshot, MaskedPatch)]
+struct Pair(u8, #[patchable(skip)] bool, char);
+
+fn account(name: &str, value: i32, kind: u32) -> Account<Inner> {
+    Account {
+        display_name: name.to_owned(),
+        settings: Inner { value },
+        session: 1,
+        r#type: kind,
+    }
+}
+
+#[test]
+fn test_field_enum_lists_non_skipped_fields() {
+    assert_eq!(
+        AccountField::ALL,
+        [
+            AccountField::DisplayName,
+            AccountField::Settings,
+            AccountField::Type
+        ]
+    );
+    assert_eq!(AccountField::DisplayName.name(), "display_name");
+    assert_eq!(AccountField::Type.name(), "type");
+    assert_eq!(PairField::ALL, [PairField::Field0, PairField::Field2]);
+    assert_eq!(PairField::Field2.name(), "2");
+}
+
+#[test]
+fn test_field_mask_set_operations() {
+    let name = AccountFieldMask::of(AccountField::DisplayName);
+    let settings: AccountFieldMask = AccountField::Settings.into();
+
+    assert!(AccountFieldMask::NONE.is_empty());
+    assert_eq!(AccountFieldMask::ALL.len(), 3);
+    assert_eq!(AccountFieldMask::default(), AccountFieldMask::NONE);
+    assert_eq!(!AccountFieldMask::NONE, AccountFieldMask::ALL);
+
+    let both = name | settings;
+    assert!(both.contains(AccountField::DisplayName));
+    assert!(!both.contains(AccountField::Type));
+    assert_eq!(both & settings, settings);
+    assert_eq!(both - settings, name);
+    assert_eq!(!both, AccountField::Type.into());
+    assert_eq!(
+        both.without(AccountField::DisplayName)
+            .with(AccountField::Type),
+        [AccountField::Settings, AccountField::Type]
+            .into_iter()
+            .collect()
+    );
+    assert_eq!(
+        both.iter().collect::<Vec<_>>(),
+        [AccountField::DisplayName, AccountField::Settings]
+    );
+    assert_eq!(format!("{both:?}"), "{DisplayName, Settings}");
+
+    let mut mask = AccountFieldMask::NONE;
+    mask.insert(AccountField::Type);
+    mask |= name;
+    mask.remove(AccountField::DisplayName);
+    assert_eq!(mask, AccountField::Type.into());
+    assert_eq!(<AccountFieldMask as FieldMask>::ALL, AccountFieldMask::ALL);
+}
+
+#[test]
+fn test_patch_masked_applies_selected_fields() {
+    let mut target = account("old", 1, 2);
+    let source = account("new", 5, 3);
+
+    let mask = AccountFieldMask::ALL.without(AccountField::DisplayName);
+    target.patch_masked(source.snapshot(), mask);
+    assert_eq!(target, account("old", 5, 5));
+
+    target.patch_masked(source.snapshot(), AccountFieldMask::NONE);
+    assert_eq!(target, account("old", 5, 5));
+
+    let mut pair = Pair(1, true, 'a');
+    pair.patch_masked(Pair(2, false, 'b').snapshot(), PairField::Field2.into());
+    assert_eq!(pair, Pair(1, true, 'b'));
+}
@@ -0,0 +1,144 @@
+use std::collections::{BTreeSet, VecDeque};
+
+use patchable::{MergeStrategy, Patch, Patchable, patchable_model};
+
+/// Keeps the longest of the current and the incoming string.
+struct Longest;
+
+impl MergeStrategy<String> for Longest {
+    fn merge(current: &mut String, incoming: String) {
+        if incoming.len() > current.len() {
+            *current = incoming;
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch)]
+struct Telemetry {
+    #[patchable(merge = "add")]
+    requests: u64,
+    #[patchable(merge = "max")]
+    peak_latency_ms: f64,
+    #[patchable(merge = "min")]
+    lowest_free_bytes: u64,
+    #[patchable(merge = "or")]
+    saw_error: bool,
+    #[patchable(merge = "and")]
+    all_healthy: bool,
+    #[patchable(merge = "extend")]
+    hosts: BTreeSet<String>,
+    #[patchable(merge = "append")]
+    log: Vec<String>,
+    #[patchable(merge = Longest)]
+    longest_path: String,
+    uptime_s: u64,
+}
+
+#[patchable_model]
+#[derive(Clone, Debug, PartialEq)]
+struct Flags(
+    #[patchable(merge = "or")] u8,
+    #[patchable(merge = "append")] VecDeque<u8>,
+    #[patchable(skip)] u8,
+);
+
+fn telemetry() -> Telemetry {
+    Telemetry {
+        requests: 10,
+        peak_latency_ms: 12.5,
+        lowest_free_bytes: 4096,
+        saw_error: false,
+        all_healthy: true,
+        hosts: BTreeSet::from(["a".to_owned()]),
+        log: vec!["boot".to_owned()],
+        longest_path: "/a/b".to_owned(),
+        uptime_s: 60,
+    }
+}
+
+#[test]
+fn test_merge_strategies_combine_with_current_values() {
+    let mut telemetry = telemetry();
+    let patch: <Telemetry as Patchable>::Patch = serde_json::from_str(
+        r#"{
+            "requests": 5,
+            "peak_latency_ms": 30.0,
+            "lowest_free_bytes": 8192,
+            "saw_error": true,
+            "all_healthy": false,
+            "hosts": ["b", "a"],
+            "log": ["ready"],
+            "longest_path": "/c",
+            "uptime_s": 120
+        }"#,
+    )
+    .unwrap();
+
+    telemetry.patch(patch);
+    assert_eq!(
+        telemetry,
+        Telemetry {
+            requests: 15,
+            peak_latency_ms: 30.0,
+            lowest_free_bytes: 4096,
+            saw_error: true,
+            all_healthy: false,
+            hosts: BTreeSet::from(["a".to_owned(), "b".to_owned()]),
+            log: vec!["boot".to_owned(), "ready".to_owned()],
+            longest_path: "/a/b".to_owned(),
+            uptime_s: 120,
+        }
+    );
+}
+
+#[test]
+fn test_merge_strategies_keep_current_values() {
+    let mut telemetry = telemetry();
+    let patch: <Telemetry as Patchable>::Patch = serde_json::from_str(
+        r#"{
+            "requests": 0,
+            "peak_latency_ms": 1.0,
+            "lowest_free_bytes": 1024,
+            "saw_error": false,
+            "all_healthy": true,
+            "hosts": [],
+            "log": [],
+            "longest_path": "/a/b/c",
+            "uptime_s": 60
+        }"#,
+    )
+    .unwrap();
+
+    telemetry.patch(patch);
+    assert_eq!(telemetry.requests, 10);
+    assert_eq!(telemetry.peak_latency_ms, 12.5);
+    assert_eq!(telemetry.lowest_free_bytes, 1024);
+    assert!(!telemetry.saw_error);
+    assert!(telemetry.all_healthy);
+    assert_eq!(telemetry.log, vec!["boot".to_owned()]);
+    assert_eq!(telemetry.longest_path, "/a/b/c");
+}
+
+#[test]
+fn test_merge_strategies_in_tuple_struct() {
+    let mut flags = Flags(0b0001, VecDeque::from([1]), 7);
+    let patch: <Flags as Patchable>::Patch = serde_json::from_str("[4, [2, 3]]").unwrap();
+
+    flags.patch(patch);
+    assert_eq!(flags, Flags(0b0101, VecDeque::from([1, 2, 3]), 7));
+}
+
+#[cfg(feature = "impl_from")]
+#[test]
+fn test_merge_from_builds_increment() {
+    let mut target = telemetry();
+    let source = Telemetry {
+        requests: 1,
+        hosts: BTreeSet::new(),
+        log: vec![],
+        ..telemetry()
+    };
+
+    target.patch(source.into());
+    assert_eq!(target.requests, 11);
+}
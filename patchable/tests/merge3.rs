@@ -0,0 +1,273 @@
+use patchable::{Conflicts, Merge3, Patch, Patchable, Resolution};
+
+#[derive(Clone, Debug, PartialEq, Merge3, Patchable, Patch)]
+struct Limits {
+    max_connections: u32,
+    timeout: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Merge3, Patchable, Patch)]
+struct Service<L, T> {
+    name: String,
+    #[patchable]
+    limits: L,
+    tag: T,
+    #[patchable(merge = "append")]
+    log: Vec<String>,
+    #[patchable(delta)]
+    restarts: i32,
+    #[patchable(skip)]
+    cache: u8,
+}
+
+#[derive(Clone, Debug, PartialEq, Default, Merge3, Patchable, Patch)]
+#[patchable(partial)]
+struct Retry {
+    attempts: u32,
+    backoff_ms: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Default, Merge3, Patchable, Patch)]
+#[patchable(partial)]
+struct Settings<R> {
+    name: String,
+    #[patchable]
+    retry: R,
+    #[patchable(merge = "add")]
+    requests: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Merge3, Patchable, Patch)]
+struct Pair(u8, u8);
+
+type ServicePatch<L, T> = <Service<L, T> as Patchable>::Patch;
+type LimitsPatch = <Limits as Patchable>::Patch;
+type SettingsPatch<R> = <Settings<R> as Patchable>::Patch;
+type RetryPatch = <Retry as Patchable>::Patch;
+type PairPatch = <Pair as Patchable>::Patch;
+
+fn service() -> Service<Limits, char> {
+    Service {
+        name: "api".to_owned(),
+        limits: Limits {
+            max_connections: 16,
+            timeout: 30,
+        },
+        tag: 'a',
+        log: vec![],
+        restarts: 0,
+        cache: 7,
+    }
+}
+
+fn service_patch(name: &str, max_connections: u32, timeout: u64) -> ServicePatch<Limits, char> {
+    ServicePatch::<Limits, char> {
+        name: name.to_owned(),
+        limits: LimitsPatch {
+            max_connections,
+            timeout,
+        },
+        tag: 'a',
+        log: vec![],
+        restarts: 0,
+    }
+}
+
+fn settings() -> Settings<Retry> {
+    Settings {
+        name: "api".to_owned(),
+        retry: Retry {
+            attempts: 3,
+            backoff_ms: 100,
+        },
+        requests: 10,
+    }
+}
+
+#[test]
+fn test_disjoint_changes_merge() {
+    let base = service();
+    let ours = ServicePatch::<Limits, char> {
+        log: vec!["ours".to_owned()],
+        restarts: 1,
+        ..service_patch("gateway", 16, 30)
+    };
+    let theirs = ServicePatch::<Limits, char> {
+        tag: 'b',
+        log: vec!["theirs".to_owned()],
+        restarts: 2,
+        ..service_patch("api", 16, 60)
+    };
+
+    let mut merged = base.clone();
+    merged.patch(base.merge3(&ours, &theirs).unwrap());
+    assert_eq!(
+        merged,
+        Service {
+            name: "gateway".to_owned(),
+            limits: Limits {
+                max_connections: 16,
+                timeout: 60,
+            },
+            tag: 'b',
+            log: vec!["ours".to_owned(), "theirs".to_owned()],
+            restarts: 3,
+            cache: 7,
+        }
+    );
+}
+
+#[test]
+fn test_same_change_on_both_sides_is_not_a_conflict() {
+    let base = service();
+    let ours = service_patch("gateway", 64, 30);
+    let theirs = service_patch("gateway", 64, 30);
+
+    let mut merged = base.clone();
+    merged.patch(base.merge3(&ours, &theirs).unwrap());
+    assert_eq!(merged.name, "gateway");
+    assert_eq!(merged.limits.max_connections, 64);
+}
+
+#[test]
+fn test_conflicts_are_reported_by_path() {
+    let base = service();
+    let ours = service_patch("gateway", 64, 30);
+    let theirs = service_patch("web", 128, 60);
+
+    let Err(conflicts) = base.merge3(&ours, &theirs) else {
+        unreachable!()
+    };
+    assert_eq!(conflicts.paths(), ["name", "limits.max_connections"]);
+    assert_eq!(
+        conflicts.to_string(),
+        "conflicting changes to `name`, `limits.max_connections`"
+    );
+}
+
+#[test]
+fn test_resolution_callback_decides_conflicts() {
+    let base = service();
+    let ours = service_patch("gateway", 64, 30);
+    let theirs = service_patch("web", 128, 60);
+
+    let mut seen = Vec::new();
+    let merged = base
+        .merge3_with(&ours, &theirs, |path| {
+            seen.push(path.to_string());
+            match path.name() {
+                "name" => Some(Resolution::Base),
+                _ => Some(Resolution::Theirs),
+            }
+        })
+        .unwrap();
+    assert_eq!(seen, ["name", "limits.max_connections"]);
+
+    let mut value = base.clone();
+    value.patch(merged);
+    assert_eq!(value.name, "api");
+    assert_eq!(
+        value.limits,
+        Limits {
+            max_connections: 128,
+            timeout: 60,
+        }
+    );
+
+    // Conflicts the callback leaves undecided are still reported.
+    let result = base.merge3_with(&ours, &theirs, |path| {
+        (path.name() == "name").then_some(Resolution::Ours)
+    });
+    assert_eq!(
+        result
+            .err()
+            .map(|conflicts: Conflicts| conflicts.paths().to_vec()),
+        Some(vec!["limits.max_connections".to_owned()])
+    );
+}
+
+#[test]
+fn test_partial_patches_merge_set_fields() {
+    let base = settings();
+    let ours = SettingsPatch::<Retry> {
+        name: Some("gateway".to_owned()),
+        retry: Some(RetryPatch {
+            attempts: Some(5),
+            ..Default::default()
+        }),
+        requests: Some(1),
+    };
+    let theirs = SettingsPatch::<Retry> {
+        retry: Some(RetryPatch {
+            backoff_ms: Some(250),
+            ..Default::default()
+        }),
+        requests: Some(2),
+        ..Default::default()
+    };
+
+    let mut merged = base.clone();
+    merged.patch(base.merge3(&ours, &theirs).unwrap());
+    assert_eq!(
+        merged,
+        Settings {
+            name: "gateway".to_owned(),
+            retry: Retry {
+                attempts: 5,
+                backoff_ms: 250,
+            },
+            requests: 13,
+        }
+    );
+
+    // A nested patch set on one side only is kept as is.
+    let theirs = SettingsPatch::<Retry>::default();
+    let merged = base.merge3(&ours, &theirs).unwrap();
+    assert_eq!(merged.retry.unwrap().attempts, Some(5));
+}
+
+#[test]
+fn test_partial_conflicts_ignore_values_equal_to_the_base() {
+    let base = settings();
+    let ours = SettingsPatch::<Retry> {
+        name: Some("api".to_owned()),
+        retry: Some(RetryPatch {
+            attempts: Some(5),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let theirs = SettingsPatch::<Retry> {
+        name: Some("web".to_owned()),
+        retry: Some(RetryPatch {
+            attempts: Some(7),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    let Err(conflicts) = base.merge3(&ours, &theirs) else {
+        unreachable!()
+    };
+    assert_eq!(conflicts.paths(), ["retry.attempts"]);
+
+    let merged = base
+        .merge3_with(&ours, &theirs, |_| Some(Resolution::Base))
+        .unwrap();
+    let mut value = base.clone();
+    value.patch(merged);
+    assert_eq!(value.name, "web");
+    assert_eq!(value.retry.attempts, 3);
+}
+
+#[test]
+fn test_tuple_fields_are_named_by_index() {
+    let base = Pair(1, 2);
+    let ours = PairPatch { 0: 3, 1: 4 };
+    let theirs = PairPatch { 0: 5, 1: 2 };
+
+    let Err(conflicts) = base.merge3(&ours, &theirs) else {
+        unreachable!()
+    };
+    assert_eq!(conflicts.paths(), ["0"]);
+}
@@ -0,0 +1,268 @@
+use patchable::partial::FieldPath;
+use patchable::{ComposePatch, PartialPatch, Patch, Patchable, ReportPatch, Snapshot, TryPatch};
+
+#[derive(Clone, Debug, Default, PartialEq, Patchable, Patch, Snapshot, ReportPatch)]
+#[patchable(partial)]
+struct Limits {
+    max_connections: u32,
+    timeout: Option<u64>,
+    #[patchable(merge = "add")]
+    retries: u8,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Patchable, Patch, Snapshot, ReportPatch)]
+#[patchable(partial)]
+struct Config<L> {
+    name: String,
+    #[patchable]
+    limits: L,
+    #[patchable(delta)]
+    weight: i64,
+    #[patchable(skip)]
+    revision: u32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Patchable, Patch)]
+#[patchable(partial)]
+struct Counter {
+    #[patchable(delta = "checked")]
+    value: u8,
+    label: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Patchable, Patch)]
+#[patchable(partial)]
+struct Pair(u8, #[patchable(skip)] bool, char);
+
+type LimitsPatch = <Limits as Patchable>::Patch;
+type ConfigPatch<L> = <Config<L> as Patchable>::Patch;
+type CounterPatch = <Counter as Patchable>::Patch;
+type PairPatch = <Pair as Patchable>::Patch;
+
+fn config() -> Config<Limits> {
+    Config {
+        name: "api".to_owned(),
+        limits: Limits {
+            max_connections: 16,
+            timeout: Some(30),
+            retries: 1,
+        },
+        weight: 5,
+        revision: 0,
+    }
+}
+
+fn set_fields<P: PartialPatch>(patch: &P) -> Vec<String> {
+    let mut paths = Vec::new();
+    patch.for_each_set_field(|path| paths.push(path.to_string()));
+    paths
+}
+
+#[test]
+fn test_only_set_fields_are_applied() {
+    let mut value = config();
+    value.patch(ConfigPatch {
+        limits: Some(LimitsPatch {
+            timeout: Some(None),
+            ..Default::default()
+        }),
+        weight: Some(-2),
+        ..Default::default()
+    });
+
+    assert_eq!(value.name, "api");
+    assert_eq!(value.limits.max_connections, 16);
+    assert_eq!(value.limits.timeout, None);
+    assert_eq!(value.weight, 3);
+
+    let mut pair = Pair(1, true, 'a');
+    pair.patch(PairPatch {
+        0: None,
+        1: Some('z'),
+    });
+    assert_eq!(pair, Pair(1, true, 'z'));
+}
+
+#[test]
+fn test_report_leaves_unset_fields_unchanged() {
+    let mut config = config();
+    let patch = ConfigPatch {
+        name: Some("api".to_owned()),
+        limits: Some(LimitsPatch {
+            max_connections: Some(32),
+            retries: Some(2),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    let report = config.patch_report(patch);
+    assert!(!report.name);
+    assert!(report.limits.max_connections);
+    assert!(!report.limits.timeout);
+    assert!(report.limits.retries);
+    assert!(!report.weight);
+    assert_eq!(
+        (config.limits.max_connections, config.limits.retries),
+        (32, 3)
+    );
+
+    let report = config.patch_report(ConfigPatch::default());
+    assert!(!report.is_changed());
+    assert_eq!(config.limits.max_connections, 32);
+}
+
+#[test]
+fn test_default_patch_sets_nothing() {
+    let patch = ConfigPatch::<Limits>::default();
+    assert!(patch.is_empty());
+    assert!(set_fields(&patch).is_empty());
+
+    let mut value = config();
+    value.patch(patch);
+    assert_eq!(value, config());
+}
+
+#[test]
+fn test_set_fields_are_visited_by_path() {
+    let patch: ConfigPatch<Limits> = ConfigPatch {
+        name: Some("gateway".to_owned()),
+        limits: Some(LimitsPatch {
+            max_connections: Some(4),
+            retries: Some(2),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    assert_eq!(
+        set_fields(&patch),
+        ["name", "limits.max_connections", "limits.retries"]
+    );
+
+    let nested = ConfigPatch::<Limits> {
+        limits: Some(LimitsPatch::default()),
+        ..Default::default()
+    };
+    assert!(nested.is_empty());
+
+    assert_eq!(
+        set_fields(&PairPatch {
+            0: Some(1),
+            1: None
+        }),
+        ["0"]
+    );
+}
+
+#[test]
+fn test_field_path_formats_dotted_names() {
+    let limits = FieldPath::new("limits", None);
+    let timeout = FieldPath::new("timeout", Some(&limits));
+
+    assert_eq!(timeout.name(), "timeout");
+    assert_eq!(timeout.parent().map(FieldPath::name), Some("limits"));
+    assert_eq!(timeout.to_string(), "limits.timeout");
+    assert_eq!(format!("{timeout:?}"), "FieldPath(limits.timeout)");
+}
+
+#[test]
+fn test_compose_keeps_the_union_of_set_fields() {
+    let first = || ConfigPatch::<Limits> {
+        name: Some("first".to_owned()),
+        limits: Some(LimitsPatch {
+            max_connections: Some(4),
+            retries: Some(1),
+            ..Default::default()
+        }),
+        weight: Some(2),
+    };
+    let later = || ConfigPatch::<Limits> {
+        limits: Some(LimitsPatch {
+            timeout: Some(Some(10)),
+            retries: Some(2),
+            ..Default::default()
+        }),
+        weight: Some(3),
+        ..Default::default()
+    };
+
+    let mut composed = config();
+    composed.patch(first().compose(later()));
+    let mut sequential = config();
+    sequential.patch(first());
+    sequential.patch(later());
+
+    assert_eq!(composed, sequential);
+    assert_eq!(composed.name, "first");
+    assert_eq!(composed.limits.max_connections, 4);
+    assert_eq!(composed.limits.timeout, Some(10));
+    assert_eq!(composed.limits.retries, 4);
+    assert_eq!(composed.weight, 10);
+}
+
+#[test]
+fn test_snapshot_sets_every_field() {
+    let value = config();
+    let snapshot = value.snapshot();
+    assert_eq!(
+        set_fields(&snapshot),
+        [
+            "name",
+            "limits.max_connections",
+            "limits.timeout",
+            "limits.retries",
+            "weight",
+        ]
+    );
+
+    let mut target = Config::<Limits>::default();
+    target.patch(value.snapshot());
+    assert_eq!(target.limits, value.limits);
+    assert_eq!(target.name, value.name);
+}
+
+#[test]
+fn test_checked_delta_leaves_unset_fields() {
+    let mut counter = Counter {
+        value: 250,
+        label: "a".to_owned(),
+    };
+    counter
+        .try_patch(CounterPatch {
+            label: Some("b".to_owned()),
+            ..Default::default()
+        })
+        .unwrap();
+    assert_eq!(counter.value, 250);
+    assert_eq!(counter.label, "b");
+
+    let error = counter.try_patch(CounterPatch {
+        value: Some(10),
+        label: Some("c".to_owned()),
+    });
+    assert!(error.is_err());
+    assert_eq!(counter.value, 250);
+    assert_eq!(counter.label, "b");
+}
+
+#[test]
+fn test_deserialize_missing_fields_as_unset() {
+    let patch: ConfigPatch<Limits> = serde_json::from_str(
+        r#"{ "limits": { "timeout": null, "max_connections": 8 }, "weight": 1 }"#,
+    )
+    .unwrap();
+    assert_eq!(
+        set_fields(&patch),
+        ["limits.max_connections", "limits.timeout", "weight"]
+    );
+
+    let mut value = config();
+    value.patch(patch);
+    assert_eq!(value.limits.timeout, None);
+    assert_eq!(value.limits.max_connections, 8);
+    assert_eq!(value.limits.retries, 1);
+    assert_eq!(value.name, "api");
+
+    let empty: ConfigPatch<Limits> = serde_json::from_str("{}").unwrap();
+    assert!(empty.is_empty());
+}
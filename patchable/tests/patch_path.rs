@@ -0,0 +1,191 @@
+use core::error::Error;
+
+use patchable::{Patch, PatchPath, Patchable, PathError};
+use serde_json::{Value, json};
+
+#[derive(Clone, Debug, Default, PartialEq, Patchable, Patch, PatchPath)]
+struct Limits {
+    max_connections: u32,
+    timeout: Option<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch, PatchPath)]
+#[patchable(after_patch = Config::touch)]
+struct Config<L, T> {
+    name: String,
+    #[patchable]
+    limits: L,
+    tags: Vec<T>,
+    #[patchable(skip)]
+    revision: u32,
+    #[patchable(delta)]
+    weight: i64,
+}
+
+impl<L, T> Config<L, T> {
+    fn touch(&mut self) {
+        self.revision += 1;
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch, PatchPath)]
+struct Pair(u8, #[patchable(skip)] bool, char);
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch, PatchPath)]
+struct Unit;
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch, PatchPath)]
+#[patchable(transparent)]
+struct Shared<L>(#[patchable] L);
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch, PatchPath)]
+#[patchable(transparent)]
+struct Label(String);
+
+fn config() -> Config<Limits, String> {
+    Config {
+        name: "api".to_owned(),
+        limits: Limits {
+            max_connections: 16,
+            timeout: Some(30),
+        },
+        tags: vec!["edge".to_owned()],
+        revision: 0,
+        weight: 5,
+    }
+}
+
+fn get<T: PatchPath>(value: &T, path: &str) -> Value {
+    serde_json::to_value(value.get_path(path).unwrap()).unwrap()
+}
+
+fn set<T: PatchPath>(
+    value: &mut T,
+    path: &str,
+    json: &str,
+) -> Result<(), PathError<serde_json::Error>> {
+    value.set_path(path, &mut serde_json::Deserializer::from_str(json))
+}
+
+#[test]
+fn test_set_path_sets_nested_fields_from_strings() {
+    let mut value = config();
+    set(&mut value, "limits.max_connections", "128").unwrap();
+    set(&mut value, "name", r#""gateway""#).unwrap();
+
+    assert_eq!(value.limits.max_connections, 128);
+    assert_eq!(value.name, "gateway");
+    assert_eq!(value.limits.timeout, Some(30));
+}
+
+#[test]
+fn test_set_path_assigns_leaf_values() {
+    let mut value = config();
+    set(&mut value, "weight", "2").unwrap();
+    set(&mut value, "tags", r#"["a", "b"]"#).unwrap();
+    value.set_path("limits.timeout", Value::Null).unwrap();
+
+    assert_eq!(value.weight, 2);
+    assert_eq!(value.tags, ["a", "b"]);
+    assert_eq!(value.limits.timeout, None);
+}
+
+#[test]
+fn test_get_path_serializes_fields() {
+    let value = config();
+
+    assert_eq!(get(&value, "limits.max_connections"), json!(16));
+    assert_eq!(get(&value, "tags"), json!(["edge"]));
+    assert_eq!(
+        get(&value, "limits"),
+        json!({ "max_connections": 16, "timeout": 30 })
+    );
+    assert_eq!(
+        get(&value, ""),
+        json!({
+            "name": "api",
+            "limits": { "max_connections": 16, "timeout": 30 },
+            "tags": ["edge"],
+            "weight": 5,
+        })
+    );
+}
+
+#[test]
+fn test_skipped_and_unknown_paths_are_not_addressable() {
+    let mut value = config();
+
+    for path in ["revision", "age", "limits.burst", "name.first", "limits."] {
+        assert_eq!(value.get_path(path).unwrap_err(), PathError::NotFound);
+        assert!(matches!(
+            set(&mut value, path, "1"),
+            Err(PathError::NotFound)
+        ));
+    }
+    assert_eq!(value, config());
+}
+
+#[test]
+fn test_set_path_reports_invalid_values() {
+    let mut value = config();
+    let error = set(&mut value, "limits.max_connections", r#""many""#).unwrap_err();
+
+    assert!(matches!(error, PathError::Value(_)));
+    assert!(error.source().is_some());
+    assert!(error.to_string().starts_with("invalid value: "));
+    assert_eq!(value, config());
+}
+
+#[test]
+fn test_set_path_applies_patches_to_structs() {
+    let mut value = config();
+    set(
+        &mut value,
+        "limits",
+        r#"{ "max_connections": 4, "timeout": null }"#,
+    )
+    .unwrap();
+    assert_eq!(
+        value.limits,
+        Limits {
+            max_connections: 4,
+            timeout: None,
+        }
+    );
+
+    let mut limits = Limits::default();
+    set(&mut limits, "", r#"{ "max_connections": 8, "timeout": 1 }"#).unwrap();
+    assert_eq!(limits.max_connections, 8);
+}
+
+#[test]
+fn test_set_path_calls_after_patch() {
+    let mut value = config();
+    set(&mut value, "limits.max_connections", "1").unwrap();
+    set(&mut value, "name", r#""gateway""#).unwrap();
+    assert_eq!(value.revision, 2);
+
+    set(&mut value, "name", "1").unwrap_err();
+    assert_eq!(value.revision, 2);
+}
+
+#[test]
+fn test_tuple_unit_and_transparent_structs() {
+    let mut pair = Pair(1, true, 'a');
+    set(&mut pair, "2", r#""z""#).unwrap();
+    assert_eq!(pair, Pair(1, true, 'z'));
+    assert_eq!(get(&pair, "0"), json!(1));
+    assert_eq!(pair.get_path("1").unwrap_err(), PathError::NotFound);
+
+    assert_eq!(get(&Unit, ""), json!({}));
+    assert_eq!(Unit.get_path("a").unwrap_err(), PathError::NotFound);
+
+    let mut shared = Shared(Limits::default());
+    set(&mut shared, "max_connections", "3").unwrap();
+    assert_eq!(get(&shared, "max_connections"), json!(3));
+
+    let mut label = Label("old".to_owned());
+    set(&mut label, "", r#""new""#).unwrap();
+    assert_eq!(get(&label, ""), json!("new"));
+    assert_eq!(label.get_path("0").unwrap_err(), PathError::NotFound);
+}
@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+
+use patchable::{Patch, PatchWithContext, Patchable, Snapshot};
+
+/// Hands out connection handles by host name.
+struct Pool {
+    handles: HashMap<&'static str, u32>,
+}
+
+fn rebind(handle: &mut Option<u32>, pool: &Pool) {
+    *handle = pool.handles.get("db").copied();
+}
+
+fn count_rebinds(rebinds: &mut usize, _pool: &Pool) {
+    *rebinds += 1;
+}
+
+#[derive(Debug, PartialEq, Patchable, Patch, Snapshot, PatchWithContext)]
+#[patchable(context = Pool)]
+struct Connection {
+    host: String,
+    #[patchable(skip, rehydrate = rebind)]
+    handle: Option<u32>,
+    #[patchable(skip, rehydrate = count_rebinds)]
+    rebinds: usize,
+}
+
+#[derive(Debug, PartialEq, Patchable, Patch, Snapshot, PatchWithContext)]
+#[patchable(context = Pool)]
+struct Service<C> {
+    #[patchable]
+    connection: C,
+    #[patchable(merge = "add")]
+    restarts: u32,
+}
+
+#[derive(Debug, PartialEq, Patchable, Patch, Snapshot, PatchWithContext)]
+struct Plain(u8, #[patchable(skip)] bool);
+
+fn pool() -> Pool {
+    Pool {
+        handles: HashMap::from([("db", 42)]),
+    }
+}
+
+fn connection(host: &str) -> Connection {
+    Connection {
+        host: host.to_owned(),
+        handle: None,
+        rebinds: 0,
+    }
+}
+
+#[test]
+fn test_patch_with_rehydrates_skipped_fields() {
+    let pool = pool();
+    let mut target = connection("old");
+
+    target.patch_with(connection("new").snapshot(), &pool);
+    assert_eq!(
+        target,
+        Connection {
+            host: "new".to_owned(),
+            handle: Some(42),
+            rebinds: 1,
+        }
+    );
+
+    target.patch(connection("plain").snapshot());
+    assert_eq!(target.host, "plain");
+    assert_eq!(target.rebinds, 1);
+}
+
+#[test]
+fn test_patch_with_passes_context_to_nested_fields() {
+    let pool = pool();
+    let mut service = Service {
+        connection: connection("old"),
+        restarts: 1,
+    };
+    let source = Service {
+        connection: connection("new"),
+        restarts: 2,
+    };
+
+    service.patch_with(source.snapshot(), &pool);
+    assert_eq!(service.connection.host, "new");
+    assert_eq!(service.connection.handle, Some(42));
+    assert_eq!(service.restarts, 3);
+}
+
+#[test]
+fn test_patch_with_accepts_any_context_without_context_attribute() {
+    let mut plain = Plain(1, true);
+    plain.patch_with(Plain(2, false).snapshot(), &"unused");
+    plain.patch_with(Plain(3, false).snapshot(), &pool());
+    assert_eq!(plain, Plain(3, true));
+}
@@ -0,0 +1,303 @@
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use patchable::reload::{ConfigReloader, ReloadError};
+use patchable::{Patch, PatchError, Patchable, ReportPatch, ValidatePatch};
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch)]
+#[patchable(invariant = Self::check)]
+struct Config {
+    name: String,
+    max_connections: u32,
+}
+
+impl Config {
+    fn check(&self) -> Result<(), &'static str> {
+        if self.max_connections > 0 {
+            Ok(())
+        } else {
+            Err("max_connections must be positive")
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch, ReportPatch)]
+struct Limits {
+    max_connections: u32,
+    timeout: u64,
+    #[patchable(delta)]
+    reloads: u64,
+}
+
+#[derive(Debug, PartialEq)]
+struct ShrinkingPool;
+
+impl std::fmt::Display for ShrinkingPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("the pool cannot shrink")
+    }
+}
+
+impl std::error::Error for ShrinkingPool {}
+
+impl ValidatePatch for Limits {
+    type Error = ShrinkingPool;
+
+    fn validate_patch(&self, patch: &Self::Patch) -> Result<(), Self::Error> {
+        if patch.max_connections < self.max_connections {
+            return Err(ShrinkingPool);
+        }
+        Ok(())
+    }
+}
+
+fn limits(max_connections: u32, timeout: u64, reloads: u64) -> Limits {
+    Limits {
+        max_connections,
+        timeout,
+        reloads,
+    }
+}
+
+fn config(name: &str, max_connections: u32) -> Config {
+    Config {
+        name: name.to_owned(),
+        max_connections,
+    }
+}
+
+fn reloader(path: &Path, value: Config) -> ConfigReloader<Config> {
+    ConfigReloader::new(path, Arc::new(RwLock::new(value)), |contents| {
+        serde_json::from_slice(contents)
+    })
+}
+
+#[test]
+fn test_reload_applies_changed_contents() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.json");
+    fs::write(&path, r#"{ "name": "api", "max_connections": 16 }"#).unwrap();
+
+    let mut reloader = reloader(&path, config("", 1));
+    let reloads = Arc::new(Mutex::new(Vec::new()));
+    reloader.subscribe({
+        let reloads = Arc::clone(&reloads);
+        move |reload| {
+            let values = (reload.previous().clone(), reload.current().clone());
+            reloads.lock().unwrap().push(values);
+        }
+    });
+
+    assert!(matches!(reloader.poll(), Ok(true)));
+    assert_eq!(*reloader.value().read().unwrap(), config("api", 16));
+    assert!(matches!(reloader.poll(), Ok(false)));
+
+    fs::write(&path, r#"{ "name": "api", "max_connections": 128 }"#).unwrap();
+    assert!(matches!(reloader.poll(), Ok(true)));
+    assert_eq!(*reloader.value().read().unwrap(), config("api", 128));
+
+    assert_eq!(
+        *reloads.lock().unwrap(),
+        [
+            (config("", 1), config("api", 16)),
+            (config("api", 16), config("api", 128)),
+        ]
+    );
+}
+
+#[test]
+fn test_rewriting_the_same_contents_is_not_a_reload() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.json");
+    let contents = r#"{ "name": "api", "max_connections": 16 }"#;
+    fs::write(&path, contents).unwrap();
+
+    let mut reloader = reloader(&path, config("", 1));
+    let calls = Arc::new(Mutex::new(0));
+    reloader.subscribe({
+        let calls = Arc::clone(&calls);
+        move |_| *calls.lock().unwrap() += 1
+    });
+    assert!(matches!(reloader.poll(), Ok(true)));
+
+    // A new modification time makes the file be read again, but its hash is unchanged.
+    fs::write(&path, contents).unwrap();
+    let file = fs::File::options().write(true).open(&path).unwrap();
+    file.set_modified(SystemTime::now() + Duration::from_secs(60))
+        .unwrap();
+    drop(file);
+    assert!(matches!(reloader.poll(), Ok(false)));
+    assert_eq!(*calls.lock().unwrap(), 1);
+}
+
+#[test]
+fn test_rejected_patch_keeps_the_old_value() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.json");
+    fs::write(&path, r#"{ "name": "api", "max_connections": 16 }"#).unwrap();
+
+    let mut reloader = reloader(&path, config("", 1));
+    let calls = Arc::new(Mutex::new(0));
+    reloader.subscribe({
+        let calls = Arc::clone(&calls);
+        move |_| *calls.lock().unwrap() += 1
+    });
+    reloader.poll().unwrap();
+
+    fs::write(&path, r#"{ "name": "web", "max_connections": 0 }"#).unwrap();
+    let Err(error) = reloader.poll() else {
+        unreachable!()
+    };
+    assert!(matches!(
+        error,
+        ReloadError::Patch(PatchError::Invariant {
+            message: "max_connections must be positive"
+        })
+    ));
+    assert_eq!(
+        error.to_string(),
+        "failed to apply the patch: the patched value is invalid: \
+         max_connections must be positive"
+    );
+    assert_eq!(*reloader.value().read().unwrap(), config("api", 16));
+    assert_eq!(*calls.lock().unwrap(), 1);
+
+    // The error is kept until the file changes and reloads successfully.
+    assert!(matches!(reloader.poll(), Ok(false)));
+    assert!(reloader.last_error().is_some());
+
+    fs::write(&path, r#"{ "name": "web", "max_connections": 80 }"#).unwrap();
+    assert!(matches!(reloader.poll(), Ok(true)));
+    assert!(reloader.last_error().is_none());
+    assert_eq!(*reloader.value().read().unwrap(), config("web", 80));
+    assert_eq!(*calls.lock().unwrap(), 2);
+}
+
+#[test]
+fn test_unreadable_or_malformed_file_is_an_error() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.json");
+
+    let mut reloader = reloader(&path, config("api", 16));
+    assert!(matches!(reloader.poll(), Err(ReloadError::Io(_))));
+
+    fs::write(&path, r#"{ "name": "api", "#).unwrap();
+    assert!(matches!(reloader.poll(), Err(ReloadError::Deserialize(_))));
+    assert!(matches!(
+        reloader.last_error(),
+        Some(ReloadError::Deserialize(_))
+    ));
+    assert_eq!(*reloader.value().read().unwrap(), config("api", 16));
+}
+
+#[test]
+fn test_reload_reports_changed_fields() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("limits.json");
+    fs::write(
+        &path,
+        r#"{ "max_connections": 16, "timeout": 60, "reloads": 0 }"#,
+    )
+    .unwrap();
+
+    let value = Arc::new(RwLock::new(limits(16, 30, 5)));
+    let mut reloader =
+        ConfigReloader::new(&path, value, |contents| serde_json::from_slice(contents));
+    let calls = Arc::new(Mutex::new(0));
+    reloader.subscribe({
+        let calls = Arc::clone(&calls);
+        move |_| *calls.lock().unwrap() += 1
+    });
+
+    // The subscribers added before `reporting` are kept.
+    let mut reloader = reloader.reporting();
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    reloader.subscribe({
+        let reports = Arc::clone(&reports);
+        move |reload| {
+            let report = reload.report();
+            reports
+                .lock()
+                .unwrap()
+                .push((report.max_connections, report.timeout, report.reloads));
+        }
+    });
+
+    // The report is the one of the applied patch: a zero delta leaves `reloads` unchanged.
+    reloader.poll().unwrap();
+    fs::write(
+        &path,
+        r#"{ "max_connections": 16, "timeout": 60, "reloads": 1 }"#,
+    )
+    .unwrap();
+    reloader.poll().unwrap();
+    assert_eq!(
+        *reports.lock().unwrap(),
+        [(false, true, false), (false, false, true)]
+    );
+    assert_eq!(*reloader.value().read().unwrap(), limits(16, 60, 6));
+    assert_eq!(*calls.lock().unwrap(), 2);
+}
+
+#[test]
+fn test_checked_reload_validates_the_patch() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("limits.json");
+    fs::write(
+        &path,
+        r#"{ "max_connections": 8, "timeout": 60, "reloads": 1 }"#,
+    )
+    .unwrap();
+
+    let value = Arc::new(RwLock::new(limits(16, 30, 0)));
+    let mut reloader =
+        ConfigReloader::checked(&path, value, |contents| serde_json::from_slice(contents))
+            .reporting();
+    let Err(error) = reloader.poll() else {
+        unreachable!()
+    };
+    assert!(matches!(error, ReloadError::Patch(ShrinkingPool)));
+    assert_eq!(
+        error.to_string(),
+        "failed to apply the patch: the pool cannot shrink"
+    );
+    assert_eq!(*reloader.value().read().unwrap(), limits(16, 30, 0));
+
+    fs::write(
+        &path,
+        r#"{ "max_connections": 32, "timeout": 60, "reloads": 1 }"#,
+    )
+    .unwrap();
+    assert!(matches!(reloader.poll(), Ok(true)));
+    assert_eq!(*reloader.value().read().unwrap(), limits(32, 60, 1));
+}
+
+#[test]
+fn test_watch_polls_from_a_thread() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.json");
+    fs::write(&path, r#"{ "name": "api", "max_connections": 16 }"#).unwrap();
+
+    let value = Arc::new(RwLock::new(config("", 1)));
+    let watcher = ConfigReloader::new(&path, Arc::clone(&value), |contents| {
+        serde_json::from_slice(contents)
+    })
+    .watch(Duration::from_millis(5));
+
+    let wait_for = |expected: Config| {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while *value.read().unwrap() != expected {
+            assert!(Instant::now() < deadline, "the value was not reloaded");
+            thread::sleep(Duration::from_millis(5));
+        }
+    };
+    wait_for(config("api", 16));
+    fs::write(&path, r#"{ "name": "api", "max_connections": 128 }"#).unwrap();
+    wait_for(config("api", 128));
+
+    let reloader = watcher.stop();
+    assert!(reloader.last_error().is_none());
+}
@@ -0,0 +1,83 @@
+use patchable::{Patch, Patchable};
+
+/// Stand-in for a dependency whose types cannot implement `patchable` traits.
+mod other_crate {
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Config {
+        pub name: String,
+        pub retries: u32,
+        pub handle: usize,
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Pair<T>(pub T, pub u8);
+}
+
+#[derive(Patchable, Patch)]
+#[patchable(remote = "other_crate::Config")]
+struct ConfigDef {
+    name: String,
+    retries: u32,
+    #[patchable(skip)]
+    handle: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, Patchable, Patch)]
+struct Counter {
+    value: i32,
+}
+
+#[derive(Patchable, Patch)]
+#[patchable(remote = "other_crate::Pair")]
+struct PairDef<T>(#[patchable] T, u8);
+
+#[test]
+fn test_remote_patch_updates_fields_and_keeps_skipped() {
+    let mut config = other_crate::Config {
+        name: "primary".to_string(),
+        retries: 1,
+        handle: 7,
+    };
+    let patch: <ConfigDef as Patchable>::Patch =
+        serde_json::from_str(r#"{"name": "fallback", "retries": 5}"#).unwrap();
+
+    ConfigDef::patch(&mut config, patch);
+    assert_eq!(
+        config,
+        other_crate::Config {
+            name: "fallback".to_string(),
+            retries: 5,
+            handle: 7,
+        }
+    );
+}
+
+#[test]
+fn test_remote_patch_with_generic_nested_field() {
+    let mut pair = other_crate::Pair(Counter { value: 1 }, 2);
+    let patch: <PairDef<Counter> as Patchable>::Patch =
+        serde_json::from_str(r#"[{"value": 10}, 20]"#).unwrap();
+
+    PairDef::patch(&mut pair, patch);
+    assert_eq!(pair, other_crate::Pair(Counter { value: 10 }, 20));
+}
+
+#[cfg(feature = "impl_from")]
+#[test]
+fn test_remote_value_converts_into_patch() {
+    let source = other_crate::Config {
+        name: "replica".to_string(),
+        retries: 3,
+        handle: 1,
+    };
+    let mut target = other_crate::Config {
+        name: String::new(),
+        retries: 0,
+        handle: 2,
+    };
+
+    ConfigDef::patch(&mut target, source.clone().into());
+    assert_eq!(target.name, source.name);
+    assert_eq!(target.retries, source.retries);
+    assert_eq!(target.handle, 2);
+}
@@ -0,0 +1,104 @@
+use patchable::{ChangeSet, Patch, Patchable, ReportPatch, Snapshot};
+
+/// A type without `PartialEq`, so its changes cannot be detected.
+#[derive(Clone, Debug, serde::Deserialize)]
+struct Opaque(u8);
+
+#[derive(Clone, Debug, Patchable, Patch, Snapshot, ReportPatch)]
+struct Inner {
+    value: i32,
+    #[patchable(skip)]
+    hits: u32,
+}
+
+#[derive(Clone, Debug, Patchable, Patch, Snapshot, ReportPatch)]
+struct Outer<T, L> {
+    #[patchable]
+    inner: T,
+    label: L,
+    opaque: Opaque,
+    #[patchable(merge = "max")]
+    peak: u32,
+    #[patchable(delta)]
+    counter: i64,
+}
+
+#[derive(Clone, Debug, Patchable, Patch, Snapshot, ReportPatch)]
+struct Pair(u8, #[patchable(skip)] bool, String);
+
+#[derive(Clone, Debug, Patchable, Patch, Snapshot, ReportPatch)]
+struct Unit;
+
+#[derive(Clone, Debug, Patchable, Patch, Snapshot, ReportPatch)]
+#[patchable(transparent)]
+struct Wrapper<T>(#[patchable] T);
+
+fn outer() -> Outer<Inner, &'static str> {
+    Outer {
+        inner: Inner { value: 1, hits: 0 },
+        label: "a",
+        opaque: Opaque(0),
+        peak: 10,
+        counter: 5,
+    }
+}
+
+#[test]
+fn test_report_flags_changed_fields() {
+    let mut target = outer();
+    let mut source = outer();
+    source.inner.value = 2;
+    source.peak = 3;
+    source.counter = 7;
+
+    let report = target.patch_report(source.snapshot());
+    assert!(report.is_changed());
+    assert!(report.inner.value);
+    assert!(!report.label);
+    // `Opaque` has no `PartialEq`, so it is assumed to change.
+    assert!(report.opaque);
+    // `max` keeps the current peak.
+    assert!(!report.peak);
+    assert!(report.counter);
+    assert_eq!(target.counter, 12);
+}
+
+#[test]
+fn test_report_of_identical_patch_is_unchanged_except_opaque_fields() {
+    let mut target = Pair(1, true, "x".to_owned());
+    let report = target.patch_report(target.snapshot());
+    assert!(!report.is_changed());
+    assert_eq!(report.into_changes(), PairChanges::default());
+
+    let report = target.patch_report(Pair(1, false, "y".to_owned()).snapshot());
+    assert_eq!(*report.changes(), PairChanges(false, true));
+    assert!(ChangeSet::is_changed(report.changes()));
+    assert!(target.1);
+    assert_eq!(format!("{report:?}"), "PairChanges(false, true)");
+
+    let mut target = outer();
+    let mut source = outer();
+    source.counter = 0;
+    let report = target.patch_report(source.snapshot());
+    // A zero delta leaves the counter unchanged.
+    assert_eq!(target.counter, 5);
+    let expected = OuterChanges::<Inner> {
+        opaque: true,
+        ..Default::default()
+    };
+    assert_eq!(report.into_changes(), expected);
+    assert_eq!(target.opaque.0, 0);
+}
+
+#[test]
+fn test_report_for_unit_and_transparent_structs() {
+    let report = Unit.patch_report(Unit.snapshot());
+    assert!(!report.is_changed());
+    assert_eq!(format!("{report:?}"), "UnitChanges");
+
+    let mut wrapper = Wrapper(Inner { value: 0, hits: 3 });
+    let report = wrapper.patch_report(Inner { value: 4, hits: 0 }.snapshot());
+    assert!(report.0.value);
+    assert!(report.is_changed());
+    assert_eq!(wrapper.0.hits, 3);
+}
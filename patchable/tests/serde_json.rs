@@ -49,6 +49,7 @@ fn test_tuple_struct_patch() {
 }
 
 #[test]
+#[allow(clippy::type_complexity)]
 fn test_tuple_struct_skip_keeps_original_field_index() {
     let mut s = TupleStructWithSkippedMiddle(1, identity, 2);
     let patch: <TupleStructWithSkippedMiddle<fn(i32) -> i32> as Patchable>::Patch =
@@ -0,0 +1,240 @@
+use patchable::{Patch, Patchable, Snapshot};
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch, Snapshot)]
+struct Inner {
+    value: i32,
+    #[patchable(skip)]
+    hits: u32,
+}
+
+#[derive(Debug, PartialEq, Patchable, Patch, Snapshot)]
+struct Outer<T, U> {
+    #[patchable]
+    inner: T,
+    extra: U,
+    label: String,
+    #[patchable(skip)]
+    handle: NotClone,
+}
+
+#[derive(Debug, PartialEq)]
+struct NotClone(u8);
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch, Snapshot)]
+struct Tuple(u8, #[patchable(skip)] u8, String);
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch, Snapshot)]
+struct Unit;
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch, Snapshot)]
+struct Counters {
+    #[patchable(delta)]
+    requests: u64,
+    #[patchable(merge = "max")]
+    peak: u32,
+    #[patchable(with = "doubled")]
+    scaled: u32,
+}
+
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, Default, PartialEq, Patchable, Patch, Snapshot)]
+struct Merged {
+    #[patchable(delta)]
+    delta: u16,
+    #[patchable(merge = "add")]
+    add: i32,
+    #[patchable(merge = "or")]
+    or: u8,
+    #[patchable(merge = "extend")]
+    extend: Vec<u8>,
+    #[patchable(merge = "append")]
+    append: String,
+    #[patchable(merge = "max")]
+    max: i32,
+    #[patchable(merge = "min")]
+    min: i32,
+    #[patchable(merge = "and")]
+    and: u8,
+}
+
+mod doubled {
+    pub type Patch = u64;
+
+    pub fn patch(field: &mut u32, patch: Patch) {
+        *field = (patch / 2) as u32;
+    }
+
+    pub fn from(field: u32) -> Patch {
+        u64::from(field) * 2
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct NamePatch {
+    name: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch, Snapshot)]
+#[patchable(patch = NamePatch)]
+struct Named {
+    name: String,
+    #[patchable(skip)]
+    cache: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch, Snapshot)]
+#[patchable(transparent)]
+struct Wrapper<T>(#[patchable] T);
+
+mod foreign {
+    pub struct Point {
+        pub x: i32,
+        pub y: i32,
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Patchable, Patch, Snapshot)]
+#[patchable(remote = "foreign::Point")]
+struct PointDef {
+    x: i32,
+    #[patchable(skip)]
+    y: i32,
+}
+
+#[test]
+fn test_snapshot_restores_checkpoint() {
+    let mut outer = Outer {
+        inner: Inner { value: 1, hits: 10 },
+        extra: vec![1_u8],
+        label: "start".to_owned(),
+        handle: NotClone(3),
+    };
+    let checkpoint = outer.snapshot();
+
+    outer.inner = Inner { value: 2, hits: 20 };
+    outer.extra.push(2);
+    outer.label.push('!');
+
+    outer.patch(checkpoint);
+    assert_eq!(
+        outer,
+        Outer {
+            inner: Inner { value: 1, hits: 20 },
+            extra: vec![1],
+            label: "start".to_owned(),
+            handle: NotClone(3),
+        }
+    );
+}
+
+#[test]
+fn test_snapshot_of_tuple_and_unit_structs() {
+    let source = Tuple(1, 2, "a".to_owned());
+    let mut target = Tuple(0, 9, String::new());
+    target.patch(source.snapshot());
+    assert_eq!(target, Tuple(1, 9, "a".to_owned()));
+
+    let mut unit = Unit;
+    unit.patch(Unit.snapshot());
+    assert_eq!(unit, Unit);
+}
+
+#[test]
+fn test_snapshot_of_special_fields() {
+    let source = Counters {
+        requests: 5,
+        peak: 7,
+        scaled: 21,
+    };
+    let mut target = Counters {
+        requests: 0,
+        peak: 0,
+        scaled: 0,
+    };
+
+    target.patch(source.snapshot());
+    assert_eq!(target, source);
+}
+
+#[test]
+fn test_snapshot_of_special_fields_applies_to_the_target_value() {
+    let source = Counters {
+        requests: 5,
+        peak: 7,
+        scaled: 21,
+    };
+    let mut target = Counters {
+        requests: 2,
+        peak: 9,
+        scaled: 4,
+    };
+
+    // The delta is added to the current value and the `max` strategy keeps the larger peak.
+    target.patch(source.snapshot());
+    assert_eq!(
+        target,
+        Counters {
+            requests: 7,
+            peak: 9,
+            scaled: 21,
+        }
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_snapshot_of_merge_fields_through_default() {
+    let source = Merged {
+        delta: 7,
+        add: -5,
+        or: 0b1010,
+        extend: vec![1, 2],
+        append: "ab".to_owned(),
+        max: 3,
+        min: -3,
+        and: 0,
+    };
+    let mut target = Merged::default();
+    target.patch(source.snapshot());
+    assert_eq!(target, source);
+
+    // Merging into the default keeps the default when the value lies on its other side.
+    let source = Merged {
+        max: -3,
+        min: 3,
+        and: 0b1010,
+        ..source
+    };
+    let mut target = Merged::default();
+    target.patch(source.snapshot());
+    assert_eq!((target.max, target.min, target.and), (0, 0, 0));
+    assert_eq!(target.append, "ab");
+}
+
+#[test]
+fn test_snapshot_of_hand_written_and_transparent_patches() {
+    let named = Named {
+        name: "n".to_owned(),
+        cache: 4,
+    };
+    assert_eq!(
+        named.snapshot(),
+        NamePatch {
+            name: "n".to_owned()
+        }
+    );
+
+    let mut wrapper = Wrapper(Inner { value: 0, hits: 1 });
+    wrapper.patch(Wrapper(Inner { value: 8, hits: 2 }).snapshot());
+    assert_eq!(wrapper, Wrapper(Inner { value: 8, hits: 1 }));
+}
+
+#[test]
+fn test_snapshot_of_remote_type() {
+    let source = foreign::Point { x: 3, y: 4 };
+    let mut target = foreign::Point { x: 0, y: 0 };
+
+    PointDef::patch(&mut target, PointDef::snapshot(&source));
+    assert_eq!((target.x, target.y), (3, 0));
+}
@@ -0,0 +1,53 @@
+use patchable::{Patch, Patchable};
+
+#[derive(Clone, Copy, Debug, PartialEq, Patchable, Patch)]
+#[patchable(transparent)]
+struct Meters(f64);
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch)]
+#[patchable(transparent)]
+struct Wrapped<T>(#[patchable] T);
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch)]
+#[patchable(transparent)]
+struct Labeled<T> {
+    value: T,
+    #[patchable(skip)]
+    label: &'static str,
+}
+
+fn assert_patch_type<T: Patchable<Patch = P>, P>() {}
+
+#[test]
+fn test_transparent_patch_type_is_the_inner_type() {
+    assert_patch_type::<Meters, f64>();
+    assert_patch_type::<Labeled<String>, String>();
+}
+
+#[test]
+fn test_transparent_patch_type_is_the_inner_patch_type() {
+    assert_patch_type::<Wrapped<Meters>, f64>();
+    assert_patch_type::<Wrapped<Wrapped<Meters>>, f64>();
+}
+
+#[test]
+fn test_transparent_patch_replaces_inner_value() {
+    let mut distance = Meters(1.5);
+    distance.patch(4.0);
+    assert_eq!(distance, Meters(4.0));
+
+    let mut labeled = Labeled {
+        value: 1u8,
+        label: "retries",
+    };
+    labeled.patch(3);
+    assert_eq!(labeled.value, 3);
+    assert_eq!(labeled.label, "retries");
+}
+
+#[test]
+fn test_transparent_patch_delegates_to_inner_patch() {
+    let mut wrapped = Wrapped(Wrapped(Meters(0.0)));
+    wrapped.patch(2.5);
+    assert_eq!(wrapped, Wrapped(Wrapped(Meters(2.5))));
+}
@@ -0,0 +1,16 @@
+use patchable::{Patch, Patchable};
+
+struct SettingsPatch {
+    retries: u32,
+    label: String,
+}
+
+#[derive(Patchable, Patch)]
+#[patchable(patch = SettingsPatch)]
+struct Settings {
+    retries: u32,
+    #[patchable(skip)]
+    label: String,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use patchable::{Patch, Patchable};
+
+struct SettingsPatch {
+    retries: u64,
+}
+
+#[derive(Patchable, Patch)]
+#[patchable(patch = SettingsPatch)]
+struct Settings {
+    retries: u32,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use patchable::{Patch, Patchable};
+
+struct SettingsPatch {
+    retries: u32,
+}
+
+#[derive(Patchable, Patch)]
+#[patchable(patch = SettingsPatch)]
+struct Settings {
+    retries: u32,
+    label: String,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use patchable::Patchable;
+
+#[derive(Patchable)]
+struct DefaultWithoutSkip {
+    #[patchable(default = 1)]
+    value: u32,
+}
+
+fn main() {}
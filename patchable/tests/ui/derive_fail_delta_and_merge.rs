@@ -0,0 +1,9 @@
+use patchable::Patchable;
+
+#[derive(Patchable)]
+struct DeltaAndMerge {
+    #[patchable(merge = "add", delta)]
+    value: u32,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use patchable::Patchable;
+
+#[derive(Patchable)]
+struct UnknownMode {
+    #[patchable(delta = "saturating")]
+    value: u32,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use patchable::{MaskedPatch, Patch, Patchable};
+
+#[derive(Patchable, Patch, MaskedPatch)]
+#[patchable(partial)]
+struct MaskedPatchPartial {
+    value: u32,
+}
+
+fn main() {}
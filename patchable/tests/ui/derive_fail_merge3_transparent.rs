@@ -0,0 +1,7 @@
+use patchable::{Merge3, Patch, Patchable};
+
+#[derive(Patchable, Patch, Merge3)]
+#[patchable(transparent)]
+struct Meters(u32);
+
+fn main() {}
@@ -0,0 +1,9 @@
+use patchable::Patchable;
+
+#[derive(Patchable)]
+struct MergeAndSkip {
+    #[patchable(skip, merge = "add")]
+    value: u32,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use patchable::Patchable;
+
+#[derive(Patchable)]
+struct UnknownStrategy {
+    #[patchable(merge = "sum")]
+    value: u32,
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use patchable::{Patch, Patchable};
+
+#[derive(Patchable, Patch)]
+#[patchable(partial, transparent)]
+struct PartialTransparent(u32);
+
+fn main() {}
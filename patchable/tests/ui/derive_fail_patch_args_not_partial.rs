@@ -0,0 +1,8 @@
+use patchable::{Patch, PatchArgs, Patchable};
+
+#[derive(Patchable, Patch, PatchArgs)]
+struct PatchArgsNotPartial {
+    value: u32,
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use patchable::{Patch, PatchArgs, Patchable};
+
+#[derive(Patchable, Patch, PatchArgs)]
+#[patchable(partial)]
+struct PatchArgsTupleStruct(u32, String);
+
+fn main() {}
@@ -0,0 +1,9 @@
+use patchable::Patchable;
+
+#[derive(Patchable)]
+#[patchable(unknown)]
+struct InvalidContainerParameter {
+    value: i32,
+}
+
+fn main() {}
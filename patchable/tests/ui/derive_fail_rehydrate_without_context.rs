@@ -0,0 +1,14 @@
+use patchable::{Patch, PatchWithContext, Patchable};
+
+fn reset(value: &mut u32, _ctx: &()) {
+    *value = 0;
+}
+
+#[derive(Patchable, Patch, PatchWithContext)]
+struct RehydrateWithoutContext {
+    name: String,
+    #[patchable(skip, rehydrate = reset)]
+    value: u32,
+}
+
+fn main() {}
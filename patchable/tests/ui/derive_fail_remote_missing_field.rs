@@ -0,0 +1,16 @@
+use patchable::{Patch, Patchable};
+
+mod other_crate {
+    pub struct Config {
+        pub name: String,
+        pub retries: u32,
+    }
+}
+
+#[derive(Patchable, Patch)]
+#[patchable(remote = "other_crate::Config")]
+struct ConfigDef {
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use patchable::{Patch, Patchable, ReportPatch};
+
+#[derive(Patchable, Patch, ReportPatch)]
+struct ReportPatchChecked {
+    #[patchable(delta = "checked")]
+    value: u32,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use patchable::{Patch, Patchable};
+
+#[derive(Clone, Patchable, Patch)]
+#[patchable(rollback)]
+struct RollbackWithoutInvariant {
+    value: u32,
+}
+
+fn main() {}
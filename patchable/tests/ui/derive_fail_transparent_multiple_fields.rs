@@ -0,0 +1,10 @@
+use patchable::Patchable;
+
+#[derive(Patchable)]
+#[patchable(transparent)]
+struct Point {
+    x: f64,
+    y: f64,
+}
+
+fn main() {}
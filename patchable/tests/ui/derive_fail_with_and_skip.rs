@@ -0,0 +1,13 @@
+use patchable::Patchable;
+
+mod custom {
+    pub type Patch = u32;
+}
+
+#[derive(Patchable)]
+struct WithAndSkip {
+    #[patchable(skip, with = "custom")]
+    value: u32,
+}
+
+fn main() {}
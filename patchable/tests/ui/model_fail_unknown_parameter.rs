@@ -0,0 +1,8 @@
+use patchable::patchable_model;
+
+#[patchable_model(unknown = "value")]
+struct InvalidModelParameter {
+    value: i32,
+}
+
+fn main() {}
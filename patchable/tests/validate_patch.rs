@@ -0,0 +1,77 @@
+use core::fmt;
+
+use patchable::{Patch, Patchable, Snapshot, ValidatePatch};
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch, Snapshot)]
+struct Limits {
+    min: u32,
+    max: u32,
+    #[patchable(skip)]
+    locked: bool,
+}
+
+#[derive(Debug, PartialEq)]
+enum LimitsError {
+    Locked,
+    Inverted,
+}
+
+impl fmt::Display for LimitsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Locked => f.write_str("the limits are locked"),
+            Self::Inverted => f.write_str("`min` exceeds `max`"),
+        }
+    }
+}
+
+impl std::error::Error for LimitsError {}
+
+impl ValidatePatch for Limits {
+    type Error = LimitsError;
+
+    fn validate_patch(&self, patch: &Self::Patch) -> Result<(), Self::Error> {
+        if self.locked {
+            return Err(LimitsError::Locked);
+        }
+        if patch.min > patch.max {
+            return Err(LimitsError::Inverted);
+        }
+        Ok(())
+    }
+}
+
+fn limits(min: u32, max: u32) -> Limits {
+    Limits {
+        min,
+        max,
+        locked: false,
+    }
+}
+
+#[test]
+fn test_checked_patch_applies_valid_patches() {
+    let mut target = limits(0, 1);
+    target.checked_patch(limits(2, 8).snapshot()).unwrap();
+    assert_eq!(target, limits(2, 8));
+}
+
+#[test]
+fn test_checked_patch_rejects_invalid_patches() {
+    let mut target = limits(0, 1);
+    assert_eq!(
+        target.checked_patch(limits(9, 8).snapshot()),
+        Err(LimitsError::Inverted)
+    );
+    assert_eq!(target, limits(0, 1));
+
+    target.locked = true;
+    assert_eq!(
+        target.checked_patch(limits(2, 8).snapshot()),
+        Err(LimitsError::Locked)
+    );
+
+    // The derived `patch` still bypasses validation.
+    target.patch(limits(9, 8).snapshot());
+    assert_eq!((target.min, target.max), (9, 8));
+}
@@ -0,0 +1,161 @@
+use patchable::{
+    Patch, PatchError, Patchable, TryPatch, Versioned, VersionedError, VersionedPatch,
+};
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+struct Account {
+    owner: String,
+    balance: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch)]
+#[patchable(invariant = Self::check)]
+struct Limits {
+    max_connections: u32,
+}
+
+impl Limits {
+    fn check(&self) -> Result<(), &'static str> {
+        if self.max_connections > 0 {
+            Ok(())
+        } else {
+            Err("max_connections must be positive")
+        }
+    }
+}
+
+type AccountPatch = <Account as Patchable>::Patch;
+type LimitsPatch = <Limits as Patchable>::Patch;
+
+fn account(owner: &str, balance: i64) -> Account {
+    Account {
+        owner: owner.to_owned(),
+        balance,
+    }
+}
+
+fn account_patch(owner: &str, balance: i64) -> AccountPatch {
+    AccountPatch {
+        owner: owner.to_owned(),
+        balance,
+    }
+}
+
+#[test]
+fn test_patch_at_current_version_increases_it() {
+    let mut state = Versioned::new(account("alice", 10));
+    assert_eq!(state.version(), 0);
+
+    state
+        .try_patch(state.next_patch(account_patch("alice", 20)))
+        .unwrap();
+    state
+        .try_patch(state.next_patch(account_patch("bob", 20)))
+        .unwrap();
+    assert_eq!(state.version(), 2);
+    assert_eq!(*state.get(), account("bob", 20));
+    assert_eq!(state.into_inner(), account("bob", 20));
+}
+
+#[test]
+fn test_stale_patch_is_a_conflict() {
+    let mut state = Versioned::with_version(account("alice", 10), 7);
+    let first = state.next_patch(account_patch("alice", 20));
+    let second = state.next_patch(account_patch("alice", 30));
+
+    state.try_patch(first).unwrap();
+    let error = state.try_patch(second).unwrap_err();
+    assert_eq!(
+        error,
+        VersionedError::Conflict {
+            current: 8,
+            expected: 7
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "the patch expects version 7, but the current version is 8"
+    );
+    assert_eq!((state.version(), state.balance), (8, 20));
+
+    // A patch from the future is a conflict as well.
+    let error = state
+        .try_patch(VersionedPatch::new(9, account_patch("alice", 40)))
+        .unwrap_err();
+    assert_eq!(
+        error,
+        VersionedError::Conflict {
+            current: 8,
+            expected: 9
+        }
+    );
+}
+
+#[test]
+fn test_rejected_inner_patch_keeps_the_version() {
+    let mut state = Versioned::new(Limits { max_connections: 4 });
+
+    let error = state
+        .try_patch(state.next_patch(LimitsPatch { max_connections: 0 }))
+        .unwrap_err();
+    assert_eq!(
+        error,
+        VersionedError::Patch(PatchError::Invariant {
+            message: "max_connections must be positive"
+        })
+    );
+    assert_eq!(error.to_string(), "the wrapped value rejected the patch");
+    assert_eq!(
+        std::error::Error::source(&error).map(ToString::to_string),
+        Some("the patched value is invalid: max_connections must be positive".to_owned())
+    );
+    assert_eq!(state.version(), 0);
+
+    state
+        .try_patch(state.next_patch(LimitsPatch { max_connections: 8 }))
+        .unwrap();
+    assert_eq!((state.version(), state.max_connections), (1, 8));
+}
+
+#[test]
+fn test_last_version_rejects_further_patches() {
+    let mut state = Versioned::with_version(account("alice", 10), u64::MAX);
+
+    let error = state
+        .try_patch(state.next_patch(account_patch("bob", 20)))
+        .unwrap_err();
+    assert_eq!(error, VersionedError::VersionOverflow);
+    assert_eq!(
+        error.to_string(),
+        "the version cannot be increased past u64::MAX"
+    );
+    assert_eq!(
+        (state.version(), state.get()),
+        (u64::MAX, &account("alice", 10))
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_versions_travel_with_serde() {
+    let mut state = Versioned::new(account("alice", 10));
+    state
+        .try_patch(state.next_patch(account_patch("alice", 20)))
+        .unwrap();
+
+    let json = serde_json::to_string(&state).unwrap();
+    assert_eq!(
+        json,
+        r#"{"version":1,"value":{"owner":"alice","balance":20}}"#
+    );
+
+    let patch: VersionedPatch<AccountPatch> =
+        serde_json::from_str(r#"{"expected_version":1,"patch":{"owner":"bob","balance":5}}"#)
+            .unwrap();
+    state.try_patch(patch).unwrap();
+    assert_eq!((state.version(), state.get()), (2, &account("bob", 5)));
+
+    let restored: Versioned<u32> = serde_json::from_str(r#"{"version":3,"value":9}"#).unwrap();
+    assert_eq!((restored.version(), *restored), (3, 9));
+}
@@ -0,0 +1,116 @@
+use std::rc::Rc;
+
+use patchable::{Patch, Patchable, patchable_model};
+
+/// Clamps incoming percentages to `0..=100`.
+mod clamped_percent {
+    pub type Patch = i64;
+
+    pub fn patch(field: &mut u8, patch: Patch) {
+        *field = patch.clamp(0, 100) as u8;
+    }
+
+    #[allow(dead_code)]
+    pub fn from(field: u8) -> Patch {
+        field.into()
+    }
+}
+
+/// Reuses the current allocation when the incoming string is unchanged.
+mod interned {
+    use std::rc::Rc;
+
+    pub type Patch = String;
+
+    pub fn patch(field: &mut Rc<str>, patch: Patch) {
+        if *field.as_ref() != *patch {
+            *field = Rc::from(patch);
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn from(field: Rc<str>) -> Patch {
+        field.as_ref().to_owned()
+    }
+}
+
+/// Keeps a cached length in sync with the patched items.
+mod with_len {
+    pub type Patch = Vec<u32>;
+
+    pub fn patch(field: &mut (Vec<u32>, usize), patch: Patch) {
+        field.1 = patch.len();
+        field.0 = patch;
+    }
+
+    #[allow(dead_code)]
+    pub fn from(field: (Vec<u32>, usize)) -> Patch {
+        field.0
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Patchable, Patch)]
+struct Gauge {
+    #[patchable(with = "clamped_percent")]
+    level: u8,
+    #[patchable(with = "interned")]
+    name: Rc<str>,
+    samples: u32,
+}
+
+#[patchable_model]
+#[derive(Clone, Debug, PartialEq)]
+struct Sampled(
+    #[patchable(with = "with_len")] (Vec<u32>, usize),
+    #[patchable(skip)] u8,
+);
+
+#[test]
+fn test_with_module_applies_custom_logic() {
+    let mut gauge = Gauge {
+        level: 10,
+        name: Rc::from("disk"),
+        samples: 1,
+    };
+    let name = Rc::clone(&gauge.name);
+    let patch: <Gauge as Patchable>::Patch =
+        serde_json::from_str(r#"{"level": 250, "name": "disk", "samples": 2}"#).unwrap();
+
+    gauge.patch(patch);
+    assert_eq!(gauge.level, 100);
+    assert!(Rc::ptr_eq(&gauge.name, &name));
+    assert_eq!(gauge.samples, 2);
+
+    let patch: <Gauge as Patchable>::Patch =
+        serde_json::from_str(r#"{"level": -5, "name": "memory", "samples": 3}"#).unwrap();
+    gauge.patch(patch);
+    assert_eq!(gauge.level, 0);
+    assert_eq!(&*gauge.name, "memory");
+}
+
+#[test]
+fn test_with_module_in_tuple_struct() {
+    let mut sampled = Sampled((vec![], 0), 7);
+    let patch: <Sampled as Patchable>::Patch = serde_json::from_str("[4, 5, 6]").unwrap();
+
+    sampled.patch(patch);
+    assert_eq!(sampled, Sampled((vec![4, 5, 6], 3), 7));
+}
+
+#[cfg(feature = "impl_from")]
+#[test]
+fn test_with_module_from_builds_patch() {
+    let source = Gauge {
+        level: 42,
+        name: Rc::from("cpu"),
+        samples: 9,
+    };
+    let mut target = Gauge {
+        level: 0,
+        name: Rc::from(""),
+        samples: 0,
+    };
+
+    target.patch(source.clone().into());
+    assert_eq!(target, source);
+}